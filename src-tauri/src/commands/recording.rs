@@ -8,6 +8,8 @@ pub struct StartRecordingRequest {
     pub headless: Option<bool>,
     pub viewport_width: Option<i32>,
     pub viewport_height: Option<i32>,
+    /// DevTools endpoint of an already running Chrome to record in instead of launching one
+    pub cdp_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -69,6 +71,9 @@ pub async fn start_recording(request: StartRecordingRequest) -> Result<Recording
     if let Some(url) = &request.start_url {
         body["start_url"] = serde_json::json!(url);
     }
+    if let Some(cdp_url) = &request.cdp_url {
+        body["cdp_url"] = serde_json::json!(cdp_url);
+    }
 
     let response = client
         .post(&url)
@@ -1,4 +1,5 @@
 use crate::sidecar::SidecarManager;
+use crate::taskfile::EnvResolver;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::AppHandle;
@@ -18,6 +19,28 @@ pub struct StartReplayRequest {
     pub stop_when: Option<String>,
     /// Max steps override (None = use global default)
     pub max_steps: Option<i32>,
+    /// Names of variables whose values must be masked in logs, steps and prompts
+    pub secret_variables: Option<Vec<String>>,
+    /// Additional secret values to mask (e.g. resolved `${ENV}` secrets)
+    pub secrets: Option<Vec<String>>,
+    /// Black out password inputs in screenshots (default: true)
+    pub mask_password_fields: Option<bool>,
+    /// Record network traffic as a HAR file in the run's files
+    pub capture_network: Option<bool>,
+    /// Switch to tabs opened by the page, e.g. OAuth popups (default: true)
+    pub follow_new_tabs: Option<bool>,
+    /// Draw element boxes and index labels on screenshots sent to the model
+    pub annotate_screenshots: Option<bool>,
+    /// Proxy settings for this run (single server or rotation pool), overriding the workflow's
+    pub proxy: Option<serde_json::Value>,
+    /// DevTools endpoint of an already running Chrome to drive instead of launching one
+    pub cdp_url: Option<String>,
+    /// Screenshot format/quality/size settings for this run, overriding the workflow's
+    pub screenshots: Option<serde_json::Value>,
+    /// "elements" (default) or "outline" to also send an accessibility outline of the page
+    pub page_representation: Option<String>,
+    /// Plan the task before the first step and track progress against the plan
+    pub planning: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -68,6 +91,28 @@ pub async fn get_llm_providers() -> Result<ProvidersResponse, String> {
     Ok(result)
 }
 
+/// Substitute `${ENV}` references in every string of a JSON value, collecting resolved values as secrets
+fn resolve_env_refs(value: &mut serde_json::Value, secrets: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) if EnvResolver::has_references(s) => {
+            let resolved = EnvResolver::new().resolve(s);
+            secrets.extend(resolved.secrets);
+            *s = resolved.value;
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                resolve_env_refs(item, secrets);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                resolve_env_refs(item, secrets);
+            }
+        }
+        _ => {}
+    }
+}
+
 #[tauri::command]
 pub async fn start_replay(app: AppHandle, request: StartReplayRequest) -> Result<ReplayResponse, String> {
     // Ensure sidecar is running
@@ -78,19 +123,34 @@ pub async fn start_replay(app: AppHandle, request: StartReplayRequest) -> Result
     let client = reqwest::Client::new();
     let url = format!("{}/replay/start", SidecarManager::base_url());
 
+    // Values resolved from `${ENV}` references below are added so the sidecar masks them
+    let mut secrets = request.secrets.clone().unwrap_or_default();
+
     // Transform workflow to match sidecar's expected format
     let mut workflow = request.workflow.clone();
+    let mut secret_variables = request.secret_variables.clone().unwrap_or_default();
     if let Some(obj) = workflow.as_object_mut() {
         // Convert variables from array to map format
         if let Some(vars_array) = obj.get("variables").and_then(|v| v.as_array()) {
+            // Variables flagged sensitive are always masked
+            for var in vars_array {
+                let sensitive = var.get("sensitive").and_then(|s| s.as_bool()).unwrap_or(false);
+                if let (true, Some(name)) = (sensitive, var.get("name").and_then(|n| n.as_str())) {
+                    if !secret_variables.iter().any(|s| s == name) {
+                        secret_variables.push(name.to_string());
+                    }
+                }
+            }
+
             let vars_map: HashMap<String, serde_json::Value> = vars_array
                 .iter()
                 .filter_map(|v| {
                     let name = v.get("name")?.as_str()?;
-                    let default = v
+                    let mut default = v
                         .get("default_value")
                         .cloned()
                         .unwrap_or(serde_json::Value::Null);
+                    resolve_env_refs(&mut default, &mut secrets);
                     Some((name.to_string(), default))
                 })
                 .collect();
@@ -101,6 +161,20 @@ pub async fn start_replay(app: AppHandle, request: StartReplayRequest) -> Result
         }
     }
 
+    let mut variables = request.variables.clone().unwrap_or_default();
+    for value in variables.values_mut() {
+        resolve_env_refs(value, &mut secrets);
+    }
+
+    // Proxy credentials are usually `${ENV}` references - resolve them here and mask the values
+    let mut proxy = request.proxy.clone();
+    if let Some(proxy) = proxy.as_mut() {
+        resolve_env_refs(proxy, &mut secrets);
+    }
+    if let Some(workflow_proxy) = workflow.pointer_mut("/metadata/proxy") {
+        resolve_env_refs(workflow_proxy, &mut secrets);
+    }
+
     // Get auth token for Tasker Fast provider
     let provider = request.llm_provider.as_deref().unwrap_or("google");
     let auth_token = if provider == "tasker-fast" {
@@ -118,11 +192,22 @@ pub async fn start_replay(app: AppHandle, request: StartReplayRequest) -> Result
         "llm_provider": provider,
         "llm_model": request.llm_model.unwrap_or_else(|| "gemini-3-pro-preview".to_string()),
         "task_description": request.task_description,
-        "variables": request.variables.unwrap_or_default(),
+        "variables": variables,
         "iterations": request.iterations.unwrap_or(1),
         "headless": request.headless.unwrap_or(false),
         "stop_when": request.stop_when,
         "max_steps": request.max_steps,
+        "secret_variables": secret_variables,
+        "secrets": secrets,
+        "mask_password_fields": request.mask_password_fields,
+        "capture_network": request.capture_network.unwrap_or(false),
+        "follow_new_tabs": request.follow_new_tabs,
+        "annotate_screenshots": request.annotate_screenshots.unwrap_or(false),
+        "proxy": proxy,
+        "cdp_url": request.cdp_url,
+        "screenshots": request.screenshots,
+        "page_representation": request.page_representation.as_deref().unwrap_or("elements"),
+        "planning": request.planning.unwrap_or(false),
         "auth_token": auth_token,
     });
 
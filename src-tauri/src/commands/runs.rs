@@ -22,6 +22,9 @@ pub struct Run {
     pub completed_at: Option<String>,
     #[serde(default)]
     pub metadata: serde_json::Value,
+    /// Numbered plan of runs started with planning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -213,6 +216,88 @@ pub async fn cancel_run(run_id: String) -> Result<serde_json::Value, String> {
         .map_err(|e| format!("Failed to parse response: {}", e))
 }
 
+#[tauri::command]
+pub async fn get_run_input(run_id: String) -> Result<Option<serde_json::Value>, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/runs/{}/input", SidecarManager::base_url(), run_id);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to get run input: {}", e))?;
+
+    // Not waiting for anything
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !response.status().is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Failed to get run input: {}", error_text));
+    }
+
+    response
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+#[tauri::command]
+pub async fn submit_run_input(run_id: String, answer: String) -> Result<serde_json::Value, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/runs/{}/input", SidecarManager::base_url(), run_id);
+
+    let response = client
+        .post(&url)
+        .json(&serde_json::json!({ "answer": answer }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to submit run input: {}", e))?;
+
+    if !response.status().is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Failed to submit run input: {}", error_text));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+#[tauri::command]
+pub async fn take_over_run(run_id: String) -> Result<serde_json::Value, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/runs/{}/takeover", SidecarManager::base_url(), run_id);
+
+    let response = client
+        .post(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to take over run: {}", e))?;
+
+    if !response.status().is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Failed to take over run: {}", error_text));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
 #[tauri::command]
 pub async fn delete_run(run_id: String) -> Result<serde_json::Value, String> {
     let client = reqwest::Client::new();
@@ -50,6 +50,24 @@ pub struct WorkflowMetadata {
     pub start_url: Option<String>,
     pub llm_provider: Option<String>,
     pub recording_source: String,
+    /// How native JS dialogs are answered during runs: manual | accept | dismiss
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialog_policy: Option<String>,
+    /// What execute_javascript may do during runs: disabled | read_only | full
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub javascript_policy: Option<String>,
+    /// Device/locale/geolocation/network emulation block, passed to the sidecar as-is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emulation: Option<serde_json::Value>,
+    /// Proxy settings (single server or rotation pool), passed to the sidecar as-is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<serde_json::Value>,
+    /// Screenshot format, quality, size limits and clip region, passed to the sidecar as-is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshots: Option<serde_json::Value>,
+    /// External MCP servers whose tools runs can call, passed to the sidecar as-is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +128,12 @@ impl From<Workflow> for WorkflowDto {
                 start_url: None,
                 llm_provider: None,
                 recording_source: "manual".to_string(),
+                dialog_policy: None,
+                javascript_policy: None,
+                emulation: None,
+                proxy: None,
+                screenshots: None,
+                mcp_servers: None,
             }),
             created_at: w.created_at,
             updated_at: w.updated_at,
@@ -155,6 +179,9 @@ pub struct AppSettings {
     pub llm_config: LLMConfig,
     #[serde(default = "default_max_steps")]
     pub default_max_steps: i32,
+    /// MCP servers every run connects to, in addition to the workflow's own
+    #[serde(default)]
+    pub mcp_servers: Vec<serde_json::Value>,
 }
 
 impl Default for AppSettings {
@@ -162,6 +189,7 @@ impl Default for AppSettings {
         AppSettings {
             llm_config: LLMConfig::default(),
             default_max_steps: 50,
+            mcp_servers: Vec::new(),
         }
     }
 }
@@ -172,4 +200,5 @@ pub struct UpdateSettingsRequest {
     pub default_provider: Option<String>,
     pub default_model: Option<String>,
     pub default_max_steps: Option<i32>,
+    pub mcp_servers: Option<Vec<serde_json::Value>>,
 }
@@ -89,6 +89,12 @@ pub async fn create_workflow(req: CreateWorkflowRequest) -> Result<Workflow, sql
         start_url: None,
         llm_provider: None,
         recording_source: "manual".to_string(),
+        dialog_policy: None,
+        javascript_policy: None,
+        emulation: None,
+        proxy: None,
+        screenshots: None,
+        mcp_servers: None,
     }))
     .expect("Failed to serialize metadata - this should never happen with valid WorkflowMetadata types");
 
@@ -219,7 +225,7 @@ pub async fn get_settings() -> Result<AppSettings, sqlx::Error> {
 
     let row = sqlx::query(
         r#"
-        SELECT llm_config_json, default_max_steps
+        SELECT llm_config_json, default_max_steps, mcp_servers_json
         FROM app_settings
         WHERE id = 1
         "#,
@@ -232,10 +238,15 @@ pub async fn get_settings() -> Result<AppSettings, sqlx::Error> {
             let llm_config_json: String = r.get("llm_config_json");
             let llm_config: LLMConfig = serde_json::from_str(&llm_config_json).unwrap_or_default();
             let default_max_steps: Option<i32> = r.get("default_max_steps");
+            let mcp_servers_json: Option<String> = r.get("mcp_servers_json");
+            let mcp_servers = mcp_servers_json
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
 
             Ok(AppSettings {
                 llm_config,
                 default_max_steps: default_max_steps.unwrap_or(50),
+                mcp_servers,
             })
         }
         None => Ok(AppSettings::default()),
@@ -271,23 +282,30 @@ pub async fn update_settings(req: UpdateSettingsRequest) -> Result<AppSettings,
     if let Some(default_max_steps) = req.default_max_steps {
         settings.default_max_steps = default_max_steps;
     }
+    if let Some(mcp_servers) = req.mcp_servers {
+        settings.mcp_servers = mcp_servers;
+    }
 
     let llm_config_json = serde_json::to_string(&settings.llm_config)
         .expect("Failed to serialize LLM config - this should never happen with valid LLMConfig types");
+    let mcp_servers_json = serde_json::to_string(&settings.mcp_servers)
+        .expect("Failed to serialize MCP servers - this should never happen with JSON values");
 
     // Upsert settings (SQLite UPSERT)
     sqlx::query(
         r#"
-        INSERT INTO app_settings (id, llm_config_json, default_max_steps, updated_at)
-        VALUES (1, ?, ?, ?)
+        INSERT INTO app_settings (id, llm_config_json, default_max_steps, mcp_servers_json, updated_at)
+        VALUES (1, ?, ?, ?, ?)
         ON CONFLICT(id) DO UPDATE SET
             llm_config_json = excluded.llm_config_json,
             default_max_steps = excluded.default_max_steps,
+            mcp_servers_json = excluded.mcp_servers_json,
             updated_at = excluded.updated_at
         "#,
     )
     .bind(&llm_config_json)
     .bind(&settings.default_max_steps)
+    .bind(&mcp_servers_json)
     .bind(&now)
     .execute(pool)
     .await?;
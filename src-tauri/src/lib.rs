@@ -80,6 +80,9 @@ pub fn run() {
             commands::runs::get_run,
             commands::runs::start_run,
             commands::runs::cancel_run,
+            commands::runs::get_run_input,
+            commands::runs::submit_run_input,
+            commands::runs::take_over_run,
             commands::runs::delete_run,
             commands::runs::get_run_steps,
             commands::runs::get_run_logs,
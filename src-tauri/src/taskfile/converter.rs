@@ -59,6 +59,20 @@ pub fn taskfile_to_workflow(taskfile: &Taskfile) -> WorkflowDto {
             start_url,
             llm_provider,
             recording_source: "imported".to_string(),
+            dialog_policy: taskfile.execution.dialogs.clone(),
+            javascript_policy: taskfile.execution.javascript.clone(),
+            emulation: Some(&taskfile.dependencies.browser.emulation)
+                .filter(|e| !e.is_empty())
+                .and_then(|e| serde_json::to_value(e).ok()),
+            proxy: taskfile
+                .dependencies
+                .proxy
+                .as_ref()
+                .and_then(|p| serde_json::to_value(p).ok()),
+            screenshots: serde_json::to_value(&taskfile.output.screenshots).ok(),
+            mcp_servers: Some(&taskfile.dependencies.mcp_servers)
+                .filter(|servers| !servers.is_empty())
+                .and_then(|servers| serde_json::to_value(servers).ok()),
         },
         created_at: String::new(), // Will be set by repository
         updated_at: String::new(), // Will be set by repository
@@ -113,9 +127,28 @@ pub fn workflow_to_taskfile(workflow: &WorkflowDto) -> Taskfile {
         },
         triggers: Triggers::default(),
         dependencies: Dependencies {
-            browser: BrowserDependency::default(),
+            browser: BrowserDependency {
+                emulation: workflow
+                    .metadata
+                    .emulation
+                    .clone()
+                    .and_then(|e| serde_json::from_value(e).ok())
+                    .unwrap_or_default(),
+                ..BrowserDependency::default()
+            },
             env: vec![],
             accounts: vec![],
+            proxy: workflow
+                .metadata
+                .proxy
+                .clone()
+                .and_then(|p| serde_json::from_value(p).ok()),
+            mcp_servers: workflow
+                .metadata
+                .mcp_servers
+                .clone()
+                .and_then(|s| serde_json::from_value(s).ok())
+                .unwrap_or_default(),
         },
         limits: Limits::default(),
         variables,
@@ -123,9 +156,19 @@ pub fn workflow_to_taskfile(workflow: &WorkflowDto) -> Taskfile {
             mode: "ai_assisted".to_string(),
             llm: llm_config,
             retry: RetryConfig::default(),
+            dialogs: workflow.metadata.dialog_policy.clone(),
+            javascript: workflow.metadata.javascript_policy.clone(),
         },
         steps,
-        output: Output::default(),
+        output: Output {
+            screenshots: workflow
+                .metadata
+                .screenshots
+                .clone()
+                .and_then(|s| serde_json::from_value(s).ok())
+                .unwrap_or_default(),
+            ..Output::default()
+        },
     }
 }
 
@@ -139,6 +182,29 @@ fn action_to_name(action: &TaskfileAction) -> String {
             let target = selector_to_description(selector);
             format!("Click {}", target)
         }
+        TaskfileAction::DoubleClick { selector } => {
+            let target = selector_to_description(selector);
+            format!("Double-click {}", target)
+        }
+        TaskfileAction::RightClick { selector } => {
+            let target = selector_to_description(selector);
+            format!("Right-click {}", target)
+        }
+        TaskfileAction::DragAndDrop {
+            selector,
+            target,
+            to,
+        } => {
+            let source = selector_to_description(selector);
+            match (target, to) {
+                (Some(target), _) => {
+                    format!("Drag {} onto {}", source, selector_to_description(target))
+                }
+                (None, Some(point)) => format!("Drag {} to ({}, {})", source, point.x, point.y),
+                (None, None) => format!("Drag {}", source),
+            }
+        }
+        TaskfileAction::ClickAt { x, y } => format!("Click at ({}, {})", x, y),
         TaskfileAction::Type { text, .. } => {
             format!("Type '{}'", truncate_string(text, 30))
         }
@@ -149,11 +215,35 @@ fn action_to_name(action: &TaskfileAction) -> String {
             }
             WaitCondition::ElementVisible { .. } => "Wait for element visible".to_string(),
             WaitCondition::ElementHidden { .. } => "Wait for element hidden".to_string(),
+            WaitCondition::RequestComplete { url_pattern, .. } => {
+                format!("Wait for request: {}", truncate_string(url_pattern, 30))
+            }
         },
+        TaskfileAction::Extract { mode, path, .. } if mode.as_deref() == Some("table") => {
+            match path {
+                Some(path) => format!("Extract table to {}", path),
+                None => "Extract table".to_string(),
+            }
+        }
         TaskfileAction::Extract { variable, .. } => {
             format!("Extract to '{}'", variable)
         }
+        TaskfileAction::ExtractResponse { url_pattern, variable, .. } => {
+            format!(
+                "Extract response {} to '{}'",
+                truncate_string(url_pattern, 30),
+                variable
+            )
+        }
         TaskfileAction::Screenshot { .. } => "Take screenshot".to_string(),
+        TaskfileAction::SavePdf { path, .. } => match path {
+            Some(path) => format!("Save PDF to {}", path),
+            None => "Save page as PDF".to_string(),
+        },
+        TaskfileAction::SaveMhtml { path } => match path {
+            Some(path) => format!("Save MHTML to {}", path),
+            None => "Save page as MHTML".to_string(),
+        },
         TaskfileAction::Scroll { direction, .. } => {
             format!("Scroll {}", direction)
         }
@@ -167,6 +257,15 @@ fn action_to_name(action: &TaskfileAction) -> String {
         TaskfileAction::Custom { prompt } => {
             format!("Custom: {}", truncate_string(prompt, 40))
         }
+        TaskfileAction::RunWorkflow { workflow, .. } => {
+            format!("Run workflow '{}'", truncate_string(workflow, 40))
+        }
+        TaskfileAction::ForEach { items, item, .. } => {
+            format!("For each {} in '{}'", item, items)
+        }
+        TaskfileAction::RepeatUntil { max_iterations, .. } => {
+            format!("Repeat until condition (max {})", max_iterations)
+        }
     }
 }
 
@@ -206,6 +305,36 @@ fn taskfile_action_to_json(action: &TaskfileAction) -> serde_json::Value {
                 "selector": selector_to_json(selector)
             })
         }
+        TaskfileAction::DoubleClick { selector } => {
+            serde_json::json!({
+                "type": "double_click",
+                "selector": selector_to_json(selector)
+            })
+        }
+        TaskfileAction::RightClick { selector } => {
+            serde_json::json!({
+                "type": "right_click",
+                "selector": selector_to_json(selector)
+            })
+        }
+        TaskfileAction::DragAndDrop {
+            selector,
+            target,
+            to,
+        } => {
+            serde_json::json!({
+                "type": "drag_and_drop",
+                "selector": selector_to_json(selector),
+                "target": target.as_ref().map(selector_to_json),
+                "coordinates": to.as_ref().map(|p| serde_json::json!({ "x": p.x, "y": p.y }))
+            })
+        }
+        TaskfileAction::ClickAt { x, y } => {
+            serde_json::json!({
+                "type": "click_at",
+                "coordinates": { "x": x, "y": y }
+            })
+        }
         TaskfileAction::Type {
             selector,
             text,
@@ -228,12 +357,42 @@ fn taskfile_action_to_json(action: &TaskfileAction) -> serde_json::Value {
             selector,
             attribute,
             variable,
+            mode,
+            format,
+            path,
+            max_pages,
         } => {
+            let mut options = serde_json::Map::new();
+            if let Some(mode) = mode {
+                options.insert("mode".to_string(), serde_json::json!(mode));
+            }
+            if let Some(format) = format {
+                options.insert("format".to_string(), serde_json::json!(format));
+            }
+            if let Some(max_pages) = max_pages {
+                options.insert("max_pages".to_string(), serde_json::json!(max_pages));
+            }
             serde_json::json!({
                 "type": "extract",
                 "selector": selector_to_json(selector),
                 "attribute": attribute,
-                "variable": variable
+                "variable": variable,
+                "value": path,
+                "options": options
+            })
+        }
+        TaskfileAction::ExtractResponse {
+            url_pattern,
+            variable,
+            method,
+        } => {
+            serde_json::json!({
+                "type": "extract_response",
+                "options": {
+                    "url_pattern": url_pattern,
+                    "variable": variable,
+                    "method": method
+                }
             })
         }
         TaskfileAction::Screenshot {
@@ -246,6 +405,41 @@ fn taskfile_action_to_json(action: &TaskfileAction) -> serde_json::Value {
                 "variable": variable
             })
         }
+        TaskfileAction::SavePdf {
+            path,
+            paper,
+            landscape,
+            print_background,
+            page_ranges,
+        } => {
+            let mut options = serde_json::Map::new();
+            if let Some(paper) = paper {
+                options.insert("paper".to_string(), serde_json::json!(paper));
+            }
+            if *landscape {
+                options.insert("landscape".to_string(), serde_json::json!(true));
+            }
+            if let Some(print_background) = print_background {
+                options.insert(
+                    "print_background".to_string(),
+                    serde_json::json!(print_background),
+                );
+            }
+            if let Some(page_ranges) = page_ranges {
+                options.insert("page_ranges".to_string(), serde_json::json!(page_ranges));
+            }
+            serde_json::json!({
+                "type": "save_pdf",
+                "value": path,
+                "options": options
+            })
+        }
+        TaskfileAction::SaveMhtml { path } => {
+            serde_json::json!({
+                "type": "save_mhtml",
+                "value": path
+            })
+        }
         TaskfileAction::Scroll { direction, amount } => {
             serde_json::json!({
                 "type": "scroll",
@@ -272,9 +466,104 @@ fn taskfile_action_to_json(action: &TaskfileAction) -> serde_json::Value {
                 "prompt": prompt
             })
         }
+        TaskfileAction::RunWorkflow {
+            workflow,
+            variables,
+            share_memories,
+        } => {
+            serde_json::json!({
+                "type": "run_workflow",
+                "options": {
+                    "workflow": workflow,
+                    "variables": variables,
+                    "share_memories": share_memories
+                }
+            })
+        }
+        TaskfileAction::ForEach {
+            items,
+            item,
+            steps,
+            max_iterations,
+        } => {
+            let mut options = serde_json::Map::new();
+            options.insert("items".to_string(), serde_json::json!(items));
+            options.insert("item".to_string(), serde_json::json!(item));
+            options.insert("steps".to_string(), steps_to_json(steps));
+            if let Some(max_iterations) = max_iterations {
+                options.insert(
+                    "max_iterations".to_string(),
+                    serde_json::json!(max_iterations),
+                );
+            }
+            serde_json::json!({
+                "type": "for_each",
+                "options": options
+            })
+        }
+        TaskfileAction::RepeatUntil {
+            until,
+            steps,
+            max_iterations,
+        } => {
+            serde_json::json!({
+                "type": "repeat_until",
+                "options": {
+                    "until": wait_condition_to_json(until),
+                    "steps": steps_to_json(steps),
+                    "max_iterations": max_iterations
+                }
+            })
+        }
     }
 }
 
+/// Loop bodies use the same shape as top-level workflow steps
+fn steps_to_json(steps: &[TaskfileStep]) -> serde_json::Value {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            serde_json::json!({
+                "id": step.id,
+                "order": i,
+                "name": step
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| action_to_name(&step.action)),
+                "description": step.description,
+                "action": taskfile_action_to_json(&step.action)
+            })
+        })
+        .collect()
+}
+
+fn json_to_steps(json: Option<&serde_json::Value>) -> Vec<TaskfileStep> {
+    json.and_then(|v| v.as_array())
+        .map(|steps| {
+            steps
+                .iter()
+                .enumerate()
+                .map(|(i, step)| TaskfileStep {
+                    id: step
+                        .get("id")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .unwrap_or_else(|| format!("step_{}", i + 1)),
+                    action: json_to_taskfile_action(
+                        step.get("action").unwrap_or(&serde_json::Value::Null),
+                    ),
+                    description: step
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    condition: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn selector_to_json(selector: &Selector) -> serde_json::Value {
     serde_json::json!({
         "css": selector.css,
@@ -319,6 +608,16 @@ fn wait_condition_to_json(condition: &WaitCondition) -> serde_json::Value {
                 "ms": ms
             })
         }
+        WaitCondition::RequestComplete {
+            url_pattern,
+            timeout_ms,
+        } => {
+            serde_json::json!({
+                "type": "request_complete",
+                "url_pattern": url_pattern,
+                "timeout_ms": timeout_ms
+            })
+        }
     }
 }
 
@@ -349,63 +648,46 @@ fn json_to_taskfile_action(json: &serde_json::Value) -> TaskfileAction {
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false),
         },
-        "wait" => {
-            let condition = json.get("condition");
-            let cond_type = condition
-                .and_then(|c| c.get("type"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("delay");
-
-            let wait_cond = match cond_type {
-                "url_match" => WaitCondition::UrlMatch {
-                    value: condition
-                        .and_then(|c| c.get("value"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                    timeout_ms: condition
-                        .and_then(|c| c.get("timeout_ms"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(10000) as u32,
-                },
-                "element_visible" => WaitCondition::ElementVisible {
-                    selector: json_to_selector(condition.and_then(|c| c.get("selector"))),
-                    timeout_ms: condition
-                        .and_then(|c| c.get("timeout_ms"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(10000) as u32,
-                },
-                "element_hidden" => WaitCondition::ElementHidden {
-                    selector: json_to_selector(condition.and_then(|c| c.get("selector"))),
-                    timeout_ms: condition
-                        .and_then(|c| c.get("timeout_ms"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(10000) as u32,
-                },
-                _ => WaitCondition::Delay {
-                    ms: condition
-                        .and_then(|c| c.get("ms"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(1000) as u32,
-                },
+        "wait" => TaskfileAction::Wait {
+            condition: json_to_wait_condition(json.get("condition")),
+        },
+        "extract" => {
+            let options = json.get("options");
+            let option = |key: &str| options.and_then(|o| o.get(key));
+            TaskfileAction::Extract {
+                selector: json_to_selector(json.get("selector")),
+                attribute: json
+                    .get("attribute")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("textContent")
+                    .to_string(),
+                variable: json
+                    .get("variable")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("extracted")
+                    .to_string(),
+                mode: option("mode").and_then(|v| v.as_str()).map(String::from),
+                format: option("format").and_then(|v| v.as_str()).map(String::from),
+                path: json.get("value").and_then(|v| v.as_str()).map(String::from),
+                max_pages: option("max_pages")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as u32),
+            }
+        }
+        "extract_response" => {
+            // Fields live under "options"; older workflows had them at the top level
+            let field = |key: &str| {
+                json.get("options")
+                    .and_then(|o| o.get(key))
+                    .or_else(|| json.get(key))
+                    .and_then(|v| v.as_str())
             };
-            TaskfileAction::Wait {
-                condition: wait_cond,
+            TaskfileAction::ExtractResponse {
+                url_pattern: field("url_pattern").unwrap_or("").to_string(),
+                variable: field("variable").unwrap_or("extracted").to_string(),
+                method: field("method").map(String::from),
             }
         }
-        "extract" => TaskfileAction::Extract {
-            selector: json_to_selector(json.get("selector")),
-            attribute: json
-                .get("attribute")
-                .and_then(|v| v.as_str())
-                .unwrap_or("textContent")
-                .to_string(),
-            variable: json
-                .get("variable")
-                .and_then(|v| v.as_str())
-                .unwrap_or("extracted")
-                .to_string(),
-        },
         "screenshot" => TaskfileAction::Screenshot {
             full_page: json
                 .get("full_page")
@@ -438,6 +720,45 @@ fn json_to_taskfile_action(json: &serde_json::Value) -> TaskfileAction {
         "hover" => TaskfileAction::Hover {
             selector: json_to_selector(json.get("selector")),
         },
+        "double_click" => TaskfileAction::DoubleClick {
+            selector: json_to_selector(json.get("selector")),
+        },
+        "right_click" => TaskfileAction::RightClick {
+            selector: json_to_selector(json.get("selector")),
+        },
+        "drag_and_drop" => TaskfileAction::DragAndDrop {
+            selector: json_to_selector(json.get("selector")),
+            target: json
+                .get("target")
+                .filter(|v| !v.is_null())
+                .map(|v| json_to_selector(Some(v))),
+            to: json_to_point(json.get("coordinates")),
+        },
+        "click_at" => {
+            let point = json_to_point(json.get("coordinates")).unwrap_or(Point { x: 0, y: 0 });
+            TaskfileAction::ClickAt {
+                x: point.x,
+                y: point.y,
+            }
+        }
+        "save_pdf" => {
+            let options = json.get("options");
+            let option = |key: &str| options.and_then(|o| o.get(key));
+            TaskfileAction::SavePdf {
+                path: json.get("value").and_then(|v| v.as_str()).map(String::from),
+                paper: option("paper").and_then(|v| v.as_str()).map(String::from),
+                landscape: option("landscape")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                print_background: option("print_background").and_then(|v| v.as_bool()),
+                page_ranges: option("page_ranges")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+            }
+        }
+        "save_mhtml" => TaskfileAction::SaveMhtml {
+            path: json.get("value").and_then(|v| v.as_str()).map(String::from),
+        },
         "custom" => TaskfileAction::Custom {
             prompt: json
                 .get("prompt")
@@ -445,12 +766,110 @@ fn json_to_taskfile_action(json: &serde_json::Value) -> TaskfileAction {
                 .unwrap_or("")
                 .to_string(),
         },
+        "run_workflow" => {
+            let options = json.get("options");
+            let option = |key: &str| options.and_then(|o| o.get(key));
+            TaskfileAction::RunWorkflow {
+                workflow: option("workflow")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                variables: option("variables")
+                    .and_then(|v| v.as_object())
+                    .map(|vars| vars.clone().into_iter().collect())
+                    .unwrap_or_default(),
+                share_memories: option("share_memories")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            }
+        }
+        "for_each" => {
+            let options = json.get("options");
+            let option = |key: &str| options.and_then(|o| o.get(key));
+            TaskfileAction::ForEach {
+                items: option("items")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                item: option("item")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("item")
+                    .to_string(),
+                steps: json_to_steps(option("steps")),
+                max_iterations: option("max_iterations")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as u32),
+            }
+        }
+        "repeat_until" => {
+            let options = json.get("options");
+            let option = |key: &str| options.and_then(|o| o.get(key));
+            TaskfileAction::RepeatUntil {
+                until: json_to_wait_condition(option("until")),
+                steps: json_to_steps(option("steps")),
+                max_iterations: option("max_iterations")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(10) as u32,
+            }
+        }
         _ => TaskfileAction::Custom {
             prompt: format!("Unknown action type: {}", action_type),
         },
     }
 }
 
+fn json_to_wait_condition(condition: Option<&serde_json::Value>) -> WaitCondition {
+    let cond_type = condition
+        .and_then(|c| c.get("type"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("delay");
+
+    match cond_type {
+        "url_match" => WaitCondition::UrlMatch {
+            value: condition
+                .and_then(|c| c.get("value"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            timeout_ms: condition
+                .and_then(|c| c.get("timeout_ms"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10000) as u32,
+        },
+        "element_visible" => WaitCondition::ElementVisible {
+            selector: json_to_selector(condition.and_then(|c| c.get("selector"))),
+            timeout_ms: condition
+                .and_then(|c| c.get("timeout_ms"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10000) as u32,
+        },
+        "element_hidden" => WaitCondition::ElementHidden {
+            selector: json_to_selector(condition.and_then(|c| c.get("selector"))),
+            timeout_ms: condition
+                .and_then(|c| c.get("timeout_ms"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10000) as u32,
+        },
+        "request_complete" => WaitCondition::RequestComplete {
+            url_pattern: condition
+                .and_then(|c| c.get("url_pattern"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            timeout_ms: condition
+                .and_then(|c| c.get("timeout_ms"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10000) as u32,
+        },
+        _ => WaitCondition::Delay {
+            ms: condition
+                .and_then(|c| c.get("ms"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(1000) as u32,
+        },
+    }
+}
+
 fn json_to_selector(json: Option<&serde_json::Value>) -> Selector {
     match json {
         Some(v) => Selector {
@@ -471,6 +890,14 @@ fn json_to_selector(json: Option<&serde_json::Value>) -> Selector {
     }
 }
 
+fn json_to_point(json: Option<&serde_json::Value>) -> Option<Point> {
+    let json = json?;
+    Some(Point {
+        x: json.get("x")?.as_i64()? as i32,
+        y: json.get("y")?.as_i64()? as i32,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -488,4 +915,279 @@ mod tests {
             _ => panic!("Expected Navigate action"),
         }
     }
+
+    #[test]
+    fn test_roundtrip_extract_response_action() {
+        let action = TaskfileAction::ExtractResponse {
+            url_pattern: "*/api/prices*".to_string(),
+            variable: "prices".to_string(),
+            method: Some("GET".to_string()),
+        };
+        let json = taskfile_action_to_json(&action);
+        let back = json_to_taskfile_action(&json);
+
+        match back {
+            TaskfileAction::ExtractResponse {
+                url_pattern,
+                variable,
+                method,
+            } => {
+                assert_eq!(url_pattern, "*/api/prices*");
+                assert_eq!(variable, "prices");
+                assert_eq!(method.as_deref(), Some("GET"));
+            }
+            _ => panic!("Expected ExtractResponse action"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_extract_table_action() {
+        let action = TaskfileAction::Extract {
+            selector: Selector {
+                css: Some("#results".to_string()),
+                xpath: None,
+                text: None,
+                aria_label: None,
+            },
+            attribute: "textContent".to_string(),
+            variable: "results".to_string(),
+            mode: Some("table".to_string()),
+            format: Some("both".to_string()),
+            path: Some("/output/results".to_string()),
+            max_pages: Some(5),
+        };
+        let json = taskfile_action_to_json(&action);
+        assert_eq!(json["options"]["mode"], "table");
+        let back = json_to_taskfile_action(&json);
+
+        match back {
+            TaskfileAction::Extract {
+                mode,
+                format,
+                path,
+                max_pages,
+                ..
+            } => {
+                assert_eq!(mode.as_deref(), Some("table"));
+                assert_eq!(format.as_deref(), Some("both"));
+                assert_eq!(path.as_deref(), Some("/output/results"));
+                assert_eq!(max_pages, Some(5));
+            }
+            _ => panic!("Expected Extract action"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_request_complete_wait() {
+        let action = TaskfileAction::Wait {
+            condition: WaitCondition::RequestComplete {
+                url_pattern: "/graphql".to_string(),
+                timeout_ms: 5000,
+            },
+        };
+        let json = taskfile_action_to_json(&action);
+        let back = json_to_taskfile_action(&json);
+
+        match back {
+            TaskfileAction::Wait {
+                condition: WaitCondition::RequestComplete { url_pattern, timeout_ms },
+            } => {
+                assert_eq!(url_pattern, "/graphql");
+                assert_eq!(timeout_ms, 5000);
+            }
+            _ => panic!("Expected RequestComplete wait"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_drag_and_drop_action() {
+        let action = TaskfileAction::DragAndDrop {
+            selector: Selector {
+                css: Some(".card".to_string()),
+                xpath: None,
+                text: None,
+                aria_label: None,
+            },
+            target: None,
+            to: Some(Point { x: 640, y: 200 }),
+        };
+        let json = taskfile_action_to_json(&action);
+        let back = json_to_taskfile_action(&json);
+
+        match back {
+            TaskfileAction::DragAndDrop {
+                selector,
+                target,
+                to,
+            } => {
+                assert_eq!(selector.css.as_deref(), Some(".card"));
+                assert!(target.is_none());
+                let to = to.expect("drop point");
+                assert_eq!((to.x, to.y), (640, 200));
+            }
+            _ => panic!("Expected DragAndDrop action"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_click_at_action() {
+        let json = taskfile_action_to_json(&TaskfileAction::ClickAt { x: 12, y: 34 });
+        match json_to_taskfile_action(&json) {
+            TaskfileAction::ClickAt { x, y } => assert_eq!((x, y), (12, 34)),
+            _ => panic!("Expected ClickAt action"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_save_pdf_action() {
+        let action = TaskfileAction::SavePdf {
+            path: Some("/evidence/confirmation.pdf".to_string()),
+            paper: Some("a4".to_string()),
+            landscape: true,
+            print_background: None,
+            page_ranges: None,
+        };
+        let json = taskfile_action_to_json(&action);
+        assert_eq!(
+            json["options"],
+            serde_json::json!({ "paper": "a4", "landscape": true })
+        );
+
+        match json_to_taskfile_action(&json) {
+            TaskfileAction::SavePdf {
+                path,
+                paper,
+                landscape,
+                print_background,
+                ..
+            } => {
+                assert_eq!(path.as_deref(), Some("/evidence/confirmation.pdf"));
+                assert_eq!(paper.as_deref(), Some("a4"));
+                assert!(landscape);
+                assert_eq!(print_background, None);
+            }
+            _ => panic!("Expected SavePdf action"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_screenshot_output() {
+        let yaml = r#"
+taskfile: "1.0"
+metadata:
+  name: "Webp screenshots"
+steps:
+  - id: "navigate"
+    action:
+      type: "navigate"
+      url: "https://example.com"
+output:
+  screenshots:
+    include: true
+    format: "webp"
+    quality: 70
+    clip: { x: 0, y: 0, width: 800, height: 600 }
+"#;
+        let taskfile = crate::taskfile::parse_yaml(yaml).unwrap();
+        let workflow = taskfile_to_workflow(&taskfile);
+        let settings = workflow.metadata.screenshots.clone().unwrap();
+        assert_eq!(settings["format"], "webp");
+        assert_eq!(settings["quality"], 70);
+        assert!(settings.get("max_width").is_none());
+
+        let back = workflow_to_taskfile(&workflow);
+        assert_eq!(back.output.screenshots.format, "webp");
+        assert_eq!(back.output.screenshots.quality, Some(70));
+        assert_eq!(back.output.screenshots.clip.unwrap().width, 800.0);
+    }
+
+    #[test]
+    fn test_roundtrip_loop_actions() {
+        let yaml = r#"
+taskfile: "1.0"
+metadata:
+  name: "Supplier pages"
+steps:
+  - id: "each_supplier"
+    action:
+      type: "for_each"
+      items: "suppliers"
+      item: "supplier"
+      max_iterations: 50
+      steps:
+        - id: "open"
+          action: { type: "navigate", url: "https://example.com/s/{{supplier}}" }
+        - id: "next_page"
+          action:
+            type: "repeat_until"
+            until: { type: "element_hidden", selector: { css: "a.next" }, timeout_ms: 2000 }
+            steps:
+              - id: "click_next"
+                action: { type: "click", selector: { css: "a.next" } }
+"#;
+        let taskfile = crate::taskfile::parse_yaml(yaml).unwrap();
+        let workflow = taskfile_to_workflow(&taskfile);
+        let options = &workflow.steps[0].action["options"];
+        assert_eq!(options["items"], "suppliers");
+        assert_eq!(options["steps"][0]["action"]["type"], "navigate");
+        assert_eq!(
+            options["steps"][1]["action"]["options"]["max_iterations"],
+            10
+        );
+
+        let back = workflow_to_taskfile(&workflow);
+        match &back.steps[0].action {
+            TaskfileAction::ForEach {
+                items,
+                item,
+                steps,
+                max_iterations,
+            } => {
+                assert_eq!(items, "suppliers");
+                assert_eq!(item, "supplier");
+                assert_eq!(*max_iterations, Some(50));
+                assert_eq!(steps[0].id, "open");
+                match &steps[1].action {
+                    TaskfileAction::RepeatUntil { until, steps, .. } => {
+                        assert!(matches!(
+                            until,
+                            WaitCondition::ElementHidden {
+                                timeout_ms: 2000,
+                                ..
+                            }
+                        ));
+                        assert!(matches!(steps[0].action, TaskfileAction::Click { .. }));
+                    }
+                    _ => panic!("Expected RepeatUntil action"),
+                }
+            }
+            _ => panic!("Expected ForEach action"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_run_workflow_action() {
+        let action = TaskfileAction::RunWorkflow {
+            workflow: "Login to portal".to_string(),
+            variables: [("account".to_string(), serde_json::json!("{{account}}"))]
+                .into_iter()
+                .collect(),
+            share_memories: true,
+        };
+        let json = taskfile_action_to_json(&action);
+        assert_eq!(json["options"]["workflow"], "Login to portal");
+
+        match json_to_taskfile_action(&json) {
+            TaskfileAction::RunWorkflow {
+                workflow,
+                variables,
+                share_memories,
+            } => {
+                assert_eq!(workflow, "Login to portal");
+                assert_eq!(variables["account"], "{{account}}");
+                assert!(share_memories);
+            }
+            _ => panic!("Expected RunWorkflow action"),
+        }
+    }
 }
@@ -54,9 +54,10 @@ impl EnvResolver {
     pub fn resolve(&self, input: &str) -> ResolveResult {
         let mut result = input.to_string();
         let mut unresolved = Vec::new();
+        let mut secrets = Vec::new();
 
         // Resolve ${ENV_VAR} patterns (environment variables)
-        result = self.resolve_env_vars(&result, &mut unresolved);
+        result = self.resolve_env_vars(&result, &mut unresolved, &mut secrets);
 
         // Resolve {{variable}} patterns (workflow variables)
         result = self.resolve_workflow_vars(&result, &mut unresolved);
@@ -64,11 +65,18 @@ impl EnvResolver {
         ResolveResult {
             value: result,
             unresolved,
+            secrets,
         }
     }
 
     /// Resolve only environment variable references
-    fn resolve_env_vars(&self, input: &str, unresolved: &mut Vec<UnresolvedVar>) -> String {
+    /// Substituted values are collected into `secrets` so callers can redact them
+    fn resolve_env_vars(
+        &self,
+        input: &str,
+        unresolved: &mut Vec<UnresolvedVar>,
+        secrets: &mut Vec<String>,
+    ) -> String {
         let re = get_env_var_regex();
 
         let mut result = input.to_string();
@@ -79,6 +87,7 @@ impl EnvResolver {
             match env::var(var_name) {
                 Ok(value) => {
                     result = result.replace(full_match, &value);
+                    secrets.push(value);
                 }
                 Err(_) => {
                     unresolved.push(UnresolvedVar {
@@ -175,6 +184,8 @@ pub struct UnresolvedVar {
 pub struct ResolveResult {
     pub value: String,
     pub unresolved: Vec<UnresolvedVar>,
+    /// Values substituted from `${ENV}` references - pass to the sidecar for redaction
+    pub secrets: Vec<String>,
 }
 
 impl ResolveResult {
@@ -206,6 +217,16 @@ mod tests {
         let result = resolver.resolve("The secret is: ${TEST_VAR_123}");
         assert_eq!(result.value, "The secret is: secret_value");
         assert!(result.is_complete());
+        assert_eq!(result.secrets, vec!["secret_value".to_string()]);
+    }
+
+    #[test]
+    fn test_workflow_vars_not_secrets() {
+        let mut resolver = EnvResolver::new();
+        resolver.set_variable("username", "testuser");
+
+        let result = resolver.resolve("Hello, {{username}}!");
+        assert!(result.secrets.is_empty());
     }
 
     #[test]
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Root Taskfile structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -153,6 +154,14 @@ pub struct Dependencies {
 
     #[serde(default)]
     pub accounts: Vec<String>,
+
+    /// Route the browser through a proxy (or rotate through a pool)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+
+    /// External MCP servers whose tools the agent can call during the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServer>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,6 +172,10 @@ pub struct BrowserDependency {
 
     #[serde(default)]
     pub headless: bool,
+
+    /// Device, locale, geolocation and network emulation
+    #[serde(flatten)]
+    pub emulation: BrowserEmulation,
 }
 
 impl Default for BrowserDependency {
@@ -170,14 +183,153 @@ impl Default for BrowserDependency {
         BrowserDependency {
             browser_type: "chromium".to_string(),
             headless: false,
+            emulation: BrowserEmulation::default(),
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BrowserEmulation {
+    /// Device preset: iphone_se | iphone_15 | iphone_15_pro_max | pixel_7 | galaxy_s23 | ipad | desktop | desktop_hd
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<Viewport>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_scale_factor: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mobile: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub touch: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geolocation: Option<Geolocation>,
+
+    /// IANA timezone, e.g. "Europe/Berlin"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// BCP 47 locale, also sent as Accept-Language, e.g. "de-DE"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+
+    /// light | dark
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+
+    /// Network throttling: offline | slow_3g | fast_3g | 4g
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+}
+
+impl BrowserEmulation {
+    pub fn is_empty(&self) -> bool {
+        self.device.is_none()
+            && self.viewport.is_none()
+            && self.device_scale_factor.is_none()
+            && self.mobile.is_none()
+            && self.touch.is_none()
+            && self.user_agent.is_none()
+            && self.geolocation.is_none()
+            && self.timezone.is_none()
+            && self.locale.is_none()
+            && self.color_scheme.is_none()
+            && self.network.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Viewport {
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+}
+
 fn default_browser_type() -> String {
     "chromium".to_string()
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProxySettings {
+    /// http://host:port, https://host:port or socks5://host:port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+
+    /// Use ${ENV_VAR} references to keep credentials out of the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// Proxies rotated across runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pool: Vec<ProxyServer>,
+
+    /// round_robin | random
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<String>,
+
+    /// Hosts that skip the proxy, e.g. "localhost", "*.internal"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bypass: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyServer {
+    pub server: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct McpServer {
+    /// Prefix of the server's tool names, e.g. "crm" gives "crm__find_customer"
+    pub name: String,
+
+    /// Start the server as a subprocess speaking MCP over stdio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+
+    /// Or reach it over streamable HTTP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Only offer these tools to the agent (empty = all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvDependency {
     pub name: String,
@@ -270,6 +422,14 @@ pub struct ExecutionConfig {
 
     #[serde(default)]
     pub retry: RetryConfig,
+
+    /// Native JS dialog handling: manual | accept | dismiss
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialogs: Option<String>,
+
+    /// What execute_javascript may do: disabled | read_only | full (default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub javascript: Option<String>,
 }
 
 impl Default for ExecutionConfig {
@@ -278,6 +438,8 @@ impl Default for ExecutionConfig {
             mode: "ai_assisted".to_string(),
             llm: None,
             retry: RetryConfig::default(),
+            dialogs: None,
+            javascript: None,
         }
     }
 }
@@ -347,6 +509,25 @@ pub enum TaskfileAction {
     Click {
         selector: Selector,
     },
+    DoubleClick {
+        selector: Selector,
+    },
+    RightClick {
+        selector: Selector,
+    },
+    /// Drag `selector` onto `target`, or to the viewport point `to` when no target is given
+    DragAndDrop {
+        selector: Selector,
+        #[serde(default)]
+        target: Option<Selector>,
+        #[serde(default)]
+        to: Option<Point>,
+    },
+    /// Click at a viewport point in CSS pixels (canvas apps, maps, charts)
+    ClickAt {
+        x: i32,
+        y: i32,
+    },
     Type {
         selector: Selector,
         text: String,
@@ -356,11 +537,33 @@ pub enum TaskfileAction {
     Wait {
         condition: WaitCondition,
     },
+    /// Read one attribute into a variable, or in table mode save the rows of a table,
+    /// grid or repeated list to the run's files
     Extract {
         selector: Selector,
         #[serde(default = "default_attribute")]
         attribute: String,
         variable: String,
+        /// value (default) | table
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<String>,
+        /// Table mode: csv (default) | json | both
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        /// Table mode: virtual path without extension (default: timestamped file in /output)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Table mode: follow "next page" links and merge up to this many pages
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_pages: Option<u32>,
+    },
+    /// Store the body of a network response (e.g. the site's own JSON API) in a variable
+    ExtractResponse {
+        /// URL substring or pattern with `*` wildcards
+        url_pattern: String,
+        variable: String,
+        #[serde(default)]
+        method: Option<String>,
     },
     Screenshot {
         #[serde(default)]
@@ -368,6 +571,28 @@ pub enum TaskfileAction {
         #[serde(default)]
         variable: Option<String>,
     },
+    /// Print the page to a PDF stored in the run's files
+    SavePdf {
+        /// Virtual path, e.g. /evidence/confirmation.pdf (default: timestamped file in /output)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// letter | legal | tabloid | a3 | a4 | a5
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        paper: Option<String>,
+        #[serde(default)]
+        landscape: bool,
+        /// Include background colors and images (default true)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        print_background: Option<bool>,
+        /// e.g. "1-3, 5"
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        page_ranges: Option<String>,
+    },
+    /// Save the page as an MHTML archive in the run's files
+    SaveMhtml {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
     Scroll {
         #[serde(default)]
         direction: String,
@@ -384,6 +609,49 @@ pub enum TaskfileAction {
     Custom {
         prompt: String,
     },
+    /// Run another saved workflow in this browser; fields of its JSON result become variables
+    RunWorkflow {
+        /// Name or ID of the saved workflow
+        workflow: String,
+        /// Values for the callee's variables (may use `{{...}}` of this workflow)
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        variables: HashMap<String, serde_json::Value>,
+        /// Let the callee read and add to this run's memories
+        #[serde(default)]
+        share_memories: bool,
+    },
+    /// Run `steps` once per entry of a list variable, with the entry available as `{{item}}`
+    ForEach {
+        /// Variable holding the list (an input or the result of an earlier extract step)
+        items: String,
+        /// Name the current entry is substituted under (default `item`)
+        #[serde(default = "default_loop_item")]
+        item: String,
+        steps: Vec<TaskfileStep>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_iterations: Option<u32>,
+    },
+    /// Run `steps` until the condition holds (checked after each pass) or the cap is reached
+    RepeatUntil {
+        until: WaitCondition,
+        steps: Vec<TaskfileStep>,
+        #[serde(default = "default_max_iterations")]
+        max_iterations: u32,
+    },
+}
+
+fn default_loop_item() -> String {
+    "item".to_string()
+}
+
+fn default_max_iterations() -> u32 {
+    10
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
 }
 
 fn default_attribute() -> String {
@@ -426,6 +694,12 @@ pub enum WaitCondition {
     Delay {
         ms: u32,
     },
+    /// Wait until a network request matching the URL pattern has completed
+    RequestComplete {
+        url_pattern: String,
+        #[serde(default = "default_wait_timeout")]
+        timeout_ms: u32,
+    },
 }
 
 fn default_wait_timeout() -> u32 {
@@ -461,21 +735,49 @@ pub struct ScreenshotOutput {
     #[serde(default)]
     pub include: bool,
 
+    /// jpeg | png | webp
     #[serde(default = "default_format")]
     pub format: String,
+
+    /// JPEG/WebP quality 1-100 (default 80)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+
+    /// Downscale wider screenshots to this width (default 1280, 0 = never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u32>,
+
+    /// Only capture this part of the viewport (CSS pixels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clip: Option<ClipRegion>,
 }
 
 impl Default for ScreenshotOutput {
     fn default() -> Self {
         ScreenshotOutput {
             include: true,
-            format: "png".to_string(),
+            format: default_format(),
+            quality: None,
+            max_width: None,
+            max_height: None,
+            clip: None,
         }
     }
 }
 
 fn default_format() -> String {
-    "png".to_string()
+    "jpeg".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
 }
 
 // === VALIDATION RESULT ===
@@ -5,6 +5,7 @@ import type {
 	RunLog,
 	RunListResponse,
 	RunListQuery,
+	HumanQuestion,
 	StartRunRequest,
 	StartRunResponse
 } from '$lib/types/run';
@@ -34,6 +35,21 @@ export async function cancelRun(runId: string): Promise<{ run_id: string; status
 	return invoke<{ run_id: string; status: string }>('cancel_run', { runId });
 }
 
+// Get the question a run is waiting on (null when it isn't waiting)
+export async function getRunInput(runId: string): Promise<HumanQuestion | null> {
+	return invoke<HumanQuestion | null>('get_run_input', { runId });
+}
+
+// Answer a run's question, or hand the browser back after a take-over
+export async function submitRunInput(runId: string, answer: string): Promise<{ run_id: string; status: string }> {
+	return invoke<{ run_id: string; status: string }>('submit_run_input', { runId, answer });
+}
+
+// Pause a headed run before its next step so the user can use the browser
+export async function takeOverRun(runId: string): Promise<{ run_id: string; status: string }> {
+	return invoke<{ run_id: string; status: string }>('take_over_run', { runId });
+}
+
 // Delete a run
 export async function deleteRun(runId: string): Promise<{ run_id: string; deleted: boolean }> {
 	return invoke<{ run_id: string; deleted: boolean }>('delete_run', { runId });
@@ -54,6 +70,7 @@ export function formatRunStatus(status: string): string {
 	const statusMap: Record<string, string> = {
 		pending: 'PENDING',
 		running: 'RUNNING',
+		waiting_for_input: 'WAITING FOR INPUT',
 		completed: 'COMPLETED',
 		failed: 'FAILED',
 		cancelled: 'CANCELLED'
@@ -66,6 +83,7 @@ export function getStatusColorClass(status: string): string {
 	const colorMap: Record<string, string> = {
 		pending: 'bg-brutal-orange',
 		running: 'bg-brutal-cyan',
+		waiting_for_input: 'bg-brutal-yellow',
 		completed: 'bg-brutal-green',
 		failed: 'bg-brutal-magenta',
 		cancelled: 'bg-gray-400'
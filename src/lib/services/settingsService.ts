@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { McpServerConfig } from '$lib/types/taskfile';
 
 export interface ApiKeys {
 	gemini?: string;
@@ -15,6 +16,8 @@ export interface LLMConfig {
 export interface AppSettings {
 	llm_config: LLMConfig;
 	default_max_steps: number;
+	/** MCP servers every run connects to, in addition to the workflow's own */
+	mcp_servers: McpServerConfig[];
 }
 
 // Available models per provider
@@ -56,12 +59,14 @@ export async function updateSettings(options: {
 	default_provider?: string;
 	default_model?: string;
 	default_max_steps?: number;
+	mcp_servers?: McpServerConfig[];
 }): Promise<AppSettings> {
 	return invoke<AppSettings>('update_settings', {
 		apiKeys: options.api_keys,
 		defaultProvider: options.default_provider,
 		defaultModel: options.default_model,
-		defaultMaxSteps: options.default_max_steps
+		defaultMaxSteps: options.default_max_steps,
+		mcpServers: options.mcp_servers
 	});
 }
 
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { Workflow } from '$lib/types/workflow';
+import type { ProxySettings, ScreenshotSettings } from '$lib/types/taskfile';
 
 // Types
 export interface RecordingResponse {
@@ -37,6 +38,8 @@ export interface StartRecordingOptions {
 	headless?: boolean;
 	viewport_width?: number;
 	viewport_height?: number;
+	/** DevTools endpoint of a running Chrome to record in instead of launching one */
+	cdp_url?: string;
 }
 
 // AI agent is ALWAYS used - recorded workflow serves as hints
@@ -52,6 +55,30 @@ export interface StartReplayOptions {
 	stop_when?: string;
 	/** Max steps override (undefined = use global default) */
 	max_steps?: number;
+	/** Names of variables whose values are masked in logs, steps and prompts */
+	secret_variables?: string[];
+	/** Extra secret values to mask; values of `${ENV}` references are added automatically */
+	secrets?: string[];
+	/** Black out password inputs in screenshots (default: true) */
+	mask_password_fields?: boolean;
+	/** Record network traffic as a HAR file in the run's files */
+	capture_network?: boolean;
+	/** Switch to tabs opened by the page, e.g. OAuth popups (default: true) */
+	follow_new_tabs?: boolean;
+	/** Draw element boxes and index labels on screenshots sent to the model */
+	annotate_screenshots?: boolean;
+	/** Proxy for this run, overriding the workflow's (a pool rotates across runs) */
+	proxy?: ProxySettings;
+	/** DevTools endpoint of a running Chrome to drive instead of launching one,
+	 *  e.g. 'http://localhost:9222' for Chrome started with --remote-debugging-port=9222 */
+	cdp_url?: string;
+	/** Screenshot format, quality and size for this run, overriding the workflow's */
+	screenshots?: ScreenshotSettings;
+	/** 'outline' also sends the whole page's headings, landmarks, lists, tables and text -
+	 *  gives text-only models the page structure (default: 'elements') */
+	page_representation?: 'elements' | 'outline';
+	/** Plan the task before the first step and track progress against the plan */
+	planning?: boolean;
 }
 
 // Sidecar management
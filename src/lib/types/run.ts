@@ -1,5 +1,5 @@
 // Run status enum
-export type RunStatus = 'pending' | 'running' | 'completed' | 'failed' | 'cancelled';
+export type RunStatus = 'pending' | 'running' | 'waiting_for_input' | 'completed' | 'failed' | 'cancelled';
 
 // Run model matching backend
 export interface Run {
@@ -14,6 +14,25 @@ export interface Run {
 	started_at: string; // Also used as created_at
 	completed_at?: string;
 	metadata: Record<string, unknown>;
+	plan?: RunPlan;
+}
+
+// Progress of one plan step
+export type PlanStepStatus = 'pending' | 'in_progress' | 'done' | 'failed' | 'skipped';
+
+// One numbered step of a run's plan
+export interface PlanStep {
+	number: number;
+	description: string;
+	status: PlanStepStatus;
+	note?: string;
+}
+
+// Plan the agent works through (runs started with planning)
+export interface RunPlan {
+	steps: PlanStep[];
+	revision: number;
+	updated_at: string;
 }
 
 // Run step model
@@ -74,6 +93,15 @@ export interface StartRunRequest {
 	hints?: unknown;
 }
 
+// Question a run is waiting on (ask_human, or a browser take-over)
+export interface HumanQuestion {
+	question: string;
+	options?: string[];
+	take_over: boolean;
+	asked_at: string;
+	timeout_secs: number;
+}
+
 // WebSocket event types for runs
 export interface RunUpdateEvent {
 	type: 'run_status' | 'run_step' | 'run_log';
@@ -57,13 +57,66 @@ export interface Dependencies {
 	browser: BrowserDependency;
 	env: EnvDependency[];
 	accounts: string[];
+	proxy?: ProxySettings;
+	/** External MCP servers whose tools the agent can call */
+	mcp_servers?: McpServerConfig[];
 }
 
-export interface BrowserDependency {
+export interface BrowserDependency extends BrowserEmulation {
 	type: 'chromium' | 'firefox' | 'webkit';
 	headless: boolean;
 }
 
+export interface BrowserEmulation {
+	/** Device preset, e.g. 'iphone_15', 'pixel_7', 'ipad' */
+	device?: string;
+	viewport?: { width: number; height: number };
+	device_scale_factor?: number;
+	mobile?: boolean;
+	touch?: boolean;
+	user_agent?: string;
+	geolocation?: { latitude: number; longitude: number; accuracy?: number };
+	/** IANA timezone, e.g. 'Europe/Berlin' */
+	timezone?: string;
+	/** BCP 47 locale, also sent as Accept-Language */
+	locale?: string;
+	color_scheme?: 'light' | 'dark';
+	network?: 'offline' | 'slow_3g' | 'fast_3g' | '4g';
+}
+
+export interface ProxyServer {
+	/** http://host:port, https://, socks4:// or socks5:// */
+	server: string;
+	username?: string;
+	password?: string;
+}
+
+export interface ProxySettings {
+	server?: string;
+	/** Use ${ENV_VAR} references to keep credentials out of the file */
+	username?: string;
+	password?: string;
+	/** Proxies rotated across runs */
+	pool?: ProxyServer[];
+	rotation?: 'round_robin' | 'random';
+	/** Hosts that skip the proxy, e.g. 'localhost', '*.internal' */
+	bypass?: string[];
+}
+
+/** An MCP server started with `command` (stdio) or reached at `url` (streamable HTTP) */
+export interface McpServerConfig {
+	/** Prefix of the server's tool names, e.g. 'crm' gives 'crm__find_customer' */
+	name: string;
+	command?: string;
+	args?: string[];
+	env?: Record<string, string>;
+	url?: string;
+	headers?: Record<string, string>;
+	/** Only offer these tools to the agent (empty = all) */
+	tools?: string[];
+	timeout_secs?: number;
+}
+
 export interface EnvDependency {
 	name: string;
 	required: boolean;
@@ -100,6 +153,9 @@ export interface ExecutionConfig {
 	mode: 'direct' | 'ai_assisted';
 	llm?: LLMExecutionConfig;
 	retry: RetryConfig;
+	dialogs?: 'manual' | 'accept' | 'dismiss';
+	/** What execute_javascript may do (default full) */
+	javascript?: 'disabled' | 'read_only' | 'full';
 }
 
 export interface LLMExecutionConfig {
@@ -125,14 +181,24 @@ export interface TaskfileStep {
 export type TaskfileAction =
 	| NavigateAction
 	| ClickAction
+	| DoubleClickAction
+	| RightClickAction
+	| DragAndDropAction
+	| ClickAtAction
 	| TypeAction
 	| WaitAction
 	| ExtractAction
+	| ExtractResponseAction
 	| ScreenshotAction
+	| SavePdfAction
+	| SaveMhtmlAction
 	| ScrollAction
 	| SelectAction
 	| HoverAction
-	| CustomAction;
+	| CustomAction
+	| RunWorkflowAction
+	| ForEachAction
+	| RepeatUntilAction;
 
 export interface NavigateAction {
 	type: 'navigate';
@@ -144,6 +210,36 @@ export interface ClickAction {
 	selector: Selector;
 }
 
+export interface DoubleClickAction {
+	type: 'double_click';
+	selector: Selector;
+}
+
+export interface RightClickAction {
+	type: 'right_click';
+	selector: Selector;
+}
+
+export interface DragAndDropAction {
+	type: 'drag_and_drop';
+	selector: Selector;
+	/** Element to drop onto; use `to` for a viewport point instead */
+	target?: Selector;
+	to?: Point;
+}
+
+export interface ClickAtAction {
+	type: 'click_at';
+	/** Viewport CSS pixels */
+	x: number;
+	y: number;
+}
+
+export interface Point {
+	x: number;
+	y: number;
+}
+
 export interface TypeAction {
 	type: 'type';
 	selector: Selector;
@@ -161,6 +257,21 @@ export interface ExtractAction {
 	selector: Selector;
 	attribute: string;
 	variable: string;
+	/** 'table' saves the rows of a table, grid or repeated list to the run's files */
+	mode?: 'value' | 'table';
+	format?: 'csv' | 'json' | 'both';
+	/** Virtual path without extension */
+	path?: string;
+	/** Follow "next page" links and merge up to this many pages */
+	max_pages?: number;
+}
+
+export interface ExtractResponseAction {
+	type: 'extract_response';
+	/** URL substring or pattern with * wildcards */
+	url_pattern: string;
+	variable: string;
+	method?: string;
 }
 
 export interface ScreenshotAction {
@@ -169,6 +280,23 @@ export interface ScreenshotAction {
 	variable?: string;
 }
 
+export interface SavePdfAction {
+	type: 'save_pdf';
+	/** Virtual path in the run's files (default: timestamped file in /output) */
+	path?: string;
+	paper?: 'letter' | 'legal' | 'tabloid' | 'a3' | 'a4' | 'a5';
+	landscape?: boolean;
+	/** Include background colors and images (default true) */
+	print_background?: boolean;
+	/** e.g. '1-3, 5' */
+	page_ranges?: string;
+}
+
+export interface SaveMhtmlAction {
+	type: 'save_mhtml';
+	path?: string;
+}
+
 export interface ScrollAction {
 	type: 'scroll';
 	direction?: string;
@@ -191,6 +319,35 @@ export interface CustomAction {
 	prompt: string;
 }
 
+export interface RunWorkflowAction {
+	type: 'run_workflow';
+	/** Name or ID of the saved workflow */
+	workflow: string;
+	/** Values for the callee's variables; may use {{...}} of this workflow */
+	variables?: Record<string, unknown>;
+	/** Let the callee read and add to this run's memories */
+	share_memories?: boolean;
+}
+
+export interface ForEachAction {
+	type: 'for_each';
+	/** Variable holding the list (an input or an earlier extract) */
+	items: string;
+	/** Name of the current entry in `{{...}}` placeholders (default 'item') */
+	item?: string;
+	steps: TaskfileStep[];
+	max_iterations?: number;
+}
+
+export interface RepeatUntilAction {
+	type: 'repeat_until';
+	/** Checked after each pass */
+	until: WaitCondition;
+	steps: TaskfileStep[];
+	/** Default 10 */
+	max_iterations?: number;
+}
+
 export interface Selector {
 	css?: string;
 	xpath?: string;
@@ -202,7 +359,8 @@ export type WaitCondition =
 	| UrlMatchCondition
 	| ElementVisibleCondition
 	| ElementHiddenCondition
-	| DelayCondition;
+	| DelayCondition
+	| RequestCompleteCondition;
 
 export interface UrlMatchCondition {
 	type: 'url_match';
@@ -227,6 +385,12 @@ export interface DelayCondition {
 	ms: number;
 }
 
+export interface RequestCompleteCondition {
+	type: 'request_complete';
+	url_pattern: string;
+	timeout_ms: number;
+}
+
 export interface StepCondition {
 	variable: string;
 	operator: 'eq' | 'ne' | 'contains' | 'exists';
@@ -240,9 +404,26 @@ export interface Output {
 	screenshots: ScreenshotOutput;
 }
 
-export interface ScreenshotOutput {
+export interface ScreenshotOutput extends ScreenshotSettings {
 	include: boolean;
-	format: 'png' | 'jpeg';
+}
+
+export interface ScreenshotSettings {
+	format: 'jpeg' | 'jpg' | 'png' | 'webp';
+	/** JPEG/WebP quality 1-100 (default 80) */
+	quality?: number;
+	/** Downscale wider screenshots to this width (default 1280, 0 = never) */
+	max_width?: number;
+	max_height?: number;
+	/** Only capture this part of the viewport, in CSS pixels */
+	clip?: ClipRegion;
+}
+
+export interface ClipRegion {
+	x: number;
+	y: number;
+	width: number;
+	height: number;
 }
 
 // === VALIDATION ===
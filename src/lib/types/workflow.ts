@@ -1,3 +1,5 @@
+import type { BrowserEmulation, McpServerConfig, ProxySettings, ScreenshotSettings } from './taskfile';
+
 export interface Workflow {
 	id: string;
 	name: string;
@@ -27,11 +29,17 @@ export interface WorkflowStep {
 
 export type BrowserAction =
 	| ClickAction
+	| DoubleClickAction
+	| RightClickAction
+	| DragAndDropAction
+	| ClickAtAction
 	| TypeAction
 	| NavigateAction
 	| ScrollAction
 	| WaitAction
 	| ExtractAction
+	| SavePdfAction
+	| SaveMhtmlAction
 	| CustomAction
 	| GoBackAction
 	| SearchAction
@@ -49,6 +57,29 @@ export interface ClickAction {
 	coordinates?: { x: number; y: number };
 }
 
+export interface DoubleClickAction {
+	type: 'double_click';
+	selector?: ElementSelector;
+}
+
+export interface RightClickAction {
+	type: 'right_click';
+	selector?: ElementSelector;
+}
+
+export interface DragAndDropAction {
+	type: 'drag_and_drop';
+	selector?: ElementSelector;
+	/** Drop target; falls back to coordinates when absent */
+	target?: ElementSelector;
+	coordinates?: { x: number; y: number };
+}
+
+export interface ClickAtAction {
+	type: 'click_at';
+	coordinates: { x: number; y: number };
+}
+
 export interface TypeAction {
 	type: 'type';
 	selector?: ElementSelector;
@@ -80,6 +111,21 @@ export interface ExtractAction {
 	attribute?: string;
 	variable_name?: string;
 	text?: string;
+	/** Table mode: virtual path in the run's files */
+	value?: string;
+	options?: { mode?: 'value' | 'table'; format?: 'csv' | 'json' | 'both'; max_pages?: number };
+}
+
+export interface SavePdfAction {
+	type: 'save_pdf';
+	/** Virtual path in the run's files */
+	value?: string;
+	options?: { paper?: string; landscape?: boolean; print_background?: boolean; page_ranges?: string };
+}
+
+export interface SaveMhtmlAction {
+	type: 'save_mhtml';
+	value?: string;
 }
 
 export interface CustomAction {
@@ -168,8 +214,19 @@ export interface WorkflowMetadata {
 	start_url?: string;
 	llm_provider?: string;
 	recording_source: 'manual' | 'recorded' | 'embedded' | 'text_description';
+	dialog_policy?: DialogPolicy;
+	javascript_policy?: JsPolicy;
+	emulation?: BrowserEmulation;
+	proxy?: ProxySettings;
+	screenshots?: ScreenshotSettings;
+	mcp_servers?: McpServerConfig[];
 }
 
+export type DialogPolicy = 'manual' | 'accept' | 'dismiss';
+
+/** What execute_javascript may do: read_only runs scripts in an isolated world without network, navigation or DOM changes */
+export type JsPolicy = 'disabled' | 'read_only' | 'full';
+
 export interface CreateWorkflowRequest {
 	name: string;
 	steps?: WorkflowStep[];
@@ -9,6 +9,7 @@ interface BackendAction {
 	url?: string;
 	text?: string;
 	coordinates?: { x: number; y: number };
+	target?: BackendSelector | ElementSelector;
 	options?: Record<string, unknown>;
 }
 
@@ -88,6 +89,33 @@ export function formatActionDescription(action: BrowserAction | BackendAction):
 			return `Click ${target}`;
 		}
 
+		case 'double_click': {
+			const target = getTargetDescription(selector);
+			return `Double-click ${target}`;
+		}
+
+		case 'right_click': {
+			const target = getTargetDescription(selector);
+			return `Right-click ${target}`;
+		}
+
+		case 'drag_and_drop': {
+			const source = getTargetDescription(selector);
+			const dropTarget = normalizeSelector(actionAny.target);
+			if (dropTarget) {
+				return `Drag ${source} onto ${getTargetDescription(dropTarget)}`;
+			}
+			if (actionAny.coordinates) {
+				return `Drag ${source} to (${actionAny.coordinates.x}, ${actionAny.coordinates.y})`;
+			}
+			return `Drag ${source}`;
+		}
+
+		case 'click_at': {
+			const { x, y } = actionAny.coordinates ?? { x: 0, y: 0 };
+			return `Click at (${x}, ${y})`;
+		}
+
 		case 'type': {
 			const text = truncateText(actionAny.value || (action as { text?: string }).text || '', 30);
 			const target = getTargetDescription(selector);
@@ -121,6 +149,8 @@ export function formatActionDescription(action: BrowserAction | BackendAction):
 				return `Wait for element to hide`;
 			} else if (condition?.type === 'url_match') {
 				return `Wait for URL to match`;
+			} else if (condition?.type === 'request_complete') {
+				return `Wait for network request`;
 			} else if (condition?.type === 'timeout') {
 				return `Wait ${condition.value}ms`;
 			}
@@ -130,6 +160,11 @@ export function formatActionDescription(action: BrowserAction | BackendAction):
 		case 'extract':
 		case 'screenshot': {
 			const target = getTargetDescription(selector);
+			const mode = actionAny.options?.mode || (action as { mode?: string }).mode;
+			if (actionType === 'extract' && mode === 'table') {
+				const path = actionAny.value || (action as { path?: string }).path;
+				return path ? `Extract table ${target} to ${truncateText(path, 40)}` : `Extract table ${target}`;
+			}
 			const varName = (action as { variable_name?: string }).variable_name;
 			if (varName) {
 				return `Extract ${target} into "${varName}"`;
@@ -137,11 +172,42 @@ export function formatActionDescription(action: BrowserAction | BackendAction):
 			return actionType === 'screenshot' ? 'Take screenshot' : `Extract ${target}`;
 		}
 
+		case 'save_pdf':
+		case 'save_mhtml': {
+			const kind = actionType === 'save_pdf' ? 'PDF' : 'MHTML';
+			const path = actionAny.value || (action as { path?: string }).path;
+			return path ? `Save ${kind} to ${truncateText(path, 40)}` : `Save page as ${kind}`;
+		}
+
+		case 'extract_response': {
+			const fields = (actionAny.options ?? action) as { url_pattern?: string; variable?: string };
+			const pattern = truncateText(fields.url_pattern || '', 30);
+			const varName = fields.variable;
+			return varName ? `Extract response ${pattern} into "${varName}"` : `Extract response ${pattern}`;
+		}
+
 		case 'custom': {
 			const prompt = truncateText((action as { prompt?: string }).prompt || '', 40);
 			return `Custom: ${prompt}`;
 		}
 
+		case 'run_workflow': {
+			const workflow = (actionAny.options?.workflow as string) || '';
+			return `Run workflow "${truncateText(workflow, 40)}"`;
+		}
+
+		case 'for_each': {
+			const items = (actionAny.options?.items as string) || '';
+			const item = (actionAny.options?.item as string) || 'item';
+			const count = (actionAny.options?.steps as unknown[] | undefined)?.length || 0;
+			return `For each ${item} in "${items}" (${count} step${count !== 1 ? 's' : ''})`;
+		}
+
+		case 'repeat_until': {
+			const max = (actionAny.options?.max_iterations as number) || 10;
+			return `Repeat until condition (max ${max} times)`;
+		}
+
 		case 'go_back':
 			return 'Go back';
 
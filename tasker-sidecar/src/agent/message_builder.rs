@@ -1,15 +1,22 @@
-use crate::browser::DOMExtractionResult;
+use crate::browser::{ConsoleEntry, DOMExtractionResult, DialogInfo, HandledDialog, TabEvent};
 use crate::models::RecordedAction;
+use crate::runs::RunPlan;
 use crate::tools::Memory;
 
 /// Builds the user message for each LLM turn
 pub struct UserMessageBuilder {
     recorded_workflow: Option<Vec<RecordedAction>>,
     custom_instructions: Option<String>,
+    plan: Option<RunPlan>,
     memories: Vec<Memory>,
+    page_errors: Vec<ConsoleEntry>,
+    open_dialog: Option<DialogInfo>,
+    handled_dialogs: Vec<HandledDialog>,
+    tab_events: Vec<TabEvent>,
     url: String,
     title: String,
     elements_repr: String,
+    outline: Option<String>,
     step_number: Option<usize>,
     max_steps: Option<usize>,
 }
@@ -19,10 +26,16 @@ impl UserMessageBuilder {
         Self {
             recorded_workflow: None,
             custom_instructions: None,
+            plan: None,
             memories: Vec::new(),
+            page_errors: Vec::new(),
+            open_dialog: None,
+            handled_dialogs: Vec::new(),
+            tab_events: Vec::new(),
             url: String::new(),
             title: String::new(),
             elements_repr: String::new(),
+            outline: None,
             step_number: None,
             max_steps: None,
         }
@@ -47,17 +60,43 @@ impl UserMessageBuilder {
         self
     }
 
+    /// Set the run's plan, shown with its progress
+    pub fn with_plan(mut self, plan: Option<&RunPlan>) -> Self {
+        self.plan = plan.cloned();
+        self
+    }
+
     /// Set the memories for this run
     pub fn with_memories(mut self, memories: &[Memory]) -> Self {
         self.memories = memories.to_vec();
         self
     }
 
+    /// Set recent page errors (JS exceptions, console errors, failed loads)
+    pub fn with_page_errors(mut self, errors: &[ConsoleEntry]) -> Self {
+        self.page_errors = errors.iter().filter(|e| e.is_error()).cloned().collect();
+        self
+    }
+
+    /// Set the dialog blocking the page (if any) and dialogs auto-handled since the last turn
+    pub fn with_dialogs(mut self, open: Option<&DialogInfo>, handled: &[HandledDialog]) -> Self {
+        self.open_dialog = open.cloned();
+        self.handled_dialogs = handled.to_vec();
+        self
+    }
+
+    /// Set tabs opened/closed by the page since the last turn
+    pub fn with_tab_events(mut self, events: &[TabEvent]) -> Self {
+        self.tab_events = events.to_vec();
+        self
+    }
+
     /// Set the current browser state
     pub fn with_browser_state(mut self, url: &str, title: &str, dom_result: &DOMExtractionResult) -> Self {
         self.url = url.to_string();
         self.title = title.to_string();
         self.elements_repr = dom_result.llm_representation.clone();
+        self.outline = dom_result.outline.clone();
         self
     }
 
@@ -82,16 +121,39 @@ impl UserMessageBuilder {
             }
         }
 
+        // Add plan progress if the run has a plan
+        if let Some(ref plan) = self.plan {
+            if !plan.steps.is_empty() {
+                parts.push(plan.to_prompt());
+            }
+        }
+
         // Add memories section if present
         if !self.memories.is_empty() {
             parts.push(format_memories(&self.memories));
         }
 
+        // Add page errors digest if present
+        if !self.page_errors.is_empty() {
+            parts.push(format_page_errors(&self.page_errors));
+        }
+
+        // Add tab changes made by the page (popups, window.close())
+        if !self.tab_events.is_empty() {
+            parts.push(format_tab_events(&self.tab_events));
+        }
+
+        // Add dialog section if a dialog is open or was auto-handled
+        if self.open_dialog.is_some() || !self.handled_dialogs.is_empty() {
+            parts.push(format_dialogs(self.open_dialog.as_ref(), &self.handled_dialogs));
+        }
+
         // Add browser state (always present)
         parts.push(format_browser_state(
             &self.url,
             &self.title,
             &self.elements_repr,
+            self.outline.as_deref(),
             self.step_number,
             self.max_steps,
         ));
@@ -144,11 +206,73 @@ fn format_memories(memories: &[Memory]) -> String {
     lines.join("\n")
 }
 
-/// Format browser state with indexed elements
+/// Maximum page errors shown per turn (most recent win)
+const MAX_PAGE_ERRORS: usize = 5;
+
+/// Format recent page errors as context for the LLM
+fn format_page_errors(errors: &[ConsoleEntry]) -> String {
+    let mut lines = Vec::new();
+    lines.push("<page_errors>".to_string());
+    lines.push("Errors reported by the page since your last action:".to_string());
+
+    let skipped = errors.len().saturating_sub(MAX_PAGE_ERRORS);
+    if skipped > 0 {
+        lines.push(format!("({} earlier errors omitted)", skipped));
+    }
+    for error in errors.iter().skip(skipped) {
+        lines.push(format!("- {}", error.to_digest_line()));
+    }
+
+    lines.push("</page_errors>".to_string());
+    lines.join("\n")
+}
+
+/// Format tabs opened/closed by the page
+fn format_tab_events(events: &[TabEvent]) -> String {
+    let mut lines = Vec::new();
+    lines.push("<tabs>".to_string());
+    for event in events {
+        lines.push(format!("- {}", event.describe()));
+    }
+    lines.push("</tabs>".to_string());
+    lines.join("\n")
+}
+
+/// Format open / auto-handled native dialogs
+fn format_dialogs(open: Option<&DialogInfo>, handled: &[HandledDialog]) -> String {
+    let mut lines = Vec::new();
+    lines.push("<dialog>".to_string());
+
+    for entry in handled {
+        lines.push(format!(
+            "Auto-{} {} dialog: \"{}\"",
+            if entry.accepted { "accepted" } else { "dismissed" },
+            entry.dialog.dialog_type,
+            entry.dialog.message
+        ));
+    }
+
+    if let Some(dialog) = open {
+        lines.push(format!(
+            "A {} dialog is open and blocks the page: \"{}\"",
+            dialog.dialog_type, dialog.message
+        ));
+        if let Some(ref default_prompt) = dialog.default_prompt {
+            lines.push(format!("Default prompt text: \"{}\"", default_prompt));
+        }
+        lines.push("Call handle_dialog to accept or dismiss it before doing anything else.".to_string());
+    }
+
+    lines.push("</dialog>".to_string());
+    lines.join("\n")
+}
+
+/// Format browser state with indexed elements and the page outline, if any
 fn format_browser_state(
     url: &str,
     title: &str,
     elements_repr: &str,
+    outline: Option<&str>,
     step_number: Option<usize>,
     max_steps: Option<usize>,
 ) -> String {
@@ -156,9 +280,13 @@ fn format_browser_state(
         (Some(step), Some(max)) => format!("\nStep: {}/{}", step, max),
         _ => String::new(),
     };
+    let outline = outline
+        .filter(|o| !o.is_empty())
+        .map(|o| format!("Page Outline:\n{}\n", o))
+        .unwrap_or_default();
     format!(
-        "<browser_state>\nURL: {}\nTitle: {}{}\n\nInteractive Elements:\n{}</browser_state>",
-        url, title, step_info, elements_repr
+        "<browser_state>\nURL: {}\nTitle: {}{}\n\n{}Interactive Elements:\n{}</browser_state>",
+        url, title, step_info, outline, elements_repr
     )
 }
 
@@ -173,6 +301,83 @@ mod tests {
         assert!(msg.contains("Interactive Elements:"));
     }
 
+    #[test]
+    fn test_with_page_errors() {
+        use crate::browser::ConsoleLevel;
+
+        let entry = |seq, level, text: &str| ConsoleEntry {
+            seq,
+            level,
+            source: "console".to_string(),
+            text: text.to_string(),
+            url: Some("https://example.com/app.js".to_string()),
+            line: Some(42),
+            column: None,
+            stack: None,
+        };
+        let msg = UserMessageBuilder::new()
+            .with_page_errors(&[
+                entry(0, ConsoleLevel::Info, "loaded"),
+                entry(1, ConsoleLevel::Error, "TypeError: form is null"),
+            ])
+            .build();
+        assert!(msg.contains("<page_errors>"));
+        assert!(msg.contains("TypeError: form is null (https://example.com/app.js:42)"));
+        assert!(!msg.contains("loaded"));
+    }
+
+    #[test]
+    fn test_no_page_errors_section_without_errors() {
+        let msg = UserMessageBuilder::new().with_page_errors(&[]).build();
+        assert!(!msg.contains("<page_errors>"));
+    }
+
+    #[test]
+    fn test_with_dialogs() {
+        let dialog = |dialog_type: &str, message: &str| DialogInfo {
+            dialog_type: dialog_type.to_string(),
+            message: message.to_string(),
+            default_prompt: None,
+            url: "https://example.com".to_string(),
+        };
+        let msg = UserMessageBuilder::new()
+            .with_dialogs(
+                Some(&dialog("confirm", "Delete this item?")),
+                &[HandledDialog { dialog: dialog("alert", "Saved"), accepted: true }],
+            )
+            .build();
+        assert!(msg.contains("<dialog>"));
+        assert!(msg.contains("Auto-accepted alert dialog: \"Saved\""));
+        assert!(msg.contains("A confirm dialog is open and blocks the page: \"Delete this item?\""));
+
+        let msg = UserMessageBuilder::new().with_dialogs(None, &[]).build();
+        assert!(!msg.contains("<dialog>"));
+    }
+
+    #[test]
+    fn test_with_tab_events() {
+        let msg = UserMessageBuilder::new()
+            .with_tab_events(&[
+                TabEvent::Opened { index: 1, url: "https://accounts.example.com/oauth".to_string(), switched: true },
+                TabEvent::Closed { index: 1, active: 0 },
+            ])
+            .build();
+        assert!(msg.contains("<tabs>"));
+        assert!(msg.contains("new tab 1 (https://accounts.example.com/oauth) - it is now the active tab"));
+        assert!(msg.contains("Tab 1 was closed by the page - active tab is now 0"));
+    }
+
+    #[test]
+    fn test_with_plan() {
+        let plan = RunPlan::new(vec!["Open the portal".to_string(), "Export the report".to_string()]);
+        let msg = UserMessageBuilder::new().with_plan(Some(&plan)).build();
+        assert!(msg.contains("<plan>\nPlan progress: 0/2 steps done\n[ ] 1. Open the portal"));
+        assert!(msg.find("<plan>").unwrap() < msg.find("<browser_state>").unwrap());
+
+        let msg = UserMessageBuilder::new().with_plan(None).build();
+        assert!(!msg.contains("<plan>"));
+    }
+
     #[test]
     fn test_with_custom_instructions() {
         let msg = UserMessageBuilder::new()
@@ -181,4 +386,23 @@ mod tests {
         assert!(msg.contains("<custom_instructions>"));
         assert!(msg.contains("Search for 'rust programming'"));
     }
+
+    #[test]
+    fn test_with_page_outline() {
+        let dom_result = DOMExtractionResult {
+            llm_representation: "[1]<a @(10,10)>Home\n".to_string(),
+            outline: Some("main\n  heading \"Pricing\" level=1\n".to_string()),
+            ..Default::default()
+        };
+        let msg = UserMessageBuilder::new()
+            .with_browser_state("https://example.com", "Pricing", &dom_result)
+            .build();
+        let outline = msg.find("Page Outline:\nmain\n  heading \"Pricing\" level=1").unwrap();
+        assert!(outline < msg.find("Interactive Elements:\n[1]").unwrap());
+
+        let msg = UserMessageBuilder::new()
+            .with_browser_state("https://example.com", "Pricing", &DOMExtractionResult::default())
+            .build();
+        assert!(!msg.contains("Page Outline:"));
+    }
 }
@@ -0,0 +1,313 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::Response,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::models::{StartBatchRequest, StartReplayRequest};
+use crate::runs::batch::{
+    mask_variables, parse_dataset, results_csv, DEFAULT_BATCH_CONCURRENCY, MAX_BATCH_CONCURRENCY,
+};
+use crate::runs::{Batch, BatchRow, BatchRowStatus, BatchStatus, Redactor, RunRepository, RunStatus};
+
+use super::super::state::AppState;
+use super::replay::{launch_replay, wait_for_run};
+use super::runs::stop_run;
+
+/// Query parameters for listing batches
+#[derive(Debug, Deserialize)]
+pub struct BatchListQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+fn repository(state: &AppState) -> Result<&RunRepository, (StatusCode, String)> {
+    state.runs_repository.as_ref().ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Runs repository not initialized".to_string(),
+        )
+    })
+}
+
+/// Start a batch: one run of the workflow per dataset row
+pub async fn start_batch(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<StartBatchRequest>,
+) -> Result<Json<Batch>, (StatusCode, String)> {
+    launch_batch(&state, request).await.map(Json)
+}
+
+/// Record the batch and start working through its rows in the background.
+/// Returns the batch as created; rows run after this returns.
+pub async fn launch_batch(
+    state: &Arc<AppState>,
+    request: StartBatchRequest,
+) -> Result<Batch, (StatusCode, String)> {
+    let repo = repository(state)?;
+
+    let variable_rows = match &request.dataset {
+        Some(dataset) => parse_dataset(dataset).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?,
+        None => request.rows,
+    };
+    if variable_rows.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Batch needs a dataset or rows".to_string(),
+        ));
+    }
+
+    let concurrency = request
+        .concurrency
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+        .clamp(1, MAX_BATCH_CONCURRENCY);
+    let workflow = &request.run.workflow;
+    let batch = Batch::new(
+        Some(workflow.id.clone()),
+        Some(workflow.name.clone()),
+        concurrency,
+        variable_rows.len(),
+    );
+
+    // Rows are stored (and exported) with secrets masked - each run still gets the real values
+    let redactor = Redactor::new(request.run.secrets.clone());
+    let rows: Vec<(BatchRow, Map<String, Value>)> = variable_rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, variables)| {
+            let masked = mask_variables(&variables, &request.run.secret_variables, &redactor);
+            (BatchRow::new(&batch.id, i, masked), variables)
+        })
+        .collect();
+    let stored: Vec<BatchRow> = rows.iter().map(|(row, _)| row.clone()).collect();
+
+    repo.create_batch(&batch, &stored).map_err(|e| {
+        tracing::error!("Failed to create batch: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+
+    // Shutdown cancels the batch along with its runs
+    let cancel_token = state.shutdown_token.child_token();
+    state.active_batches.insert(batch.id.clone(), cancel_token.clone());
+
+    tracing::info!(
+        "Started batch {} for workflow {}: {} rows, {} at a time",
+        batch.id,
+        workflow.id,
+        rows.len(),
+        concurrency
+    );
+    tokio::spawn(run_batch(
+        Arc::clone(state),
+        batch.id.clone(),
+        request.run,
+        rows,
+        concurrency,
+        cancel_token,
+    ));
+
+    Ok(batch)
+}
+
+/// Start rows as slots free up, then settle the batch status
+async fn run_batch(
+    state: Arc<AppState>,
+    batch_id: String,
+    template: StartReplayRequest,
+    rows: Vec<(BatchRow, Map<String, Value>)>,
+    concurrency: usize,
+    cancel_token: CancellationToken,
+) {
+    let slots = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = JoinSet::new();
+
+    for (row, variables) in rows {
+        let permit = tokio::select! {
+            permit = Arc::clone(&slots).acquire_owned() => permit.ok(),
+            _ = cancel_token.cancelled() => None,
+        };
+        let Some(permit) = permit.filter(|_| !cancel_token.is_cancelled()) else {
+            break;
+        };
+        tasks.spawn(run_row(
+            Arc::clone(&state),
+            template.clone(),
+            row,
+            variables,
+            cancel_token.clone(),
+            permit,
+        ));
+    }
+    while tasks.join_next().await.is_some() {}
+
+    if let Some(repo) = &state.runs_repository {
+        let status = if cancel_token.is_cancelled() {
+            if let Err(e) = repo.cancel_pending_batch_rows(&batch_id) {
+                tracing::error!("Failed to cancel pending rows of batch {}: {}", batch_id, e);
+            }
+            BatchStatus::Cancelled
+        } else {
+            BatchStatus::Completed
+        };
+        if let Err(e) = repo.update_batch_status(&batch_id, status) {
+            tracing::error!("Failed to update batch {}: {}", batch_id, e);
+        }
+    }
+    state.active_batches.remove(&batch_id);
+    tracing::info!("Batch {} finished", batch_id);
+}
+
+/// Run the workflow with one row's variables and record the outcome on the row
+async fn run_row(
+    state: Arc<AppState>,
+    mut request: StartReplayRequest,
+    mut row: BatchRow,
+    variables: Map<String, Value>,
+    cancel_token: CancellationToken,
+    _permit: OwnedSemaphorePermit,
+) {
+    let Some(repo) = state.runs_repository.clone() else {
+        return;
+    };
+    let save = |row: &BatchRow| {
+        if let Err(e) = repo.update_batch_row(row) {
+            tracing::error!("Failed to update row {} of batch {}: {}", row.row_index, row.batch_id, e);
+        }
+    };
+
+    request.variables.extend(variables);
+
+    let run_id = match launch_replay(&state, request).await {
+        Ok(run_id) => run_id,
+        Err((_, error)) => {
+            row.status = BatchRowStatus::Failed;
+            row.error = Some(error);
+            save(&row);
+            return;
+        }
+    };
+    row.status = BatchRowStatus::Running;
+    row.run_id = Some(run_id.clone());
+    save(&row);
+
+    let run = tokio::select! {
+        run = wait_for_run(&state, &run_id) => run,
+        _ = cancel_token.cancelled() => {
+            if let Err(e) = stop_run(&state, &repo, &run_id) {
+                tracing::error!("Failed to cancel run {}: {}", run_id, e);
+            }
+            wait_for_run(&state, &run_id).await
+        }
+    };
+
+    match run {
+        Some(run) => {
+            row.status = match run.status {
+                RunStatus::Completed => BatchRowStatus::Completed,
+                RunStatus::Cancelled => BatchRowStatus::Cancelled,
+                _ => BatchRowStatus::Failed,
+            };
+            row.result = run.result;
+            row.error = run.error;
+        }
+        None => {
+            row.status = BatchRowStatus::Failed;
+            row.error = Some("Run record not found".to_string());
+        }
+    }
+    save(&row);
+}
+
+/// List batches, newest first
+pub async fn list_batches(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BatchListQuery>,
+) -> Result<Json<Vec<Batch>>, (StatusCode, String)> {
+    let repo = repository(&state)?;
+
+    let batches = repo.list_batches(query.limit, query.offset).map_err(|e| {
+        tracing::error!("Failed to list batches: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+
+    Ok(Json(batches))
+}
+
+/// Get a batch with its rows
+pub async fn get_batch(
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<String>,
+) -> Result<Json<Batch>, (StatusCode, String)> {
+    let repo = repository(&state)?;
+
+    let batch = repo
+        .get_batch(&batch_id, true)
+        .map_err(|e| {
+            tracing::error!("Failed to get batch: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Batch not found".to_string()))?;
+
+    Ok(Json(batch))
+}
+
+/// Cancel a batch: rows still waiting are skipped and running ones are stopped
+pub async fn cancel_batch(
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (_, token) = state
+        .active_batches
+        .remove(&batch_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Batch is not running".to_string()))?;
+    token.cancel();
+
+    tracing::info!("Cancelling batch {}", batch_id);
+
+    Ok(Json(serde_json::json!({
+        "batch_id": batch_id,
+        "status": "cancelled"
+    })))
+}
+
+/// Download the consolidated results as CSV (one line per row)
+pub async fn download_batch_results(
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<String>,
+) -> Result<Response, (StatusCode, String)> {
+    let repo = repository(&state)?;
+
+    let batch = repo
+        .get_batch(&batch_id, true)
+        .map_err(|e| {
+            tracing::error!("Failed to get batch: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Batch not found".to_string()))?;
+
+    let csv = results_csv(&batch.rows).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"batch-{}.csv\"", batch.id),
+        )
+        .body(Body::from(csv))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
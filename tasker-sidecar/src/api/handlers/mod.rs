@@ -1,3 +1,4 @@
+pub mod batches;
 pub mod files;
 pub mod health;
 pub mod providers;
@@ -53,6 +53,7 @@ pub async fn start_recording(
 
     // Create recorder
     let recorder = Arc::new(BrowserRecorder::new());
+    recorder.set_cdp_url(request.cdp_url.clone()).await;
 
     // Store recorder with initializing session BEFORE launching browser
     state.recordings.insert(
@@ -99,7 +100,7 @@ pub async fn start_recording(
                     while let Ok(step) = step_rx.recv().await {
                         let _ = ws_broadcast.send(WsEvent::RecordingStep {
                             session_id: sid_inner.clone(),
-                            step,
+                            step: Box::new(step),
                         });
                     }
                 });
@@ -7,9 +7,13 @@ use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::browser::BrowserManager;
-use crate::models::{SessionStatusResponse, StartReplayRequest, StartReplayResponse, StepResult, Viewport};
-use crate::runs::{ExecutorConfig, Run, RunEvent, RunExecutor, RunLogger, RunStatus};
+use crate::browser::{proxy, BrowserManager};
+use crate::models::{
+    describe_loops, expand_loops, ActionType, McpServerConfig, SessionStatusResponse, StartReplayRequest,
+    StartReplayResponse, StepResult, Viewport,
+};
+use crate::runs::human::DEFAULT_INPUT_TIMEOUT_SECS;
+use crate::runs::{ExecutorConfig, Redactor, Run, RunEvent, RunExecutor, RunLogger, RunStatus};
 
 use super::super::state::{AppState, WsEvent};
 
@@ -46,6 +50,20 @@ pub async fn start_replay(
     State(state): State<Arc<AppState>>,
     Json(request): Json<StartReplayRequest>,
 ) -> Result<Json<StartReplayResponse>, (StatusCode, String)> {
+    let run_id = launch_replay(&state, request).await?;
+
+    Ok(Json(StartReplayResponse {
+        session_id: run_id,
+        status: "running".to_string(),
+    }))
+}
+
+/// Create the run, launch its browser and start the agent in the background.
+/// Returns the run ID; the run continues after this returns.
+pub async fn launch_replay(
+    state: &Arc<AppState>,
+    request: StartReplayRequest,
+) -> Result<String, (StatusCode, String)> {
     tracing::info!("Starting replay with task_description: {:?}", request.task_description);
 
     let mut workflow = request.workflow.clone();
@@ -70,27 +88,55 @@ pub async fn start_replay(
         None, // custom_instructions
     );
 
-    // Add workflow steps as hints in metadata, include variables for substitution
-    let hints = serde_json::to_value(&workflow.steps).unwrap_or_default();
+    // Add workflow steps as hints in metadata, include variables for substitution.
+    // Loops over lists we already have are unrolled; the rest are described to the agent
+    let steps = expand_loops(&workflow.steps, &request.variables);
+    let hints = serde_json::to_value(&steps).unwrap_or_default();
     let variables = serde_json::to_value(&request.variables).unwrap_or_default();
     run.metadata = json!({
         "hints": hints,
+        "loops": describe_loops(&steps),
         "start_url": workflow.start_url,
         "variables": variables,
         "stop_when": request.stop_when.as_deref().or(workflow.stop_when.as_deref()),
         "max_steps": request.max_steps.or(workflow.max_steps),
+        "secret_variables": request.secret_variables,
     });
 
     let run_id = run.id.clone();
 
+    // Collect every secret value for this run so it never reaches logs, steps or the model
+    let mut secret_values = request.secrets.clone();
+    for name in &request.secret_variables {
+        match request.variables.get(name) {
+            Some(serde_json::Value::String(value)) => secret_values.push(value.clone()),
+            Some(serde_json::Value::Null) | None => {}
+            Some(value) => secret_values.push(value.to_string()),
+        }
+    }
+    // Pick this run's proxy - per-run settings override the workflow's
+    let proxy_settings = request.proxy.clone().or_else(|| workflow.metadata.proxy.clone());
+    let proxy = proxy_settings
+        .as_ref()
+        .and_then(|settings| settings.choose(state.next_proxy_turn(&workflow.id)))
+        .map(|p| proxy::normalize(&p));
+    if let Some(password) = proxy.as_ref().and_then(|p| p.password.clone()) {
+        secret_values.push(password);
+    }
+    let redactor = Redactor::new(secret_values);
+
+    // Persist a masked copy - the executor still gets the real variable values
+    let mut persisted_run = run.clone();
+    persisted_run.metadata = redactor.redact_value(&run.metadata);
+
     // Save to database
-    repo.create_run(&run).map_err(|e| {
+    repo.create_run(&persisted_run).map_err(|e| {
         tracing::error!("Failed to create run: {}", e);
         (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
     })?;
 
     // Store in active runs for tracking
-    state.active_runs.insert(run_id.clone(), run.clone());
+    state.active_runs.insert(run_id.clone(), persisted_run);
 
     // Get LLM config
     let provider = request.llm_provider.as_deref().unwrap_or("google");
@@ -107,6 +153,15 @@ pub async fn start_replay(
 
     // Create browser manager
     let browser = Arc::new(BrowserManager::new());
+    let bypass = proxy_settings.map(|settings| settings.bypass).unwrap_or_default();
+    browser.set_proxy(proxy, bypass).await;
+    browser.set_cdp_url(request.cdp_url.clone()).await;
+    browser
+        .set_launch_locale(workflow.metadata.emulation.as_ref().and_then(|e| e.locale.clone()))
+        .await;
+    if let Some(settings) = request.screenshots.clone().or(workflow.metadata.screenshots.clone()) {
+        browser.set_screenshot_settings(settings).await;
+    }
 
     // Get viewport from workflow metadata
     let viewport = workflow.metadata.browser_viewport.clone().unwrap_or(Viewport {
@@ -125,8 +180,23 @@ pub async fn start_replay(
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
 
+    // Apply the workflow's device/locale/network emulation before the first navigation
+    if let Some(emulation) = workflow.metadata.emulation.clone() {
+        if let Err(e) = browser.set_emulation(emulation).await {
+            tracing::error!("Failed to apply browser emulation: {}", e);
+            let _ = browser.close().await;
+            let _ = repo.update_run_status(&run_id, RunStatus::Failed, Some(&e.to_string()));
+            return Err((StatusCode::BAD_REQUEST, e.to_string()));
+        }
+    }
+
+    // extract_response steps read API responses - keep bodies from the first request on
+    if steps.iter().any(|s| s.action.action_type == ActionType::ExtractResponse) {
+        browser.network().enable_body_capture();
+    }
+
     // Create logger and executor
-    let logger = RunLogger::new(repo.clone());
+    let logger = RunLogger::new(repo.clone()).with_redactor(redactor);
 
     let config = ExecutorConfig {
         model: model.to_string(),
@@ -136,9 +206,26 @@ pub async fn start_replay(
         provider: Some(provider.to_string()),
         min_llm_delay_ms: 2000, // 2 seconds minimum between LLM calls
         capture_screenshots: true, // Enable screenshots by default for debugging
+        mask_password_fields: request.mask_password_fields.unwrap_or(true),
+        capture_network: request.capture_network,
+        include_page_errors: true, // JS errors often explain silently failing forms
+        dialog_policy: workflow.metadata.dialog_policy.unwrap_or_default(),
+        follow_new_tabs: request.follow_new_tabs.unwrap_or(true),
+        annotate_screenshots: request.annotate_screenshots,
+        page_representation: request.page_representation,
+        js_policy: workflow.metadata.javascript_policy.unwrap_or_default(),
+        input_timeout_secs: request.input_timeout_secs.unwrap_or(DEFAULT_INPUT_TIMEOUT_SECS),
+        on_input_timeout: request.on_input_timeout,
+        mcp_servers: McpServerConfig::merge([
+            crate::config::get_mcp_servers(),
+            workflow.metadata.mcp_servers.clone(),
+            request.mcp_servers.clone(),
+        ]),
+        planning: request.planning,
     };
 
-    let executor = RunExecutor::new(logger.clone(), Arc::clone(&browser), config);
+    let executor = RunExecutor::new(logger.clone(), Arc::clone(&browser), config)
+        .with_human_inputs(Arc::clone(&state.human_inputs));
     let cancel_token = executor.cancel_token();
 
     // Store cancel token for external cancellation
@@ -181,6 +268,18 @@ pub async fn start_replay(
                         });
                     }
                 }
+                RunEvent::Input { run_id: rid, question } => {
+                    let _ = ws_broadcast.send(WsEvent::InputRequest {
+                        session_id: rid,
+                        question: question.map(|q| serde_json::to_value(&q).unwrap_or_default()),
+                    });
+                }
+                RunEvent::Plan { run_id: rid, plan } => {
+                    let _ = ws_broadcast.send(WsEvent::PlanUpdate {
+                        session_id: rid,
+                        plan: serde_json::to_value(&plan).unwrap_or_default(),
+                    });
+                }
                 RunEvent::Log { .. } => {
                     // Logs are persisted to DB, no WebSocket broadcast needed
                 }
@@ -191,17 +290,23 @@ pub async fn start_replay(
     // Execute in background
     let run_for_exec = run.clone();
     let browser_for_cleanup = Arc::clone(&browser);
-    let state_for_cleanup = Arc::clone(&state);
+    let state_for_cleanup = Arc::clone(state);
     let run_id_for_cleanup = run_id.clone();
     let shutdown_token = state.shutdown_token.clone();
 
     tokio::spawn(async move {
+        let mut executor = executor;
+        let executor_token = executor.cancel_token();
+
         // Listen for both executor completion and global shutdown
         let result = tokio::select! {
-            res = executor.execute(&run_for_exec) => res,
+            res = async {
+                executor.connect_mcp_servers(&run_for_exec.id).await;
+                executor.execute(&run_for_exec).await
+            } => res,
             _ = shutdown_token.cancelled() => {
                 // Global shutdown - cancel the executor
-                executor.cancel();
+                executor_token.cancel();
                 // Give executor time to handle cancellation
                 tokio::time::sleep(Duration::from_millis(100)).await;
                 Err(anyhow::anyhow!("Process shutdown"))
@@ -219,14 +324,24 @@ pub async fn start_replay(
         // Remove from tracking maps
         state_for_cleanup.active_runs.remove(&run_id_for_cleanup);
         state_for_cleanup.active_executors.remove(&run_id_for_cleanup);
+        state_for_cleanup.human_inputs.cancel(&run_id_for_cleanup);
     });
 
     tracing::info!("Started run {} for workflow: {}", run_id, workflow.id);
 
-    Ok(Json(StartReplayResponse {
-        session_id: run_id,
-        status: "running".to_string(),
-    }))
+    Ok(run_id)
+}
+
+/// Wait until a run started with [`launch_replay`] has finished and return its final record
+pub async fn wait_for_run(state: &AppState, run_id: &str) -> Option<Run> {
+    let repo = state.runs_repository.as_ref()?;
+    loop {
+        let run = repo.get_run(run_id).ok()??;
+        if run.status.is_finished() && !state.active_executors.contains_key(run_id) {
+            return Some(run);
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
 }
 
 /// Stop a replay session
@@ -249,6 +364,8 @@ pub async fn stop_replay(
         tokio::time::sleep(Duration::from_millis(100)).await;
         tracing::info!("Cancelled executor for run {}", session_id);
     }
+    // End a pending ask_human wait right away instead of at its timeout
+    state.human_inputs.cancel(&session_id);
 
     // Update status to cancelled in database
     repo.update_run_status(&session_id, RunStatus::Cancelled, Some("Cancelled by user"))
@@ -6,7 +6,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::runs::{Run, RunListQuery, RunListResponse, RunStatus};
+use crate::runs::{HumanQuestion, Run, RunListQuery, RunListResponse, RunRepository, RunStatus};
 
 use super::super::state::AppState;
 
@@ -154,21 +154,89 @@ pub async fn cancel_run(
         )
     })?;
 
-    // Update status to cancelled
-    repo.update_run_status(&run_id, RunStatus::Cancelled, None)
-        .map_err(|e| {
-            tracing::error!("Failed to cancel run: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        })?;
+    stop_run(&state, repo, &run_id).map_err(|e| {
+        tracing::error!("Failed to cancel run: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "run_id": run_id,
+        "status": "cancelled"
+    })))
+}
+
+/// Mark a run cancelled and stop its executor, including a pending ask_human wait
+pub(crate) fn stop_run(state: &AppState, repo: &RunRepository, run_id: &str) -> anyhow::Result<()> {
+    repo.update_run_status(run_id, RunStatus::Cancelled, None)?;
+
+    if let Some((_, token)) = state.active_executors.remove(run_id) {
+        token.cancel();
+    }
+    state.human_inputs.cancel(run_id);
 
     // Remove from active runs
-    state.active_runs.remove(&run_id);
+    state.active_runs.remove(run_id);
 
     tracing::info!("Cancelled run {}", run_id);
+    Ok(())
+}
+
+/// Answer to a run's open question
+#[derive(Debug, Deserialize)]
+pub struct RunInputRequest {
+    /// The answer, or an optional note when handing the browser back after a take-over
+    #[serde(default)]
+    pub answer: String,
+}
+
+/// Get the question a run is waiting on
+pub async fn get_run_input(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+) -> Result<Json<HumanQuestion>, (StatusCode, String)> {
+    state
+        .human_inputs
+        .question(&run_id)
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Run {} is not waiting for input", run_id)))
+}
+
+/// Answer a run's open question (or hand the browser back) and resume it
+pub async fn submit_run_input(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+    Json(request): Json<RunInputRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if !state.human_inputs.answer(&run_id, request.answer) {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Run {} is not waiting for input", run_id),
+        ));
+    }
+
+    tracing::info!("Input submitted for run {}", run_id);
 
     Ok(Json(serde_json::json!({
         "run_id": run_id,
-        "status": "cancelled"
+        "status": "running"
+    })))
+}
+
+/// Pause a headed run before its next step so a person can use the browser
+pub async fn take_over_run(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if !state.active_executors.contains_key(&run_id) {
+        return Err((StatusCode::NOT_FOUND, format!("Run {} is not running", run_id)));
+    }
+
+    state.human_inputs.request_takeover(&run_id);
+    tracing::info!("Take-over requested for run {}", run_id);
+
+    Ok(Json(serde_json::json!({
+        "run_id": run_id,
+        "status": "take_over_requested"
     })))
 }
 
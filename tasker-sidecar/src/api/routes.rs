@@ -6,7 +6,8 @@ use axum::{
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 
-use super::handlers::{files, health, providers, recording, replay, runs, workflow};
+use super::handlers::{batches, files, health, providers, recording, replay, runs, workflow};
+use crate::mcp;
 use super::state::AppState;
 use super::websocket::ws_handler;
 
@@ -55,14 +56,33 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/runs/:run_id", get(runs::get_run))
         .route("/runs/:run_id", delete(runs::delete_run))
         .route("/runs/:run_id/cancel", post(runs::cancel_run))
+        .route("/runs/:run_id/input", get(runs::get_run_input))
+        .route("/runs/:run_id/input", post(runs::submit_run_input))
+        .route("/runs/:run_id/takeover", post(runs::take_over_run))
         .route("/runs/:run_id/steps", get(runs::get_run_steps))
         .route("/runs/:run_id/logs", get(runs::get_run_logs))
         .route("/runs/:run_id/files", get(files::list_files_for_run))
+        // Batch endpoints
+        .route("/batches", get(batches::list_batches))
+        .route("/batches", post(batches::start_batch))
+        .route("/batches/:batch_id", get(batches::get_batch))
+        .route("/batches/:batch_id/cancel", post(batches::cancel_batch))
+        .route(
+            "/batches/:batch_id/results.csv",
+            get(batches::download_batch_results),
+        )
         // Files endpoints
         .route("/files", get(files::list_files))
         .route("/files/:file_id", get(files::get_file_content))
         .route("/files/:file_id/download", get(files::download_file))
         .route("/files/:file_id", delete(files::delete_file))
+        // MCP (streamable HTTP)
+        .route(
+            "/mcp",
+            post(mcp::http::handle_post)
+                .get(mcp::http::handle_get)
+                .delete(mcp::http::handle_delete),
+        )
         // WebSocket
         .route("/ws/:client_id", get(ws_handler))
         .layer(cors)
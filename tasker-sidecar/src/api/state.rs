@@ -5,16 +5,17 @@ use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, Mutex};
 use tokio_util::sync::CancellationToken;
 
+use crate::mcp::McpSession;
 use crate::models::{RecordingSession, ReplaySession, StepResult, WorkflowStep};
 use crate::recording::BrowserRecorder;
-use crate::runs::{Run, RunRepository};
+use crate::runs::{HumanInputs, Run, RunRepository};
 
 /// WebSocket event types broadcast to clients
 #[derive(Debug, Clone)]
 pub enum WsEvent {
     RecordingStep {
         session_id: String,
-        step: WorkflowStep,
+        step: Box<WorkflowStep>,
     },
     ReplayStep {
         session_id: String,
@@ -28,6 +29,16 @@ pub enum WsEvent {
         session_id: String,
         error: String,
     },
+    /// A run is waiting for a person (None = the question was answered or dropped)
+    InputRequest {
+        session_id: String,
+        question: Option<serde_json::Value>,
+    },
+    /// A run's plan was created, updated or revised
+    PlanUpdate {
+        session_id: String,
+        plan: serde_json::Value,
+    },
     Pong,
 }
 
@@ -56,6 +67,15 @@ pub struct AppState {
     /// Cancel tokens for active executors: run_id -> token
     pub active_executors: DashMap<String, CancellationToken>,
 
+    /// Open ask_human questions and take-over requests by run ID
+    pub human_inputs: Arc<HumanInputs>,
+
+    /// Cancel tokens for running batches: batch_id -> token (children of the shutdown token)
+    pub active_batches: DashMap<String, CancellationToken>,
+
+    /// MCP sessions over streamable HTTP: session ID -> session
+    pub mcp_sessions: DashMap<String, Arc<McpSession>>,
+
     /// Global shutdown token for graceful shutdown
     pub shutdown_token: CancellationToken,
 
@@ -74,6 +94,9 @@ pub struct AppState {
     /// Global lock to prevent multiple concurrent recording starts
     /// This prevents race condition where two browser instances are launched
     pub recording_lock: Mutex<()>,
+
+    /// Runs started per workflow, used to rotate through proxy pools
+    proxy_turns: DashMap<String, usize>,
 }
 
 impl AppState {
@@ -96,15 +119,27 @@ impl AppState {
             recordings: DashMap::new(),
             active_runs: DashMap::new(),
             active_executors: DashMap::new(),
+            human_inputs: Arc::new(HumanInputs::new()),
+            active_batches: DashMap::new(),
+            mcp_sessions: DashMap::new(),
             shutdown_token: CancellationToken::new(),
             connected_clients: DashMap::new(),
             connection_count: AtomicUsize::new(0),
             runs_repository,
             ws_broadcast: tx,
             recording_lock: Mutex::new(()),
+            proxy_turns: DashMap::new(),
         }
     }
 
+    /// How many runs of this workflow already picked a proxy (increments on each call)
+    pub fn next_proxy_turn(&self, workflow_id: &str) -> usize {
+        let mut turn = self.proxy_turns.entry(workflow_id.to_string()).or_insert(0);
+        let current = *turn;
+        *turn += 1;
+        current
+    }
+
     pub fn broadcast(&self, event: WsEvent) {
         // Ignore send errors (no receivers)
         let _ = self.ws_broadcast.send(event);
@@ -174,6 +209,13 @@ impl AppState {
             entry.value().cancel();
         }
 
+        // Close MCP session browsers
+        let mcp_sessions: Vec<Arc<McpSession>> = self.mcp_sessions.iter().map(|s| Arc::clone(s.value())).collect();
+        self.mcp_sessions.clear();
+        for session in mcp_sessions {
+            session.close().await;
+        }
+
         // Wait briefly for cleanup
         if active_count > 0 {
             tokio::time::sleep(Duration::from_millis(500)).await;
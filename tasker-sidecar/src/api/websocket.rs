@@ -31,6 +31,10 @@ struct WsOutgoing {
     session: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    question: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan: Option<serde_json::Value>,
 }
 
 pub async fn ws_handler(
@@ -63,6 +67,8 @@ async fn handle_socket(socket: WebSocket, client_id: String, state: Arc<AppState
                     result: None,
                     session: None,
                     error: None,
+                    question: None,
+                    plan: None,
                 },
                 WsEvent::ReplayStep { session_id, result } => WsOutgoing {
                     msg_type: "replay_step".to_string(),
@@ -71,6 +77,8 @@ async fn handle_socket(socket: WebSocket, client_id: String, state: Arc<AppState
                     result: Some(serde_json::to_value(&result).unwrap_or_default()),
                     session: None,
                     error: None,
+                    question: None,
+                    plan: None,
                 },
                 WsEvent::ReplayComplete { session_id, session } => WsOutgoing {
                     msg_type: "replay_complete".to_string(),
@@ -79,6 +87,8 @@ async fn handle_socket(socket: WebSocket, client_id: String, state: Arc<AppState
                     result: None,
                     session: Some(serde_json::to_value(&session).unwrap_or_default()),
                     error: None,
+                    question: None,
+                    plan: None,
                 },
                 WsEvent::Error { session_id, error } => WsOutgoing {
                     msg_type: "error".to_string(),
@@ -87,6 +97,28 @@ async fn handle_socket(socket: WebSocket, client_id: String, state: Arc<AppState
                     result: None,
                     session: None,
                     error: Some(error),
+                    question: None,
+                    plan: None,
+                },
+                WsEvent::InputRequest { session_id, question } => WsOutgoing {
+                    msg_type: if question.is_some() { "input_requested" } else { "input_resolved" }.to_string(),
+                    session_id: Some(session_id),
+                    step: None,
+                    result: None,
+                    session: None,
+                    error: None,
+                    question,
+                    plan: None,
+                },
+                WsEvent::PlanUpdate { session_id, plan } => WsOutgoing {
+                    msg_type: "plan_updated".to_string(),
+                    session_id: Some(session_id),
+                    step: None,
+                    result: None,
+                    session: None,
+                    error: None,
+                    question: None,
+                    plan: Some(plan),
                 },
                 WsEvent::Pong => WsOutgoing {
                     msg_type: "pong".to_string(),
@@ -95,6 +127,8 @@ async fn handle_socket(socket: WebSocket, client_id: String, state: Arc<AppState
                     result: None,
                     session: None,
                     error: None,
+                    question: None,
+                    plan: None,
                 },
             };
 
@@ -0,0 +1,138 @@
+use image::{Rgb, RgbImage};
+
+use crate::browser::cdp_dom::SimplifiedElement;
+
+/// Box colours, cycled by element index so neighbouring boxes differ
+const PALETTE: [[u8; 3]; 8] = [
+    [230, 25, 75],
+    [60, 180, 75],
+    [0, 130, 200],
+    [245, 130, 48],
+    [145, 30, 180],
+    [0, 150, 150],
+    [240, 50, 230],
+    [128, 128, 0],
+];
+
+/// Outline thickness in pixels
+const BOX_THICKNESS: u32 = 2;
+
+/// Each glyph pixel is drawn as a GLYPH_SCALE x GLYPH_SCALE square
+const GLYPH_SCALE: u32 = 2;
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+const LABEL_PADDING: u32 = 2;
+
+/// 3x5 bitmaps for the digits 0-9, one row per entry, most significant bit on the left
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Draw a "set of marks" overlay: each element's bounding box plus its `[index]` label.
+/// `scale` converts element bounds (CSS pixels) to image pixels.
+pub fn draw_marks(img: &mut RgbImage, elements: &[SimplifiedElement], scale: f64) {
+    for element in elements {
+        let bounds = &element.bounds;
+        if bounds.width <= 0.0 || bounds.height <= 0.0 {
+            continue;
+        }
+
+        let color = Rgb(PALETTE[element.index.unsigned_abs() as usize % PALETTE.len()]);
+        let x0 = (bounds.x * scale).max(0.0) as u32;
+        let y0 = (bounds.y * scale).max(0.0) as u32;
+        let x1 = ((bounds.x + bounds.width) * scale).max(0.0) as u32;
+        let y1 = ((bounds.y + bounds.height) * scale).max(0.0) as u32;
+
+        draw_outline(img, x0, y0, x1, y1, color);
+        draw_label(img, x0, y0, &element.index.to_string(), color);
+    }
+}
+
+fn fill_rect(img: &mut RgbImage, x0: u32, y0: u32, x1: u32, y1: u32, color: Rgb<u8>) {
+    let (width, height) = img.dimensions();
+    for y in y0..y1.min(height) {
+        for x in x0..x1.min(width) {
+            img.put_pixel(x, y, color);
+        }
+    }
+}
+
+fn draw_outline(img: &mut RgbImage, x0: u32, y0: u32, x1: u32, y1: u32, color: Rgb<u8>) {
+    let t = BOX_THICKNESS;
+    fill_rect(img, x0, y0, x1, y0 + t, color);
+    fill_rect(img, x0, y1.saturating_sub(t), x1, y1, color);
+    fill_rect(img, x0, y0, x0 + t, y1, color);
+    fill_rect(img, x1.saturating_sub(t), y0, x1, y1, color);
+}
+
+/// Draw white digits on a filled tag at the box's top-left corner (above it when there's room)
+fn draw_label(img: &mut RgbImage, x: u32, y: u32, text: &str, background: Rgb<u8>) {
+    let glyph_w = GLYPH_WIDTH * GLYPH_SCALE;
+    let glyph_h = GLYPH_HEIGHT * GLYPH_SCALE;
+    let label_w = text.len() as u32 * (glyph_w + GLYPH_SCALE) - GLYPH_SCALE + 2 * LABEL_PADDING;
+    let label_h = glyph_h + 2 * LABEL_PADDING;
+
+    let top = if y >= label_h { y - label_h } else { y };
+    fill_rect(img, x, top, x + label_w, top + label_h, background);
+
+    let white = Rgb([255, 255, 255]);
+    let mut cursor = x + LABEL_PADDING;
+    for digit in text.chars().filter_map(|c| c.to_digit(10)) {
+        for (row, bits) in DIGITS[digit as usize].iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    let px = cursor + col * GLYPH_SCALE;
+                    let py = top + LABEL_PADDING + row as u32 * GLYPH_SCALE;
+                    fill_rect(img, px, py, px + GLYPH_SCALE, py + GLYPH_SCALE, white);
+                }
+            }
+        }
+        cursor += glyph_w + GLYPH_SCALE;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::cdp_dom::DOMRect;
+
+    fn element(index: i32, bounds: DOMRect) -> SimplifiedElement {
+        SimplifiedElement {
+            index,
+            tag: "button".to_string(),
+            bounds,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_draw_marks_outlines_and_labels() {
+        let mut img = RgbImage::from_pixel(200, 100, Rgb([255, 255, 255]));
+        let bounds = DOMRect { x: 20.0, y: 40.0, width: 50.0, height: 30.0 };
+        draw_marks(&mut img, &[element(1, bounds)], 2.0);
+
+        let color = Rgb(PALETTE[1]);
+        // Outline starts at the scaled corner; the inside stays untouched
+        assert_eq!(*img.get_pixel(40, 90), color);
+        assert_eq!(*img.get_pixel(100, 99), Rgb([255, 255, 255]));
+        // Label tag sits above the box
+        assert_eq!(*img.get_pixel(40, 80 - 1), color);
+    }
+
+    #[test]
+    fn test_draw_marks_clips_to_image() {
+        let mut img = RgbImage::from_pixel(50, 50, Rgb([0, 0, 0]));
+        let bounds = DOMRect { x: 30.0, y: -10.0, width: 100.0, height: 100.0 };
+        // Must not panic on boxes hanging off the edges
+        draw_marks(&mut img, &[element(123, bounds)], 1.0);
+    }
+}
@@ -32,7 +32,10 @@ fn build_ax_lookup(ax_nodes: &Option<Vec<serde_json::Value>>) -> HashMap<Backend
 
 /// Parse an AX node from JSON
 fn parse_ax_node(node: &serde_json::Value) -> AXNodeData {
-    let mut data = AXNodeData::default();
+    let mut data = AXNodeData {
+        ignored: node.get("ignored").and_then(|v| v.as_bool()).unwrap_or(false),
+        ..Default::default()
+    };
 
     // Role
     if let Some(role) = node.get("role").and_then(|r| r.get("value")).and_then(|v| v.as_str()) {
@@ -69,6 +72,7 @@ fn parse_ax_node(node: &serde_json::Value) -> AXNodeData {
                 "focused" => data.focused = value.and_then(|v| v.as_bool()).unwrap_or(false),
                 "required" => data.required = value.and_then(|v| v.as_bool()).unwrap_or(false),
                 "readonly" => data.readonly = value.and_then(|v| v.as_bool()).unwrap_or(false),
+                "level" => data.level = value.and_then(|v| v.as_i64()),
                 _ => {}
             }
         }
@@ -13,7 +13,7 @@ pub fn filter_by_paint_order(tree: &mut EnhancedDOMNode, viewport: &DOMRect) {
     }
 
     // Sort by paint order descending (highest = on top)
-    interactive_elements.sort_by(|a, b| b.2.cmp(&a.2));
+    interactive_elements.sort_by_key(|e| std::cmp::Reverse(e.2));
 
     // Find obscured elements
     let mut obscured: HashSet<BackendNodeId> = HashSet::new();
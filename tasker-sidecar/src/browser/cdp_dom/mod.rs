@@ -1,6 +1,7 @@
 mod builder;
 mod extractor;
 mod filter;
+mod outline;
 mod serializer;
 pub mod types;
 
@@ -12,8 +13,9 @@ pub use types::{
     BackendNodeId, DOMExtractionResult, DOMRect, ElementIndex, SelectorMap, SimplifiedElement,
 };
 
-/// Extract DOM using CDP and return structured result for LLM
-pub async fn extract_dom(page: &Page) -> Result<DOMExtractionResult> {
+/// Extract DOM using CDP and return structured result for LLM.
+/// With `include_outline`, also serialize the whole page as an accessibility outline.
+pub async fn extract_dom(page: &Page, include_outline: bool) -> Result<DOMExtractionResult> {
     // Get page info
     let (url, title) = extractor::get_page_info(page).await?;
     tracing::debug!("DOM extraction: url={}, title={}", url, title);
@@ -26,6 +28,9 @@ pub async fn extract_dom(page: &Page) -> Result<DOMExtractionResult> {
     // Build enhanced tree
     let tree = builder::build_enhanced_tree(raw_trees);
 
+    // The filters below drop off-screen content, the outline covers the whole page
+    let outline_tree = tree.as_ref().filter(|_| include_outline).cloned();
+
     let mut selector_map = if let Some(mut tree) = tree {
         tracing::debug!("DOM extraction: tree built successfully");
 
@@ -51,10 +56,12 @@ pub async fn extract_dom(page: &Page) -> Result<DOMExtractionResult> {
 
     // Format for LLM
     let llm_representation = serializer::format_for_llm(&selector_map);
+    let outline = outline_tree.map(|tree| outline::format_outline(&tree, &selector_map));
 
     Ok(DOMExtractionResult {
         selector_map,
         llm_representation,
+        outline,
         viewport,
         url,
         title,
@@ -121,12 +128,10 @@ async fn resolve_bounds_via_cdp(
         }
 
         // Check if element is in viewport
-        let in_viewport = elem.bounds.y < viewport.height
+        elem.bounds.y < viewport.height
             && elem.bounds.y + elem.bounds.height > 0.0
             && elem.bounds.x < viewport.width
-            && elem.bounds.x + elem.bounds.width > 0.0;
-
-        in_viewport
+            && elem.bounds.x + elem.bounds.width > 0.0
     });
 
     let after_count = selector_map.ordered_elements.len();
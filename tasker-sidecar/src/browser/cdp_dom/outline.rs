@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use super::types::{BackendNodeId, ElementIndex, EnhancedDOMNode, SelectorMap};
+
+/// Maximum characters for the page outline
+const MAX_OUTLINE_CHARS: usize = 30000;
+
+/// Maximum characters of an accessible name
+const MAX_NAME_CHARS: usize = 200;
+
+/// Roles rendered as a single line - their accessible name sums up their content
+const LEAF_ROLES: &[&str] = &[
+    "heading", "link", "button", "image", "img", "textbox", "searchbox", "checkbox", "radio",
+    "combobox", "listbox", "slider", "spinbutton", "switch", "tab", "menuitem",
+    "menuitemcheckbox", "menuitemradio", "option", "progressbar", "meter",
+];
+
+/// Roles that give the page its structure
+const CONTAINER_ROLES: &[&str] = &[
+    "banner", "navigation", "main", "complementary", "contentinfo", "search", "form", "region",
+    "article", "dialog", "alertdialog", "alert", "list", "table", "grid", "treegrid", "figure",
+    "blockquote", "tablist", "tabpanel", "menu", "menubar", "tree", "group",
+];
+
+/// Container roles whose content is usually a run of text, collapsed onto one line
+const INLINE_ROLES: &[&str] = &[
+    "paragraph", "listitem", "cell", "gridcell", "columnheader", "rowheader", "term",
+    "definition", "treeitem", "caption",
+];
+
+/// A line of the outline, before indentation
+#[derive(Debug)]
+enum Line {
+    Text(String),
+    Node { label: String, children: Vec<Line> },
+}
+
+impl Line {
+    fn is_flat(&self) -> bool {
+        match self {
+            Line::Text(_) => true,
+            Line::Node { children, .. } => children.is_empty(),
+        }
+    }
+
+    /// Content as a single line of text
+    fn flatten(&self, out: &mut Vec<String>) {
+        match self {
+            Line::Text(text) => out.push(text.clone()),
+            Line::Node { label, children } => {
+                out.push(label.clone());
+                for child in children {
+                    child.flatten(out);
+                }
+            }
+        }
+    }
+}
+
+struct OutlineBuilder {
+    /// Element indices of the interactive elements, so outline entries can be acted on
+    indices: HashMap<BackendNodeId, ElementIndex>,
+    /// Without layout data every text node would look unrendered
+    has_layout: bool,
+}
+
+/// Format the page as an accessibility outline: landmarks, headings, lists, tables
+/// and text with their roles and names. Entries that are also interactive elements
+/// carry their `[index]`.
+pub fn format_outline(tree: &EnhancedDOMNode, selector_map: &SelectorMap) -> String {
+    let builder = OutlineBuilder {
+        indices: selector_map
+            .ordered_elements
+            .iter()
+            .map(|e| (e.backend_node_id, e.index))
+            .collect(),
+        has_layout: has_layout(tree),
+    };
+
+    let mut lines = Vec::new();
+    let mut text = Vec::new();
+    builder.collect(tree, &mut lines, &mut text);
+    flush_text(&mut lines, &mut text);
+
+    let mut output = String::new();
+    let mut truncated = false;
+    for line in &lines {
+        if !write_line(line, 0, &mut output) {
+            truncated = true;
+            break;
+        }
+    }
+    if truncated {
+        output.push_str("... truncated (use extract_content or scroll for the rest)\n");
+        tracing::warn!("Page outline truncated at {} chars", output.len());
+    }
+    output
+}
+
+impl OutlineBuilder {
+    /// Append the lines for `node` and its subtree. Text of transparent nodes
+    /// (generic divs, spans, ...) accumulates in `text` until the next entry.
+    fn collect(&self, node: &EnhancedDOMNode, lines: &mut Vec<Line>, text: &mut Vec<String>) {
+        if node.node_type == 3 {
+            if let Some(t) = node.text_content.as_deref().filter(|_| self.is_rendered(node)) {
+                text.push(normalize(t));
+            }
+            return;
+        }
+        if node.attributes.get("aria-hidden").map(String::as_str) == Some("true") {
+            return;
+        }
+
+        let role = role_of(node);
+        match role.as_deref() {
+            Some(role) if LEAF_ROLES.contains(&role) => {
+                if let Some(line) = self.leaf(node, role) {
+                    flush_text(lines, text);
+                    lines.push(line);
+                }
+            }
+            Some("row") => {
+                let cells: Vec<String> = node
+                    .children
+                    .iter()
+                    .map(|cell| self.flatten(cell))
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                if !cells.is_empty() {
+                    flush_text(lines, text);
+                    lines.push(Line::Node { label: format!("row: {}", cells.join(" | ")), children: Vec::new() });
+                }
+            }
+            Some(role) if INLINE_ROLES.contains(&role) || CONTAINER_ROLES.contains(&role) => {
+                let children = self.children(node);
+                let name = accessible_name(node).filter(|_| !INLINE_ROLES.contains(&role));
+                if children.is_empty() && name.is_none() {
+                    return;
+                }
+                flush_text(lines, text);
+
+                let mut label = role.to_string();
+                if let Some(name) = name {
+                    label.push_str(&format!(" \"{}\"", name));
+                }
+                if INLINE_ROLES.contains(&role) && children.iter().all(Line::is_flat) {
+                    let mut parts = Vec::new();
+                    for child in &children {
+                        child.flatten(&mut parts);
+                    }
+                    lines.push(Line::Node { label: format!("{}: {}", label, parts.join(" ")), children: Vec::new() });
+                } else {
+                    lines.push(Line::Node { label, children });
+                }
+            }
+            _ => {
+                for child in &node.children {
+                    self.collect(child, lines, text);
+                }
+            }
+        }
+    }
+
+    /// Lines for the subtree below `node`
+    fn children(&self, node: &EnhancedDOMNode) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut text = Vec::new();
+        for child in &node.children {
+            self.collect(child, &mut lines, &mut text);
+        }
+        flush_text(&mut lines, &mut text);
+        lines
+    }
+
+    /// Content of `node` as one line of text
+    fn flatten(&self, node: &EnhancedDOMNode) -> String {
+        let mut parts = Vec::new();
+        for line in &self.children(node) {
+            line.flatten(&mut parts);
+        }
+        parts.join(" ")
+    }
+
+    fn leaf(&self, node: &EnhancedDOMNode, role: &str) -> Option<Line> {
+        let mut indices = Vec::new();
+        self.collect_indices(node, &mut indices);
+        indices.sort_unstable();
+
+        let name = accessible_name(node).or_else(|| {
+            let text = self.flatten(node);
+            (!text.is_empty()).then(|| truncate(&text))
+        });
+        if name.is_none() && indices.is_empty() {
+            return None;
+        }
+
+        let mut label = role.to_string();
+        if let Some(name) = name {
+            label.push_str(&format!(" \"{}\"", name));
+        }
+        if role == "heading" {
+            if let Some(level) = heading_level(node) {
+                label.push_str(&format!(" level={}", level));
+            }
+        }
+        if let Some(ax) = &node.ax_data {
+            if let Some(value) = ax.value.as_deref().filter(|v| !v.is_empty()) {
+                label.push_str(&format!(" value=\"{}\"", truncate(&normalize(value))));
+            }
+            if ax.checked == Some(true) {
+                label.push_str(" checked");
+            }
+            if ax.selected == Some(true) {
+                label.push_str(" selected");
+            }
+            if ax.disabled == Some(true) {
+                label.push_str(" disabled");
+            }
+            if let Some(expanded) = ax.expanded {
+                label.push_str(if expanded { " expanded" } else { " collapsed" });
+            }
+        }
+        for index in indices {
+            label.push_str(&format!(" [{}]", index));
+        }
+        Some(Line::Node { label, children: Vec::new() })
+    }
+
+    fn collect_indices(&self, node: &EnhancedDOMNode, indices: &mut Vec<ElementIndex>) {
+        if let Some(index) = self.indices.get(&node.backend_node_id) {
+            indices.push(*index);
+        }
+        for child in &node.children {
+            self.collect_indices(child, indices);
+        }
+    }
+
+    /// Text nodes inside `display: none` subtrees have no layout object
+    fn is_rendered(&self, node: &EnhancedDOMNode) -> bool {
+        if !self.has_layout {
+            return true;
+        }
+        match &node.layout {
+            Some(layout) => layout.visibility.as_deref() != Some("hidden"),
+            None => false,
+        }
+    }
+}
+
+/// Role from the accessibility tree, or implied by the tag when the AX tree is unavailable
+fn role_of(node: &EnhancedDOMNode) -> Option<String> {
+    if let Some(ax) = &node.ax_data {
+        if ax.ignored {
+            return None;
+        }
+        return ax.role.as_ref().map(|r| r.to_ascii_lowercase());
+    }
+
+    let role = match node.tag_name.as_str() {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "a" if node.attributes.contains_key("href") => "link",
+        "button" => "button",
+        "img" => "img",
+        "input" | "textarea" => "textbox",
+        "select" => "combobox",
+        "ul" | "ol" => "list",
+        "li" => "listitem",
+        "p" => "paragraph",
+        "table" => "table",
+        "tr" => "row",
+        "td" => "cell",
+        "th" => "columnheader",
+        "nav" => "navigation",
+        "main" => "main",
+        "form" => "form",
+        "article" => "article",
+        "blockquote" => "blockquote",
+        _ => return None,
+    };
+    Some(role.to_string())
+}
+
+fn accessible_name(node: &EnhancedDOMNode) -> Option<String> {
+    let name = node.ax_data.as_ref()?.name.as_deref()?;
+    let name = normalize(name);
+    (!name.is_empty()).then(|| truncate(&name))
+}
+
+fn heading_level(node: &EnhancedDOMNode) -> Option<i64> {
+    node.ax_data
+        .as_ref()
+        .and_then(|ax| ax.level)
+        .or_else(|| node.tag_name.strip_prefix('h')?.parse().ok())
+}
+
+fn has_layout(node: &EnhancedDOMNode) -> bool {
+    node.layout.is_some() || node.children.iter().any(has_layout)
+}
+
+fn flush_text(lines: &mut Vec<Line>, text: &mut Vec<String>) {
+    let joined = text.join(" ");
+    text.clear();
+    if !joined.trim().is_empty() {
+        lines.push(Line::Text(joined));
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_NAME_CHARS {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(MAX_NAME_CHARS).collect();
+    short.push_str("...");
+    short
+}
+
+/// Write a line and its children; false once the character limit is reached
+fn write_line(line: &Line, depth: usize, output: &mut String) -> bool {
+    let indent = "  ".repeat(depth);
+    let text = match line {
+        Line::Text(text) => format!("{}text: {}\n", indent, text),
+        Line::Node { label, .. } => format!("{}{}\n", indent, label),
+    };
+    if output.len() + text.len() > MAX_OUTLINE_CHARS {
+        return false;
+    }
+    output.push_str(&text);
+
+    if let Line::Node { children, .. } = line {
+        for child in children {
+            if !write_line(child, depth + 1, output) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::cdp_dom::types::{AXNodeData, SimplifiedElement};
+
+    fn element(id: BackendNodeId, tag: &str, role: &str, name: &str, children: Vec<EnhancedDOMNode>) -> EnhancedDOMNode {
+        EnhancedDOMNode {
+            backend_node_id: id,
+            tag_name: tag.to_string(),
+            ax_data: Some(AXNodeData {
+                role: Some(role.to_string()),
+                name: (!name.is_empty()).then(|| name.to_string()),
+                ..Default::default()
+            }),
+            children,
+            ..Default::default()
+        }
+    }
+
+    fn text(content: &str) -> EnhancedDOMNode {
+        EnhancedDOMNode {
+            node_type: 3,
+            text_content: Some(content.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_outline() {
+        let tree = element(1, "body", "RootWebArea", "Docs", vec![
+            element(2, "nav", "navigation", "Main", vec![
+                element(3, "ul", "list", "", vec![
+                    element(4, "li", "listitem", "", vec![
+                        element(5, "a", "link", "Home", vec![text("Home")]),
+                    ]),
+                ]),
+            ]),
+            element(6, "main", "main", "", vec![
+                element(7, "h1", "heading", "Getting started", vec![text("Getting started")]),
+                element(8, "div", "generic", "", vec![text("Install the"), text("CLI first.")]),
+                element(9, "table", "table", "Prices", vec![
+                    element(10, "tr", "row", "", vec![
+                        element(11, "td", "cell", "", vec![text("Pro")]),
+                        element(12, "td", "cell", "", vec![text("$10")]),
+                    ]),
+                ]),
+            ]),
+        ]);
+        let mut selector_map = SelectorMap::new();
+        selector_map.ordered_elements.push(SimplifiedElement {
+            index: 1,
+            backend_node_id: 5,
+            ..Default::default()
+        });
+
+        let outline = format_outline(&tree, &selector_map);
+        assert_eq!(
+            outline,
+            "navigation \"Main\"\n\
+             \x20 list\n\
+             \x20   listitem: link \"Home\" [1]\n\
+             main\n\
+             \x20 heading \"Getting started\" level=1\n\
+             \x20 text: Install the CLI first.\n\
+             \x20 table \"Prices\"\n\
+             \x20   row: Pro | $10\n"
+        );
+    }
+}
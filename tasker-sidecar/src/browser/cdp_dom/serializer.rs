@@ -232,9 +232,8 @@ const MAX_LLM_OUTPUT_CHARS: usize = 40000;
 /// Format selector map for LLM context
 pub fn format_for_llm(selector_map: &SelectorMap) -> String {
     let mut output = String::new();
-    let mut elements_added = 0;
 
-    for elem in &selector_map.ordered_elements {
+    for (elements_added, elem) in selector_map.ordered_elements.iter().enumerate() {
         let mut attrs: Vec<String> = Vec::new();
 
         // Type (skip default "text" for inputs)
@@ -368,7 +367,6 @@ pub fn format_for_llm(selector_map: &SelectorMap) -> String {
         }
 
         output.push_str(&line);
-        elements_added += 1;
     }
 
     output
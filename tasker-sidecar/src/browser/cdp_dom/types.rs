@@ -75,6 +75,10 @@ pub struct AXNodeData {
     pub focused: bool,
     pub required: bool,
     pub readonly: bool,
+    /// Heading level (1-6) for headings, nesting level for tree items
+    pub level: Option<i64>,
+    /// Excluded from the accessibility tree (hidden, presentational, ...)
+    pub ignored: bool,
 }
 
 /// Layout/snapshot data for an element
@@ -295,6 +299,8 @@ impl SelectorMap {
 pub struct DOMExtractionResult {
     pub selector_map: SelectorMap,
     pub llm_representation: String,
+    /// Accessibility outline of the whole page, when requested
+    pub outline: Option<String>,
     pub viewport: DOMRect,
     pub url: String,
     pub title: String,
@@ -305,6 +311,7 @@ impl Default for DOMExtractionResult {
         Self {
             selector_map: SelectorMap::new(),
             llm_representation: String::new(),
+            outline: None,
             viewport: DOMRect::default_viewport(),
             url: String::new(),
             title: String::new(),
@@ -0,0 +1,309 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::log::{
+    EnableParams as LogEnableParams, EventEntryAdded, LogEntryLevel,
+};
+use chromiumoxide::cdp::js_protocol::runtime::{
+    ConsoleApiCalledType, EnableParams as RuntimeEnableParams, EventConsoleApiCalled,
+    EventExceptionThrown, RemoteObject, StackTrace,
+};
+use chromiumoxide::Page;
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of console entries kept per run (oldest are dropped first)
+const MAX_ENTRIES: usize = 500;
+
+/// Maximum length of a single console message
+const MAX_TEXT_CHARS: usize = 2000;
+
+/// Severity of a console entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl ConsoleLevel {
+    /// Level of a console.* call (console.assert failures count as errors)
+    fn from_console_call(call: &ConsoleApiCalledType) -> Self {
+        match call {
+            ConsoleApiCalledType::Error | ConsoleApiCalledType::Assert => ConsoleLevel::Error,
+            ConsoleApiCalledType::Warning => ConsoleLevel::Warning,
+            ConsoleApiCalledType::Debug | ConsoleApiCalledType::Trace => ConsoleLevel::Debug,
+            _ => ConsoleLevel::Info,
+        }
+    }
+
+    /// Level of a browser log entry
+    fn from_log_entry(level: &LogEntryLevel) -> Self {
+        match level {
+            LogEntryLevel::Error => ConsoleLevel::Error,
+            LogEntryLevel::Warning => ConsoleLevel::Warning,
+            LogEntryLevel::Info => ConsoleLevel::Info,
+            LogEntryLevel::Verbose => ConsoleLevel::Debug,
+        }
+    }
+}
+
+/// A console message, uncaught exception or browser log entry
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleEntry {
+    /// Capture order - increases monotonically for the lifetime of the recorder
+    pub seq: u64,
+    pub level: ConsoleLevel,
+    /// "console", "exception", or the browser log source ("network", "security", ...)
+    pub source: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+}
+
+impl ConsoleEntry {
+    /// Whether this entry is an error (console.error, uncaught exception, failed load)
+    pub fn is_error(&self) -> bool {
+        self.level == ConsoleLevel::Error
+    }
+
+    /// Metadata for persisting as a RunLog
+    pub fn metadata(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// Short one-line form for the LLM page state
+    pub fn to_digest_line(&self) -> String {
+        let location = match (&self.url, self.line) {
+            (Some(url), Some(line)) if !url.is_empty() => format!(" ({}:{})", url, line),
+            (Some(url), None) if !url.is_empty() => format!(" ({})", url),
+            _ => String::new(),
+        };
+        let text: String = self.text.chars().take(200).collect();
+        format!("[{}] {}{}", self.source, text, location)
+    }
+}
+
+#[derive(Default)]
+struct ConsoleState {
+    entries: VecDeque<ConsoleEntry>,
+    next_seq: u64,
+}
+
+/// Records console output, uncaught exceptions and browser log entries via CDP
+#[derive(Clone, Default)]
+pub struct ConsoleRecorder {
+    state: Arc<Mutex<ConsoleState>>,
+}
+
+impl ConsoleRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable Runtime/Log domains on a page and start recording
+    pub async fn attach(&self, page: &Page) -> Result<()> {
+        page.execute(RuntimeEnableParams::default())
+            .await
+            .map_err(|e| anyhow!("Failed to enable runtime domain: {}", e))?;
+        page.execute(LogEnableParams::default())
+            .await
+            .map_err(|e| anyhow!("Failed to enable log domain: {}", e))?;
+
+        let mut console = page.event_listener::<EventConsoleApiCalled>().await
+            .map_err(|e| anyhow!("Failed to create console listener: {}", e))?;
+        let mut exceptions = page.event_listener::<EventExceptionThrown>().await
+            .map_err(|e| anyhow!("Failed to create exception listener: {}", e))?;
+        let mut log_entries = page.event_listener::<EventEntryAdded>().await
+            .map_err(|e| anyhow!("Failed to create log listener: {}", e))?;
+
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = console.next().await {
+                let level = ConsoleLevel::from_console_call(&event.r#type);
+                let text = event.args.iter().map(remote_object_text).collect::<Vec<_>>().join(" ");
+                let top_frame = event.stack_trace.as_ref().and_then(|s| s.call_frames.first());
+                recorder.push(ConsoleEntry {
+                    seq: 0,
+                    level,
+                    source: "console".to_string(),
+                    text,
+                    url: top_frame.map(|f| f.url.clone()),
+                    line: top_frame.map(|f| f.line_number + 1),
+                    column: top_frame.map(|f| f.column_number + 1),
+                    stack: event.stack_trace.as_ref().map(format_stack),
+                });
+            }
+        });
+
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = exceptions.next().await {
+                let details = &event.exception_details;
+                // `text` is usually just "Uncaught" - the description carries the message
+                let text = details
+                    .exception
+                    .as_ref()
+                    .and_then(|e| e.description.clone())
+                    .map(|d| d.lines().next().unwrap_or_default().to_string())
+                    .map(|d| format!("{} {}", details.text, d))
+                    .unwrap_or_else(|| details.text.clone());
+                recorder.push(ConsoleEntry {
+                    seq: 0,
+                    level: ConsoleLevel::Error,
+                    source: "exception".to_string(),
+                    text,
+                    url: details.url.clone(),
+                    line: Some(details.line_number + 1),
+                    column: Some(details.column_number + 1),
+                    stack: details.stack_trace.as_ref().map(format_stack),
+                });
+            }
+        });
+
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = log_entries.next().await {
+                let entry = &event.entry;
+                recorder.push(ConsoleEntry {
+                    seq: 0,
+                    level: ConsoleLevel::from_log_entry(&entry.level),
+                    source: entry.source.as_ref().to_string(),
+                    text: entry.text.clone(),
+                    url: entry.url.clone(),
+                    line: entry.line_number.map(|l| l + 1),
+                    column: None,
+                    stack: entry.stack_trace.as_ref().map(format_stack),
+                });
+            }
+        });
+
+        tracing::debug!("Console recorder attached to page");
+        Ok(())
+    }
+
+    fn push(&self, mut entry: ConsoleEntry) {
+        if entry.text.chars().count() > MAX_TEXT_CHARS {
+            entry.text = format!("{}...", entry.text.chars().take(MAX_TEXT_CHARS).collect::<String>());
+        }
+
+        let mut state = self.state.lock().unwrap();
+        entry.seq = state.next_seq;
+        state.next_seq += 1;
+        if state.entries.len() >= MAX_ENTRIES {
+            state.entries.pop_front();
+        }
+        state.entries.push_back(entry);
+    }
+
+    /// Sequence number the next entry will get
+    pub fn next_seq(&self) -> u64 {
+        self.state.lock().unwrap().next_seq
+    }
+
+    /// Entries captured since the given sequence number, oldest first, and the sequence
+    /// number to pass next time. Read under one lock, so no entry is missed or repeated.
+    pub fn since(&self, seq: u64) -> (Vec<ConsoleEntry>, u64) {
+        let state = self.state.lock().unwrap();
+        let entries = state.entries.iter().filter(|e| e.seq >= seq).cloned().collect();
+        (entries, state.next_seq)
+    }
+}
+
+/// Render a console argument the way DevTools would (roughly)
+fn remote_object_text(obj: &RemoteObject) -> String {
+    match &obj.value {
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+        None => obj
+            .description
+            .clone()
+            .or_else(|| obj.unserializable_value.as_ref().map(|u| u.inner().clone()))
+            .unwrap_or_else(|| obj.r#type.as_ref().to_string()),
+    }
+}
+
+fn format_stack(stack: &StackTrace) -> String {
+    stack
+        .call_frames
+        .iter()
+        .take(10)
+        .map(|f| {
+            let name = if f.function_name.is_empty() { "<anonymous>" } else { &f.function_name };
+            format!("at {} ({}:{}:{})", name, f.url, f.line_number + 1, f.column_number + 1)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str) -> ConsoleEntry {
+        ConsoleEntry {
+            seq: 0,
+            level: ConsoleLevel::Info,
+            source: "console".to_string(),
+            text: text.to_string(),
+            url: None,
+            line: None,
+            column: None,
+            stack: None,
+        }
+    }
+
+    #[test]
+    fn test_level_mapping() {
+        assert_eq!(ConsoleLevel::from_console_call(&ConsoleApiCalledType::Error), ConsoleLevel::Error);
+        assert_eq!(ConsoleLevel::from_console_call(&ConsoleApiCalledType::Assert), ConsoleLevel::Error);
+        assert_eq!(ConsoleLevel::from_console_call(&ConsoleApiCalledType::Warning), ConsoleLevel::Warning);
+        assert_eq!(ConsoleLevel::from_console_call(&ConsoleApiCalledType::Trace), ConsoleLevel::Debug);
+        assert_eq!(ConsoleLevel::from_console_call(&ConsoleApiCalledType::Log), ConsoleLevel::Info);
+        assert_eq!(ConsoleLevel::from_console_call(&ConsoleApiCalledType::Table), ConsoleLevel::Info);
+
+        assert_eq!(ConsoleLevel::from_log_entry(&LogEntryLevel::Error), ConsoleLevel::Error);
+        assert_eq!(ConsoleLevel::from_log_entry(&LogEntryLevel::Warning), ConsoleLevel::Warning);
+        assert_eq!(ConsoleLevel::from_log_entry(&LogEntryLevel::Verbose), ConsoleLevel::Debug);
+    }
+
+    #[test]
+    fn test_long_messages_are_truncated() {
+        let recorder = ConsoleRecorder::new();
+        recorder.push(entry(&"ü".repeat(MAX_TEXT_CHARS + 10)));
+        recorder.push(entry("short"));
+
+        let (entries, _) = recorder.since(0);
+        assert_eq!(entries[0].text.chars().count(), MAX_TEXT_CHARS + 3);
+        assert!(entries[0].text.ends_with("ü..."));
+        assert_eq!(entries[1].text, "short");
+    }
+
+    #[test]
+    fn test_buffer_cap_and_cursor() {
+        let recorder = ConsoleRecorder::new();
+        for i in 0..MAX_ENTRIES + 5 {
+            recorder.push(entry(&i.to_string()));
+        }
+
+        let (entries, next) = recorder.since(0);
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].text, "5");
+        assert_eq!(next, (MAX_ENTRIES + 5) as u64);
+
+        // Reading from the returned cursor yields only what came after
+        recorder.push(entry("later"));
+        let (entries, next) = recorder.since(next);
+        assert_eq!(entries.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(), ["later"]);
+        assert!(recorder.since(next).0.is_empty());
+    }
+}
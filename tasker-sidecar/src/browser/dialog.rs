@@ -0,0 +1,234 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::page::{
+    DialogType, EventJavascriptDialogClosed, EventJavascriptDialogOpening, HandleJavaScriptDialogParams,
+};
+use chromiumoxide::Page;
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+use crate::models::DialogPolicy;
+
+/// A native JavaScript dialog (alert/confirm/prompt/beforeunload)
+#[derive(Debug, Clone, Serialize)]
+pub struct DialogInfo {
+    /// "alert", "confirm", "prompt" or "beforeunload"
+    pub dialog_type: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_prompt: Option<String>,
+    /// URL of the frame that opened the dialog
+    pub url: String,
+}
+
+impl DialogInfo {
+    fn from_event(event: &EventJavascriptDialogOpening) -> Self {
+        let dialog_type = match event.r#type {
+            DialogType::Alert => "alert",
+            DialogType::Confirm => "confirm",
+            DialogType::Prompt => "prompt",
+            DialogType::Beforeunload => "beforeunload",
+        };
+        Self {
+            dialog_type: dialog_type.to_string(),
+            message: event.message.clone(),
+            default_prompt: event.default_prompt.clone().filter(|p| !p.is_empty()),
+            url: event.url.clone(),
+        }
+    }
+}
+
+/// How the policy answers a dialog on its own (None = leave it open for the agent).
+/// Accepted prompts get their default text.
+fn auto_answer(policy: DialogPolicy, dialog: &DialogInfo) -> Option<HandleJavaScriptDialogParams> {
+    let accept = match policy {
+        DialogPolicy::Accept => true,
+        DialogPolicy::Dismiss => false,
+        DialogPolicy::Manual => return None,
+    };
+    Some(HandleJavaScriptDialogParams {
+        accept,
+        prompt_text: dialog.default_prompt.clone(),
+    })
+}
+
+/// A dialog that was answered automatically by the dialog policy
+#[derive(Debug, Clone, Serialize)]
+pub struct HandledDialog {
+    pub dialog: DialogInfo,
+    pub accepted: bool,
+}
+
+#[derive(Default)]
+struct DialogState {
+    policy: DialogPolicy,
+    /// Dialog currently blocking a page, and the page it belongs to
+    open: Option<(DialogInfo, Page)>,
+    /// Dialogs auto-handled since the last `take_auto_handled`
+    auto_handled: Vec<HandledDialog>,
+}
+
+/// Watches for native JS dialogs and either answers them per policy or holds them for the agent
+#[derive(Clone, Default)]
+pub struct DialogWatcher {
+    state: Arc<Mutex<DialogState>>,
+    /// Signalled when a dialog opens that needs a manual answer
+    opened: Arc<Notify>,
+}
+
+impl DialogWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how newly opened dialogs are handled
+    pub fn set_policy(&self, policy: DialogPolicy) {
+        self.state.lock().unwrap().policy = policy;
+    }
+
+    /// Start listening for dialogs on a page
+    pub async fn attach(&self, page: &Page) -> Result<()> {
+        let mut opening = page.event_listener::<EventJavascriptDialogOpening>().await
+            .map_err(|e| anyhow!("Failed to create dialog listener: {}", e))?;
+        let mut closed = page.event_listener::<EventJavascriptDialogClosed>().await
+            .map_err(|e| anyhow!("Failed to create dialog listener: {}", e))?;
+
+        let watcher = self.clone();
+        let dialog_page = page.clone();
+        tokio::spawn(async move {
+            while let Some(event) = opening.next().await {
+                let dialog = DialogInfo::from_event(&event);
+                let policy = watcher.state.lock().unwrap().policy;
+
+                let Some(params) = auto_answer(policy, &dialog) else {
+                    tracing::info!("{} dialog opened: {}", dialog.dialog_type, dialog.message);
+                    watcher.state.lock().unwrap().open = Some((dialog, dialog_page.clone()));
+                    watcher.opened.notify_waiters();
+                    continue;
+                };
+
+                let accept = params.accept;
+                match dialog_page.execute(params).await {
+                    Ok(_) => {
+                        tracing::info!(
+                            "Auto-{} {} dialog: {}",
+                            if accept { "accepted" } else { "dismissed" },
+                            dialog.dialog_type,
+                            dialog.message
+                        );
+                        watcher.state.lock().unwrap().auto_handled.push(HandledDialog { dialog, accepted: accept });
+                    }
+                    Err(e) => {
+                        // Leave it for the agent rather than silently hanging
+                        tracing::warn!("Failed to auto-handle dialog: {}", e);
+                        watcher.state.lock().unwrap().open = Some((dialog, dialog_page.clone()));
+                        watcher.opened.notify_waiters();
+                    }
+                }
+            }
+        });
+
+        let watcher = self.clone();
+        tokio::spawn(async move {
+            while closed.next().await.is_some() {
+                watcher.state.lock().unwrap().open = None;
+            }
+        });
+
+        tracing::debug!("Dialog watcher attached to page");
+        Ok(())
+    }
+
+    /// The dialog currently waiting for an answer, if any
+    pub fn open_dialog(&self) -> Option<DialogInfo> {
+        self.state.lock().unwrap().open.as_ref().map(|(dialog, _)| dialog.clone())
+    }
+
+    /// Dialogs auto-handled since the last call
+    pub fn take_auto_handled(&self) -> Vec<HandledDialog> {
+        std::mem::take(&mut self.state.lock().unwrap().auto_handled)
+    }
+
+    /// Wait until a dialog opens that needs a manual answer
+    pub async fn wait_opened(&self) -> DialogInfo {
+        loop {
+            self.opened.notified().await;
+            if let Some(dialog) = self.open_dialog() {
+                return dialog;
+            }
+        }
+    }
+
+    /// Accept or dismiss the open dialog
+    pub async fn handle(&self, accept: bool, prompt_text: Option<String>) -> Result<DialogInfo> {
+        let (dialog, page) = self
+            .state
+            .lock()
+            .unwrap()
+            .open
+            .clone()
+            .ok_or_else(|| anyhow!("No dialog is open"))?;
+
+        page.execute(HandleJavaScriptDialogParams { accept, prompt_text })
+            .await
+            .map_err(|e| anyhow!("Failed to handle dialog: {}", e))?;
+
+        self.state.lock().unwrap().open = None;
+        Ok(dialog)
+    }
+
+    /// Forget any open dialog (e.g. when the browser closes)
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.open = None;
+        state.auto_handled.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dialog(dialog_type: &str, default_prompt: Option<&str>) -> DialogInfo {
+        DialogInfo {
+            dialog_type: dialog_type.to_string(),
+            message: "Continue?".to_string(),
+            default_prompt: default_prompt.map(String::from),
+            url: "https://example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_auto_answer_per_policy() {
+        let confirm = dialog("confirm", None);
+        let answer = auto_answer(DialogPolicy::Accept, &confirm).unwrap();
+        assert!(answer.accept);
+        assert_eq!(answer.prompt_text, None);
+
+        let answer = auto_answer(DialogPolicy::Dismiss, &confirm).unwrap();
+        assert!(!answer.accept);
+
+        assert!(auto_answer(DialogPolicy::Manual, &confirm).is_none());
+        assert!(auto_answer(DialogPolicy::default(), &confirm).is_none());
+    }
+
+    #[test]
+    fn test_accepted_prompt_gets_default_text() {
+        let prompt = dialog("prompt", Some("Jane"));
+        let answer = auto_answer(DialogPolicy::Accept, &prompt).unwrap();
+        assert!(answer.accept);
+        assert_eq!(answer.prompt_text.as_deref(), Some("Jane"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_without_open_dialog() {
+        let watcher = DialogWatcher::new();
+        watcher.set_policy(DialogPolicy::Manual);
+
+        assert!(watcher.open_dialog().is_none());
+        let error = watcher.handle(true, None).await.unwrap_err();
+        assert_eq!(error.to_string(), "No dialog is open");
+        assert!(watcher.take_auto_handled().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::page::{
+    CaptureSnapshotFormat, CaptureSnapshotParams, PrintToPdfParams,
+};
+use chromiumoxide::Page;
+use serde::Deserialize;
+
+/// Paper sizes in inches (portrait width, height)
+pub const PAPER_SIZES: &[(&str, f64, f64)] = &[
+    ("letter", 8.5, 11.0),
+    ("legal", 8.5, 14.0),
+    ("tabloid", 11.0, 17.0),
+    ("a3", 11.69, 16.54),
+    ("a4", 8.27, 11.69),
+    ("a5", 5.83, 8.27),
+];
+
+pub fn paper_size(name: &str) -> Option<(f64, f64)> {
+    let name = name.trim().to_ascii_lowercase();
+    PAPER_SIZES.iter().find(|(n, _, _)| *n == name).map(|(_, w, h)| (*w, *h))
+}
+
+/// Page.printToPDF options exposed to tools and workflow steps
+#[derive(Debug, Clone, Deserialize)]
+pub struct PdfOptions {
+    /// letter, legal, tabloid, a3, a4 or a5 (default letter)
+    #[serde(default)]
+    pub paper: Option<String>,
+    #[serde(default)]
+    pub landscape: bool,
+    /// Keep background colors and images - forms often rely on them (default true)
+    #[serde(default = "default_true")]
+    pub print_background: bool,
+    /// Pages to print, e.g. "1-3, 5" (default all)
+    #[serde(default)]
+    pub page_ranges: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            paper: None,
+            landscape: false,
+            print_background: true,
+            page_ranges: None,
+        }
+    }
+}
+
+impl PdfOptions {
+    pub fn to_params(&self) -> Result<PrintToPdfParams> {
+        let paper = self.paper.as_deref().unwrap_or("letter");
+        let (width, height) = paper_size(paper).ok_or_else(|| {
+            let known: Vec<_> = PAPER_SIZES.iter().map(|(n, _, _)| *n).collect();
+            anyhow!("Unknown paper size '{}'. Use one of: {}", paper, known.join(", "))
+        })?;
+
+        let mut builder = PrintToPdfParams::builder()
+            .paper_width(width)
+            .paper_height(height)
+            .landscape(self.landscape)
+            .print_background(self.print_background);
+        if let Some(ranges) = self.page_ranges.as_ref().filter(|r| !r.trim().is_empty()) {
+            builder = builder.page_ranges(ranges.clone());
+        }
+        Ok(builder.build())
+    }
+}
+
+/// Print a page to PDF bytes
+pub async fn print_pdf(page: &Page, options: &PdfOptions) -> Result<Vec<u8>> {
+    page.pdf(options.to_params()?).await
+        .map_err(|e| anyhow!("Failed to print page to PDF (Chrome only prints in headless mode): {}", e))
+}
+
+/// Capture a page, with its images and stylesheets, as a single MHTML archive
+pub async fn capture_mhtml(page: &Page) -> Result<Vec<u8>> {
+    let params = CaptureSnapshotParams::builder()
+        .format(CaptureSnapshotFormat::Mhtml)
+        .build();
+    let snapshot = page.execute(params).await
+        .map_err(|e| anyhow!("Failed to capture MHTML snapshot: {}", e))?;
+    Ok(snapshot.result.data.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_options_to_params() {
+        let options: PdfOptions = serde_json::from_value(serde_json::json!({
+            "paper": "A4",
+            "landscape": true,
+            "page_ranges": "1-2"
+        }))
+        .unwrap();
+        let params = options.to_params().unwrap();
+        assert_eq!((params.paper_width, params.paper_height), (Some(8.27), Some(11.69)));
+        assert_eq!(params.landscape, Some(true));
+        assert_eq!(params.print_background, Some(true));
+        assert_eq!(params.page_ranges.as_deref(), Some("1-2"));
+
+        let unknown = PdfOptions { paper: Some("b5".to_string()), ..Default::default() };
+        assert!(unknown.to_params().is_err());
+    }
+}
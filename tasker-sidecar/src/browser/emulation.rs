@@ -0,0 +1,253 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::emulation::{
+    MediaFeature, SetDeviceMetricsOverrideParams, SetEmulatedMediaParams,
+    SetGeolocationOverrideParams, SetLocaleOverrideParams, SetTimezoneOverrideParams,
+    SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
+};
+use chromiumoxide::cdp::browser_protocol::network::{
+    EmulateNetworkConditionsParams, EnableParams as NetworkEnableParams,
+};
+use chromiumoxide::Page;
+
+use crate::models::{BrowserEmulation, ColorScheme, Viewport};
+
+/// A device preset: screen, pixel ratio, input and user agent
+#[derive(Debug, Clone, Copy)]
+pub struct DevicePreset {
+    pub name: &'static str,
+    pub width: i64,
+    pub height: i64,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+    pub touch: bool,
+    pub user_agent: Option<&'static str>,
+}
+
+const IPHONE_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const IPAD_UA: &str = "Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const PIXEL_UA: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+const GALAXY_UA: &str = "Mozilla/5.0 (Linux; Android 14; SM-S911B) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+
+pub const DEVICE_PRESETS: &[DevicePreset] = &[
+    DevicePreset { name: "iphone_se", width: 375, height: 667, device_scale_factor: 2.0, mobile: true, touch: true, user_agent: Some(IPHONE_UA) },
+    DevicePreset { name: "iphone_15", width: 393, height: 852, device_scale_factor: 3.0, mobile: true, touch: true, user_agent: Some(IPHONE_UA) },
+    DevicePreset { name: "iphone_15_pro_max", width: 430, height: 932, device_scale_factor: 3.0, mobile: true, touch: true, user_agent: Some(IPHONE_UA) },
+    DevicePreset { name: "pixel_7", width: 412, height: 915, device_scale_factor: 2.625, mobile: true, touch: true, user_agent: Some(PIXEL_UA) },
+    DevicePreset { name: "galaxy_s23", width: 360, height: 780, device_scale_factor: 3.0, mobile: true, touch: true, user_agent: Some(GALAXY_UA) },
+    DevicePreset { name: "ipad", width: 820, height: 1180, device_scale_factor: 2.0, mobile: true, touch: true, user_agent: Some(IPAD_UA) },
+    DevicePreset { name: "desktop", width: 1280, height: 720, device_scale_factor: 1.0, mobile: false, touch: false, user_agent: None },
+    DevicePreset { name: "desktop_hd", width: 1920, height: 1080, device_scale_factor: 1.0, mobile: false, touch: false, user_agent: None },
+];
+
+/// Network throttling profile (throughput in bytes/second, latency in ms)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NetworkProfile {
+    pub offline: bool,
+    pub latency: f64,
+    pub download_throughput: f64,
+    pub upload_throughput: f64,
+}
+
+/// Throttling presets, matching the DevTools ones where they exist
+pub fn network_profile(name: &str) -> Option<NetworkProfile> {
+    const KBPS: f64 = 1024.0 / 8.0;
+    let profile = match name {
+        "offline" => NetworkProfile { offline: true, latency: 0.0, download_throughput: 0.0, upload_throughput: 0.0 },
+        "slow_3g" => NetworkProfile { offline: false, latency: 2000.0, download_throughput: 400.0 * KBPS, upload_throughput: 400.0 * KBPS },
+        "fast_3g" => NetworkProfile { offline: false, latency: 562.5, download_throughput: 1475.0 * KBPS, upload_throughput: 675.0 * KBPS },
+        "4g" => NetworkProfile { offline: false, latency: 60.0, download_throughput: 9000.0 * KBPS, upload_throughput: 1500.0 * KBPS },
+        _ => return None,
+    };
+    Some(profile)
+}
+
+pub fn device_preset(name: &str) -> Option<&'static DevicePreset> {
+    let name = name.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+    DEVICE_PRESETS.iter().find(|d| d.name == name)
+}
+
+/// Screen metrics after merging the device preset with explicit overrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceMetrics {
+    pub width: i64,
+    pub height: i64,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+    pub touch: bool,
+    pub user_agent: Option<String>,
+}
+
+impl DeviceMetrics {
+    /// Resolve metrics, or None when the emulation doesn't touch the device at all
+    pub fn resolve(emulation: &BrowserEmulation, base_viewport: &Viewport) -> Result<Option<Self>> {
+        let preset = match &emulation.device {
+            Some(name) => Some(device_preset(name).ok_or_else(|| {
+                let known: Vec<_> = DEVICE_PRESETS.iter().map(|d| d.name).collect();
+                anyhow!("Unknown device preset '{}'. Known presets: {}", name, known.join(", "))
+            })?),
+            None => None,
+        };
+
+        let touches_device = preset.is_some()
+            || emulation.viewport.is_some()
+            || emulation.device_scale_factor.is_some()
+            || emulation.mobile.is_some()
+            || emulation.touch.is_some()
+            || emulation.user_agent.is_some();
+        if !touches_device {
+            return Ok(None);
+        }
+
+        let (width, height) = match (&emulation.viewport, preset) {
+            (Some(viewport), _) => (viewport.width as i64, viewport.height as i64),
+            (None, Some(preset)) => (preset.width, preset.height),
+            (None, None) => (base_viewport.width as i64, base_viewport.height as i64),
+        };
+
+        Ok(Some(Self {
+            width,
+            height,
+            device_scale_factor: emulation.device_scale_factor
+                .or(preset.map(|p| p.device_scale_factor))
+                .unwrap_or(1.0),
+            mobile: emulation.mobile.or(preset.map(|p| p.mobile)).unwrap_or(false),
+            touch: emulation.touch.or(preset.map(|p| p.touch)).unwrap_or(false),
+            user_agent: emulation.user_agent.clone()
+                .or(preset.and_then(|p| p.user_agent.map(String::from))),
+        }))
+    }
+}
+
+/// Apply an emulation block to a page. `base_viewport` is used when only some device fields are set.
+pub async fn apply(page: &Page, emulation: &BrowserEmulation, base_viewport: &Viewport) -> Result<()> {
+    let metrics = DeviceMetrics::resolve(emulation, base_viewport)?;
+
+    if let Some(metrics) = &metrics {
+        let params = SetDeviceMetricsOverrideParams::builder()
+            .width(metrics.width)
+            .height(metrics.height)
+            .device_scale_factor(metrics.device_scale_factor)
+            .mobile(metrics.mobile)
+            .build()
+            .map_err(|e| anyhow!("Failed to build device metrics: {}", e))?;
+        page.execute(params).await
+            .map_err(|e| anyhow!("Failed to set device metrics: {}", e))?;
+
+        let touch = SetTouchEmulationEnabledParams {
+            enabled: metrics.touch,
+            max_touch_points: metrics.touch.then_some(5),
+        };
+        page.execute(touch).await
+            .map_err(|e| anyhow!("Failed to set touch emulation: {}", e))?;
+    }
+
+    let user_agent = metrics.as_ref().and_then(|m| m.user_agent.clone());
+    if user_agent.is_some() || emulation.locale.is_some() {
+        // Accept-Language can only be overridden together with a user agent - keep the browser's own
+        let user_agent = match user_agent {
+            Some(ua) => ua,
+            None => page.user_agent().await
+                .map_err(|e| anyhow!("Failed to read user agent: {}", e))?,
+        };
+        let params = SetUserAgentOverrideParams {
+            user_agent,
+            accept_language: emulation.locale.clone(),
+            platform: None,
+            user_agent_metadata: None,
+        };
+        page.execute(params).await
+            .map_err(|e| anyhow!("Failed to set user agent: {}", e))?;
+    }
+
+    if let Some(locale) = &emulation.locale {
+        page.execute(SetLocaleOverrideParams { locale: Some(locale.clone()) }).await
+            .map_err(|e| anyhow!("Failed to set locale '{}': {}", locale, e))?;
+    }
+
+    if let Some(timezone) = &emulation.timezone {
+        page.execute(SetTimezoneOverrideParams::new(timezone.clone())).await
+            .map_err(|e| anyhow!("Failed to set timezone '{}': {}", timezone, e))?;
+    }
+
+    if let Some(geo) = &emulation.geolocation {
+        let params = SetGeolocationOverrideParams {
+            latitude: Some(geo.latitude),
+            longitude: Some(geo.longitude),
+            accuracy: Some(geo.accuracy),
+        };
+        page.execute(params).await
+            .map_err(|e| anyhow!("Failed to set geolocation: {}", e))?;
+    }
+
+    if let Some(scheme) = emulation.color_scheme {
+        let value = match scheme {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        };
+        let params = SetEmulatedMediaParams {
+            media: None,
+            features: Some(vec![MediaFeature::new("prefers-color-scheme", value)]),
+        };
+        page.execute(params).await
+            .map_err(|e| anyhow!("Failed to set color scheme: {}", e))?;
+    }
+
+    if let Some(name) = &emulation.network {
+        let profile = network_profile(name)
+            .ok_or_else(|| anyhow!("Unknown network preset '{}'. Use offline, slow_3g, fast_3g or 4g", name))?;
+        page.execute(NetworkEnableParams::default()).await
+            .map_err(|e| anyhow!("Failed to enable network domain: {}", e))?;
+        let params = EmulateNetworkConditionsParams::new(
+            profile.offline,
+            profile.latency,
+            profile.download_throughput,
+            profile.upload_throughput,
+        );
+        page.execute(params).await
+            .map_err(|e| anyhow!("Failed to throttle network: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Viewport {
+        Viewport { width: 1280, height: 720 }
+    }
+
+    #[test]
+    fn test_resolve_device_preset_with_overrides() {
+        let emulation = BrowserEmulation {
+            device: Some("iPhone 15".to_string()),
+            device_scale_factor: Some(2.0),
+            ..Default::default()
+        };
+        let metrics = DeviceMetrics::resolve(&emulation, &base()).unwrap().unwrap();
+        assert_eq!((metrics.width, metrics.height), (393, 852));
+        assert_eq!(metrics.device_scale_factor, 2.0);
+        assert!(metrics.mobile && metrics.touch);
+        assert!(metrics.user_agent.unwrap().contains("iPhone"));
+    }
+
+    #[test]
+    fn test_resolve_without_device_fields() {
+        let emulation = BrowserEmulation {
+            timezone: Some("Europe/Berlin".to_string()),
+            ..Default::default()
+        };
+        assert!(DeviceMetrics::resolve(&emulation, &base()).unwrap().is_none());
+
+        let unknown = BrowserEmulation { device: Some("nokia_3310".to_string()), ..Default::default() };
+        assert!(DeviceMetrics::resolve(&unknown, &base()).is_err());
+    }
+
+    #[test]
+    fn test_network_profiles() {
+        assert!(network_profile("offline").unwrap().offline);
+        assert_eq!(network_profile("slow_3g").unwrap().latency, 2000.0);
+        assert!(network_profile("dialup").is_none());
+    }
+}
@@ -0,0 +1,355 @@
+use anyhow::{anyhow, Result};
+
+/// CDP `Input.dispatchKeyEvent` / `dispatchMouseEvent` modifier bits
+pub const MODIFIER_ALT: i64 = 1;
+pub const MODIFIER_CONTROL: i64 = 2;
+pub const MODIFIER_META: i64 = 4;
+pub const MODIFIER_SHIFT: i64 = 8;
+
+/// A physical key on a US keyboard, as CDP expects it
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyDefinition {
+    /// DOM `key` value ("a", "A", "Enter", "Control")
+    pub key: String,
+    /// DOM `code` value ("KeyA", "Enter", "ControlLeft")
+    pub code: String,
+    /// Windows virtual key code
+    pub key_code: i64,
+    /// Text the key inserts, if any
+    pub text: Option<String>,
+    /// 1 for left-hand modifiers, 0 otherwise
+    pub location: i64,
+}
+
+/// A key press with modifiers held down, e.g. "Control+Shift+K"
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyChord {
+    /// Bitmask of MODIFIER_* values
+    pub modifiers: i64,
+    /// Modifier keys (with their MODIFIER_* bit) in the order they are pressed
+    pub modifier_keys: Vec<(KeyDefinition, i64)>,
+    pub key: KeyDefinition,
+}
+
+/// One entry of a `send_keys` sequence
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyInput {
+    Chord(KeyChord),
+    /// Characters with no US-keyboard key (accents, CJK, emoji) - inserted as text
+    Text(String),
+}
+
+/// Printable keys: (unshifted, shifted, code, key code)
+const PRINTABLE_KEYS: &[(char, char, &str, i64)] = &[
+    ('`', '~', "Backquote", 192),
+    ('1', '!', "Digit1", 49),
+    ('2', '@', "Digit2", 50),
+    ('3', '#', "Digit3", 51),
+    ('4', '$', "Digit4", 52),
+    ('5', '%', "Digit5", 53),
+    ('6', '^', "Digit6", 54),
+    ('7', '&', "Digit7", 55),
+    ('8', '*', "Digit8", 56),
+    ('9', '(', "Digit9", 57),
+    ('0', ')', "Digit0", 48),
+    ('-', '_', "Minus", 189),
+    ('=', '+', "Equal", 187),
+    ('[', '{', "BracketLeft", 219),
+    (']', '}', "BracketRight", 221),
+    ('\\', '|', "Backslash", 220),
+    (';', ':', "Semicolon", 186),
+    ('\'', '"', "Quote", 222),
+    (',', '<', "Comma", 188),
+    ('.', '>', "Period", 190),
+    ('/', '?', "Slash", 191),
+    (' ', ' ', "Space", 32),
+];
+
+/// Non-printable key: (lowercase aliases, key, code, key code, text)
+type NamedKey = (&'static [&'static str], &'static str, &'static str, i64, Option<&'static str>);
+
+const NAMED_KEYS: &[NamedKey] = &[
+    (&["enter", "return"], "Enter", "Enter", 13, Some("\r")),
+    (&["tab"], "Tab", "Tab", 9, None),
+    (&["escape", "esc"], "Escape", "Escape", 27, None),
+    (&["backspace"], "Backspace", "Backspace", 8, None),
+    (&["delete", "del"], "Delete", "Delete", 46, None),
+    (&["insert", "ins"], "Insert", "Insert", 45, None),
+    (&["arrowup", "up"], "ArrowUp", "ArrowUp", 38, None),
+    (&["arrowdown", "down"], "ArrowDown", "ArrowDown", 40, None),
+    (&["arrowleft", "left"], "ArrowLeft", "ArrowLeft", 37, None),
+    (&["arrowright", "right"], "ArrowRight", "ArrowRight", 39, None),
+    (&["home"], "Home", "Home", 36, None),
+    (&["end"], "End", "End", 35, None),
+    (&["pageup", "pgup"], "PageUp", "PageUp", 33, None),
+    (&["pagedown", "pgdn"], "PageDown", "PageDown", 34, None),
+    (&["space", "spacebar"], " ", "Space", 32, Some(" ")),
+    (&["capslock"], "CapsLock", "CapsLock", 20, None),
+    (&["contextmenu", "menu"], "ContextMenu", "ContextMenu", 93, None),
+];
+
+/// Modifier keys: (lowercase aliases, key, code, key code, bit)
+const MODIFIER_KEYS: &[(&[&str], &str, &str, i64, i64)] = &[
+    (&["shift"], "Shift", "ShiftLeft", 16, MODIFIER_SHIFT),
+    (&["control", "ctrl"], "Control", "ControlLeft", 17, MODIFIER_CONTROL),
+    (&["alt", "option", "opt"], "Alt", "AltLeft", 18, MODIFIER_ALT),
+    (&["meta", "cmd", "command", "super", "win", "os"], "Meta", "MetaLeft", 91, MODIFIER_META),
+];
+
+/// Look up a modifier by name; "CmdOrCtrl"/"Mod" resolve to the platform's shortcut key
+fn modifier(name: &str) -> Option<(KeyDefinition, i64)> {
+    let lower = name.to_lowercase();
+    let lower = match lower.as_str() {
+        "mod" | "cmdorctrl" | "commandorcontrol" if cfg!(target_os = "macos") => "meta".to_string(),
+        "mod" | "cmdorctrl" | "commandorcontrol" => "control".to_string(),
+        _ => lower,
+    };
+    MODIFIER_KEYS
+        .iter()
+        .find(|(aliases, ..)| aliases.contains(&lower.as_str()))
+        .map(|(_, key, code, key_code, bit)| {
+            (
+                KeyDefinition {
+                    key: key.to_string(),
+                    code: code.to_string(),
+                    key_code: *key_code,
+                    text: None,
+                    location: 1,
+                },
+                *bit,
+            )
+        })
+}
+
+/// Look up a key by name or character on a US keyboard
+pub fn key_definition(name: &str) -> Option<KeyDefinition> {
+    let lower = name.to_lowercase();
+
+    if let Some((_, key, code, key_code, text)) =
+        NAMED_KEYS.iter().find(|(aliases, ..)| aliases.contains(&lower.as_str()))
+    {
+        return Some(KeyDefinition {
+            key: key.to_string(),
+            code: code.to_string(),
+            key_code: *key_code,
+            text: text.map(|t| t.to_string()),
+            location: 0,
+        });
+    }
+
+    if let Some((key, _)) = modifier(name) {
+        return Some(key);
+    }
+
+    // Function keys F1-F24
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<i64>().ok()) {
+        if (1..=24).contains(&n) {
+            return Some(KeyDefinition {
+                key: format!("F{}", n),
+                code: format!("F{}", n),
+                key_code: 111 + n,
+                text: None,
+                location: 0,
+            });
+        }
+    }
+
+    let mut chars = name.chars();
+    let (c, None) = (chars.next()?, chars.next()) else {
+        return None;
+    };
+
+    if c.is_ascii_alphabetic() {
+        let upper = c.to_ascii_uppercase();
+        return Some(KeyDefinition {
+            key: c.to_string(),
+            code: format!("Key{}", upper),
+            key_code: upper as i64,
+            text: Some(c.to_string()),
+            location: 0,
+        });
+    }
+
+    PRINTABLE_KEYS
+        .iter()
+        .find(|(plain, shifted, ..)| *plain == c || *shifted == c)
+        .map(|(_, _, code, key_code)| KeyDefinition {
+            key: c.to_string(),
+            code: code.to_string(),
+            key_code: *key_code,
+            text: Some(c.to_string()),
+            location: 0,
+        })
+}
+
+/// Shifted character for a printable key ("a" -> "A", "1" -> "!")
+fn shifted(key: &KeyDefinition) -> Option<char> {
+    let mut chars = key.key.chars();
+    let (c, None) = (chars.next()?, chars.next()) else {
+        return None;
+    };
+    if c.is_ascii_lowercase() {
+        return Some(c.to_ascii_uppercase());
+    }
+    PRINTABLE_KEYS
+        .iter()
+        .find(|(plain, ..)| *plain == c)
+        .map(|(_, shifted, ..)| *shifted)
+}
+
+/// Parse a chord like "Enter", "Control+A", "Shift+Tab" or "Meta+Shift+K"
+pub fn parse_chord(chord: &str) -> Result<KeyChord> {
+    let chord = chord.trim();
+    if chord.is_empty() {
+        return Err(anyhow!("Empty key"));
+    }
+
+    // "+" on its own or at the end ("Control++") is the plus key, not a separator
+    let (prefix, key_name) = if chord == "+" {
+        ("", "+")
+    } else if let Some(prefix) = chord.strip_suffix("++") {
+        (prefix, "+")
+    } else {
+        match chord.rsplit_once('+') {
+            Some((prefix, key)) => (prefix, key),
+            None => ("", chord),
+        }
+    };
+
+    let mut modifiers = 0;
+    let mut modifier_keys = Vec::new();
+    if !prefix.is_empty() {
+        for part in prefix.split('+') {
+            let (key, bit) = modifier(part.trim())
+                .ok_or_else(|| anyhow!("Unknown modifier '{}' in '{}'", part, chord))?;
+            if modifiers & bit == 0 {
+                modifiers |= bit;
+                modifier_keys.push((key, bit));
+            }
+        }
+    }
+
+    let mut key = key_definition(key_name.trim())
+        .ok_or_else(|| anyhow!("Unknown key '{}'", key_name))?;
+
+    if modifiers & MODIFIER_SHIFT != 0 {
+        if let Some(c) = shifted(&key) {
+            key.key = c.to_string();
+            key.text = Some(c.to_string());
+        }
+    }
+    // Shortcuts (Ctrl+A, Cmd+Enter) are commands, not text input
+    if modifiers & (MODIFIER_CONTROL | MODIFIER_ALT | MODIFIER_META) != 0 {
+        key.text = None;
+    }
+
+    Ok(KeyChord { modifiers, modifier_keys, key })
+}
+
+/// Parse modifier names for clicks, e.g. "Control" or "Control+Shift"
+pub fn parse_modifiers(modifiers: &str) -> Result<i64> {
+    modifiers
+        .split('+')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .try_fold(0, |mask, part| {
+            modifier(part)
+                .map(|(_, bit)| mask | bit)
+                .ok_or_else(|| anyhow!("Unknown modifier '{}'", part))
+        })
+}
+
+/// Parse a `send_keys` value: whitespace-separated chords ("Control+A Backspace").
+/// Tokens that are not a key name and have no '+' ("é", "日本語") are inserted as text
+pub fn parse_key_sequence(keys: &str) -> Result<Vec<KeyInput>> {
+    // A lone space is the space bar, not a separator
+    if keys == " " {
+        return Ok(vec![KeyInput::Chord(parse_chord("Space")?)]);
+    }
+
+    let inputs = keys
+        .split_whitespace()
+        .map(|token| match parse_chord(token) {
+            Ok(chord) => Ok(KeyInput::Chord(chord)),
+            Err(_) if !token.contains('+') => Ok(KeyInput::Text(token.to_string())),
+            Err(e) => Err(e),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if inputs.is_empty() {
+        return Err(anyhow!("No keys given"));
+    }
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_named_and_character_keys() {
+        let enter = parse_chord("enter").unwrap();
+        assert_eq!(enter.key.key, "Enter");
+        assert_eq!(enter.key.text.as_deref(), Some("\r"));
+        assert_eq!(enter.modifiers, 0);
+
+        let a = parse_chord("a").unwrap();
+        assert_eq!((a.key.code.as_str(), a.key.key_code), ("KeyA", 65));
+
+        let f5 = parse_chord("F5").unwrap();
+        assert_eq!((f5.key.key.as_str(), f5.key.key_code), ("F5", 116));
+    }
+
+    #[test]
+    fn test_parse_chords_with_modifiers() {
+        let chord = parse_chord("Control+Shift+k").unwrap();
+        assert_eq!(chord.modifiers, MODIFIER_CONTROL | MODIFIER_SHIFT);
+        assert_eq!(chord.modifier_keys.len(), 2);
+        assert_eq!(chord.key.key, "K");
+        assert_eq!(chord.key.text, None);
+
+        let shift_tab = parse_chord("Shift+Tab").unwrap();
+        assert_eq!(shift_tab.modifiers, MODIFIER_SHIFT);
+        assert_eq!(shift_tab.key.code, "Tab");
+
+        // Shift alone still produces text
+        let bang = parse_chord("Shift+1").unwrap();
+        assert_eq!(bang.key.text.as_deref(), Some("!"));
+        assert_eq!(bang.key.code, "Digit1");
+
+        let cmd_enter = parse_chord("Cmd+Enter").unwrap();
+        assert_eq!(cmd_enter.modifiers, MODIFIER_META);
+        assert_eq!(cmd_enter.key.text, None);
+
+        let plus = parse_chord("Control++").unwrap();
+        assert_eq!(plus.key.key, "+");
+        assert_eq!(plus.modifiers, MODIFIER_CONTROL);
+
+        assert!(parse_chord("Hyper+A").is_err());
+        assert!(parse_chord("Control+Nope").is_err());
+    }
+
+    #[test]
+    fn test_parse_key_sequence() {
+        let inputs = parse_key_sequence("Control+A Backspace é").unwrap();
+        assert_eq!(inputs.len(), 3);
+        assert!(matches!(&inputs[0], KeyInput::Chord(c) if c.modifiers == MODIFIER_CONTROL));
+        assert_eq!(inputs[2], KeyInput::Text("é".to_string()));
+
+        let inputs = parse_key_sequence("日本語 Tab Grüße").unwrap();
+        assert_eq!(inputs[0], KeyInput::Text("日本語".to_string()));
+        assert!(matches!(&inputs[1], KeyInput::Chord(c) if c.key.key == "Tab"));
+        assert_eq!(inputs[2], KeyInput::Text("Grüße".to_string()));
+
+        assert!(parse_key_sequence("Control+Entr").is_err());
+        assert!(parse_key_sequence("").is_err());
+        assert!(parse_key_sequence(" ").is_ok());
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        assert_eq!(parse_modifiers("Control+Shift").unwrap(), MODIFIER_CONTROL | MODIFIER_SHIFT);
+        assert_eq!(parse_modifiers("").unwrap(), 0);
+        assert!(parse_modifiers("Fn").is_err());
+    }
+}
@@ -8,22 +8,221 @@ use chromiumoxide::cdp::browser_protocol::dom::{
 };
 use chromiumoxide::cdp::js_protocol::runtime::CallFunctionOnParams;
 use chromiumoxide::cdp::browser_protocol::input::{
-    DispatchKeyEventParams, DispatchKeyEventType,
-    DispatchMouseEventParams, DispatchMouseEventType, MouseButton,
+    DispatchDragEventParams, DispatchDragEventType, DispatchKeyEventParams, DispatchKeyEventType,
+    DispatchMouseEventParams, DispatchMouseEventType, EventDragIntercepted, InsertTextParams,
+    MouseButton, SetInterceptDragsParams,
 };
-use chromiumoxide::cdp::browser_protocol::page::{AddScriptToEvaluateOnNewDocumentParams, CaptureScreenshotFormat, EventFrameNavigated};
+use chromiumoxide::cdp::browser_protocol::browser::{GrantPermissionsParams, PermissionType};
+use chromiumoxide::cdp::browser_protocol::page::{AddScriptToEvaluateOnNewDocumentParams, EventFrameNavigated, Viewport as CdpViewport};
+use chromiumoxide::cdp::browser_protocol::target::{EventTargetCreated, EventTargetDestroyed, TargetId};
 use chromiumoxide::cdp::js_protocol::runtime::{AddBindingParams, EventBindingCalled};
 use chromiumoxide::listeners::EventStream;
 use chromiumoxide::Page;
 use futures_util::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::time::timeout;
 use tracing::instrument;
 
-use crate::browser::cdp_dom::{self, BackendNodeId, DOMExtractionResult};
-use crate::models::Viewport;
+use crate::browser::annotate;
+use crate::browser::cdp_dom::{self, BackendNodeId, DOMExtractionResult, DOMRect, SimplifiedElement};
+use crate::browser::console::ConsoleRecorder;
+use crate::browser::dialog::DialogWatcher;
+use crate::browser::document::{self, PdfOptions};
+use crate::browser::emulation;
+use crate::browser::keyboard::{self, KeyChord, KeyDefinition, KeyInput};
+use crate::browser::markdown;
+use crate::browser::network::NetworkRecorder;
+use crate::browser::pointer::{self, DragTarget};
+use crate::browser::script;
+use crate::browser::table::{self, ExtractedTable, TableTarget};
+use crate::browser::proxy;
+use crate::browser::screenshot;
+use crate::browser::tabs::{active_after_close, TabEvent, TabEvents};
+use crate::models::{
+    BrowserEmulation, ClipRegion, DialogPolicy, ImageFormat, ProxyConfig, ScreenshotSettings, Viewport,
+};
+
+/// Build a key event for a key definition
+fn key_event(r#type: DispatchKeyEventType, key: &KeyDefinition, modifiers: i64) -> DispatchKeyEventParams {
+    DispatchKeyEventParams {
+        r#type,
+        modifiers: Some(modifiers),
+        key: Some(key.key.clone()),
+        code: Some(key.code.clone()),
+        windows_virtual_key_code: Some(key.key_code),
+        native_virtual_key_code: Some(key.key_code),
+        text: None,
+        unmodified_text: None,
+        key_identifier: None,
+        auto_repeat: None,
+        is_keypad: None,
+        is_system_key: None,
+        location: Some(key.location),
+        timestamp: None,
+        commands: None,
+    }
+}
+
+/// Press a chord: modifiers down in order, key down/up, modifiers up in reverse
+async fn press_chord(page: &Page, chord: &KeyChord) -> Result<()> {
+    let mut held = 0;
+    for (key, bit) in &chord.modifier_keys {
+        held |= bit;
+        page.execute(key_event(DispatchKeyEventType::RawKeyDown, key, held)).await
+            .map_err(|e| anyhow!("Failed to dispatch {} down: {}", key.key, e))?;
+    }
+
+    // Keys that insert text need keyDown (with text); the rest use rawKeyDown
+    let mut key_down = match &chord.key.text {
+        Some(_) => key_event(DispatchKeyEventType::KeyDown, &chord.key, chord.modifiers),
+        None => key_event(DispatchKeyEventType::RawKeyDown, &chord.key, chord.modifiers),
+    };
+    key_down.text = chord.key.text.clone();
+    key_down.unmodified_text = chord.key.text.clone();
+    page.execute(key_down).await
+        .map_err(|e| anyhow!("Failed to dispatch key down: {}", e))?;
+    page.execute(key_event(DispatchKeyEventType::KeyUp, &chord.key, chord.modifiers)).await
+        .map_err(|e| anyhow!("Failed to dispatch key up: {}", e))?;
+
+    for (key, bit) in chord.modifier_keys.iter().rev() {
+        held &= !bit;
+        page.execute(key_event(DispatchKeyEventType::KeyUp, key, held)).await
+            .map_err(|e| anyhow!("Failed to dispatch {} up: {}", key.key, e))?;
+    }
+
+    Ok(())
+}
+
+/// Wait for chromiumoxide to finish attaching to a newly created target
+async fn wait_for_page(browser: &Mutex<Option<Browser>>, target_id: TargetId) -> Option<Page> {
+    for _ in 0..50 {
+        if let Some(browser) = browser.lock().await.as_ref() {
+            if let Ok(page) = browser.get_page(target_id.clone()).await {
+                return Some(page);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    None
+}
+
+/// Center of an element's content box in viewport CSS pixels
+async fn element_center(page: &Page, backend_id: BackendNodeId) -> Result<(f64, f64)> {
+    let box_params = GetBoxModelParams {
+        node_id: None,
+        backend_node_id: Some(CdpBackendNodeId::new(backend_id)),
+        object_id: None,
+    };
+    let box_result = page.execute(box_params).await
+        .map_err(|e| anyhow!("Failed to get box model for {}: {}", backend_id, e))?;
+
+    // Content quad is [x1,y1, x2,y2, x3,y3, x4,y4]
+    let content = box_result.result.model.content.inner();
+    let center_x = (content[0] + content[2] + content[4] + content[6]) / 4.0;
+    let center_y = (content[1] + content[3] + content[5] + content[7]) / 4.0;
+    Ok((center_x, center_y))
+}
+
+/// Apply per-tab setup shared by new_tab and adopted popups
+async fn prepare_new_tab(
+    page: &Page,
+    headless: bool,
+    emulation: Option<&BrowserEmulation>,
+    viewport: &Viewport,
+    proxy: Option<&ProxyConfig>,
+) {
+    // Each tab answers proxy auth challenges itself
+    if let Some(credentials) = proxy.and_then(proxy::credentials) {
+        if let Err(e) = page.authenticate(credentials).await {
+            tracing::warn!("Failed to set proxy credentials on new tab: {}", e);
+        }
+    }
+
+    // For headed mode, clear any viewport emulation so tab uses natural window size
+    if !headless {
+        use chromiumoxide::cdp::browser_protocol::emulation::ClearDeviceMetricsOverrideParams;
+        page.execute(ClearDeviceMetricsOverrideParams::default())
+            .await
+            .ok(); // Ignore errors - best-effort cleanup
+    }
+
+    if let Some(emulation) = emulation {
+        if let Err(e) = emulation::apply(page, emulation, viewport).await {
+            tracing::warn!("Failed to apply emulation to new tab: {}", e);
+        }
+    }
+}
+
+/// `--lang` flag for the launched Chrome - the emulated locale, en_US by default
+fn lang_arg(locale: Option<&str>) -> String {
+    format!("--lang={}", locale.unwrap_or("en_US"))
+}
+
+/// Chrome launch config: chromiumoxide's defaults minus the automation banner, plus the proxy
+/// and UI language (`locale`, en_US when none is set)
+fn launch_config(
+    headless: bool,
+    viewport: &Viewport,
+    proxy: Option<&ProxyConfig>,
+    bypass: &[String],
+    locale: Option<&str>,
+) -> Result<BrowserConfig> {
+    // Use disable_default_args() to prevent chromiumoxide from adding --enable-automation
+    // which causes the yellow "Chrome is being controlled" banner
+    let mut config = BrowserConfig::builder()
+        .disable_default_args();
+
+    if headless {
+        // For headless mode, set a fixed viewport size
+        config = config.window_size(viewport.width as u32, viewport.height as u32);
+    } else {
+        // For headed mode, start maximized and let Chrome determine viewport
+        config = config
+            .with_head()
+            .arg("--start-maximized");
+    }
+
+    // Manually add chromiumoxide's DEFAULT_ARGS, EXCEPT --enable-automation
+    // This removes the automation banner while keeping other useful defaults
+    config = config
+        .arg("--disable-background-networking")
+        .arg("--enable-features=NetworkService,NetworkServiceInProcess")
+        .arg("--disable-background-timer-throttling")
+        .arg("--disable-backgrounding-occluded-windows")
+        .arg("--disable-breakpad")
+        .arg("--disable-client-side-phishing-detection")
+        .arg("--disable-component-extensions-with-background-pages")
+        .arg("--disable-default-apps")
+        .arg("--disable-dev-shm-usage")
+        .arg("--disable-features=TranslateUI")
+        .arg("--disable-hang-monitor")
+        .arg("--disable-ipc-flooding-protection")
+        .arg("--disable-popup-blocking")
+        .arg("--disable-prompt-on-repost")
+        .arg("--disable-renderer-backgrounding")
+        .arg("--disable-sync")
+        .arg("--force-color-profile=srgb")
+        .arg("--metrics-recording-only")
+        .arg("--no-first-run")
+        .arg("--password-store=basic")
+        .arg("--use-mock-keychain")
+        .arg(lang_arg(locale))
+        .arg("--disable-infobars")
+        .arg("--no-default-browser-check")
+        .arg("--disable-extensions");
+
+    if let Some(proxy) = proxy {
+        for arg in proxy::chrome_args(proxy, bypass) {
+            config = config.arg(arg);
+        }
+        tracing::info!("Routing browser through proxy {}", proxy.server);
+    }
+
+    config.build().map_err(|e| anyhow!("Failed to build browser config: {}", e))
+}
 
 /// Manages browser lifecycle and page connections
 pub struct BrowserManager {
@@ -36,6 +235,75 @@ pub struct BrowserManager {
     launch_lock: tokio::sync::Mutex<()>,
     /// Whether browser is running in headless mode
     headless: Arc<Mutex<bool>>,
+    /// Viewport the browser was launched with
+    viewport: Arc<Mutex<Viewport>>,
+    /// Workflow emulation, re-applied to every new tab
+    emulation: Arc<Mutex<Option<BrowserEmulation>>>,
+    /// Proxy used by the next launch (credentials already split out of the URL)
+    proxy: Arc<Mutex<Option<ProxyConfig>>>,
+    /// Hosts that skip the proxy
+    proxy_bypass: Arc<Mutex<Vec<String>>>,
+    /// Format, size and clip of screenshots
+    screenshot_settings: Arc<Mutex<ScreenshotSettings>>,
+    /// DevTools endpoint of a running Chrome to connect to instead of launching one
+    cdp_url: Arc<Mutex<Option<String>>>,
+    /// UI language of the next launched Chrome - navigator.languages and Accept-Language
+    launch_locale: Arc<Mutex<Option<String>>>,
+    /// False when connected to a Chrome we didn't start - close() leaves it running
+    owns_browser: Arc<AtomicBool>,
+    /// Per-page recorders/watchers, attached to every tab once started
+    monitors: PageMonitors,
+    /// Tabs opened/closed by the page since the agent last looked
+    tab_events: TabEvents,
+    /// Make tabs opened by the page (popups, `target=_blank`) the active tab
+    follow_new_tabs: Arc<AtomicBool>,
+    /// Guarded isolated worlds read-only scripts run in
+    read_only_worlds: Arc<script::ReadOnlyWorlds>,
+}
+
+/// Per-page CDP listeners that have to follow the run across tabs
+#[derive(Clone, Default)]
+struct PageMonitors {
+    /// Network traffic recorder (only attached once capture is started)
+    network: NetworkRecorder,
+    /// Whether network capture is active - new tabs are attached automatically
+    network_capture: Arc<Mutex<bool>>,
+    /// Console/exception recorder (only attached once capture is started)
+    console: ConsoleRecorder,
+    /// Whether console capture is active - new tabs are attached automatically
+    console_capture: Arc<Mutex<bool>>,
+    /// Native JS dialog watcher (only attached once dialog handling is started)
+    dialogs: DialogWatcher,
+    /// Whether dialog handling is active - new tabs are attached automatically
+    dialog_handling: Arc<Mutex<bool>>,
+}
+
+impl PageMonitors {
+    /// Attach every started monitor to a newly opened tab (best-effort)
+    async fn attach_new_page(&self, page: &Page) {
+        if *self.network_capture.lock().await {
+            if let Err(e) = self.network.attach(page).await {
+                tracing::warn!("Failed to attach network recorder to new tab: {}", e);
+            }
+        }
+        if *self.console_capture.lock().await {
+            if let Err(e) = self.console.attach(page).await {
+                tracing::warn!("Failed to attach console recorder to new tab: {}", e);
+            }
+        }
+        if *self.dialog_handling.lock().await {
+            if let Err(e) = self.dialogs.attach(page).await {
+                tracing::warn!("Failed to attach dialog watcher to new tab: {}", e);
+            }
+        }
+    }
+
+    async fn reset(&self) {
+        *self.network_capture.lock().await = false;
+        *self.console_capture.lock().await = false;
+        *self.dialog_handling.lock().await = false;
+        self.dialogs.reset();
+    }
 }
 
 impl BrowserManager {
@@ -46,6 +314,18 @@ impl BrowserManager {
             active_tab: Arc::new(Mutex::new(0)),
             launch_lock: tokio::sync::Mutex::new(()),
             headless: Arc::new(Mutex::new(false)),
+            viewport: Arc::new(Mutex::new(Viewport { width: 1280, height: 720 })),
+            emulation: Arc::new(Mutex::new(None)),
+            proxy: Arc::new(Mutex::new(None)),
+            proxy_bypass: Arc::new(Mutex::new(Vec::new())),
+            screenshot_settings: Arc::new(Mutex::new(ScreenshotSettings::default())),
+            cdp_url: Arc::new(Mutex::new(None)),
+            launch_locale: Arc::new(Mutex::new(None)),
+            owns_browser: Arc::new(AtomicBool::new(true)),
+            monitors: PageMonitors::default(),
+            tab_events: TabEvents::default(),
+            follow_new_tabs: Arc::new(AtomicBool::new(true)),
+            read_only_worlds: Arc::new(script::ReadOnlyWorlds::default()),
         }
     }
 
@@ -80,60 +360,31 @@ impl BrowserManager {
             height: 720,
         });
 
-        // Use disable_default_args() to prevent chromiumoxide from adding --enable-automation
-        // which causes the yellow "Chrome is being controlled" banner
-        let mut config = BrowserConfig::builder()
-            .disable_default_args();
-
-        if headless {
-            // For headless mode, set a fixed viewport size
-            config = config.window_size(viewport.width as u32, viewport.height as u32);
-        } else {
-            // For headed mode, start maximized and let Chrome determine viewport
-            config = config
-                .with_head()
-                .arg("--start-maximized");
-        }
+        let proxy = self.proxy.lock().await.clone();
+        let cdp_url = self.cdp_url.lock().await.clone();
+        let (browser, mut handler) = match &cdp_url {
+            Some(url) => {
+                if let Some(proxy) = &proxy {
+                    // --proxy-server is a launch flag - only the auth answers still apply
+                    tracing::warn!("Proxy {} is ignored when connecting to a running browser", proxy.server);
+                }
+                timeout(Duration::from_secs(30), Browser::connect(url.clone()))
+                    .await
+                    .map_err(|_| anyhow!("Timed out (30s) connecting to Chrome at {}", url))?
+                    .map_err(|e| anyhow!("Failed to connect to Chrome at {} - is it running with --remote-debugging-port? {}", url, e))?
+            }
+            None => {
+                let bypass = self.proxy_bypass.lock().await.clone();
+                let locale = self.launch_locale.lock().await.clone();
+                let config = launch_config(headless, &viewport, proxy.as_ref(), &bypass, locale.as_deref())?;
 
-        // Manually add chromiumoxide's DEFAULT_ARGS, EXCEPT --enable-automation
-        // This removes the automation banner while keeping other useful defaults
-        config = config
-            .arg("--disable-background-networking")
-            .arg("--enable-features=NetworkService,NetworkServiceInProcess")
-            .arg("--disable-background-timer-throttling")
-            .arg("--disable-backgrounding-occluded-windows")
-            .arg("--disable-breakpad")
-            .arg("--disable-client-side-phishing-detection")
-            .arg("--disable-component-extensions-with-background-pages")
-            .arg("--disable-default-apps")
-            .arg("--disable-dev-shm-usage")
-            .arg("--disable-features=TranslateUI")
-            .arg("--disable-hang-monitor")
-            .arg("--disable-ipc-flooding-protection")
-            .arg("--disable-popup-blocking")
-            .arg("--disable-prompt-on-repost")
-            .arg("--disable-renderer-backgrounding")
-            .arg("--disable-sync")
-            .arg("--force-color-profile=srgb")
-            .arg("--metrics-recording-only")
-            .arg("--no-first-run")
-            .arg("--password-store=basic")
-            .arg("--use-mock-keychain")
-            .arg("--lang=en_US")
-            .arg("--disable-infobars")
-            .arg("--no-default-browser-check")
-            .arg("--disable-extensions");
-
-        let config = config.build().map_err(|e| anyhow!("Failed to build browser config: {}", e))?;
-
-        // Launch browser with timeout
-        let (browser, mut handler) = timeout(
-            Duration::from_secs(30),
-            Browser::launch(config)
-        )
-        .await
-        .map_err(|_| anyhow!("Browser launch timeout (30s) - Chrome may not be installed or is unresponsive"))?
-        .map_err(|e| anyhow!("Failed to launch browser: {}", e))?;
+                // Launch browser with timeout
+                timeout(Duration::from_secs(30), Browser::launch(config))
+                    .await
+                    .map_err(|_| anyhow!("Browser launch timeout (30s) - Chrome may not be installed or is unresponsive"))?
+                    .map_err(|e| anyhow!("Failed to launch browser: {}", e))?
+            }
+        };
 
         // Spawn handler task
         tokio::spawn(async move {
@@ -142,11 +393,20 @@ impl BrowserManager {
             }
         });
 
+        if let Err(e) = self.watch_targets(&browser).await {
+            tracing::warn!("Popup/new tab tracking unavailable: {}", e);
+        }
+
         // Small delay for Chrome to fully initialize
         tokio::time::sleep(Duration::from_millis(200)).await;
 
-        // Use existing page if available, otherwise create one
-        let page = match browser.pages().await {
+        // Use existing page if available, otherwise create one.
+        // A connected browser gets its own tab so the user's tabs are left alone.
+        let existing = match cdp_url {
+            Some(_) => Ok(Vec::new()),
+            None => browser.pages().await,
+        };
+        let page = match existing {
             Ok(pages) if !pages.is_empty() => {
                 tracing::debug!("Using existing browser page");
                 pages.into_iter().next().unwrap()
@@ -159,6 +419,11 @@ impl BrowserManager {
             }
         };
 
+        if let Some(credentials) = proxy.as_ref().and_then(proxy::credentials) {
+            page.authenticate(credentials).await
+                .map_err(|e| anyhow!("Failed to set proxy credentials: {}", e))?;
+        }
+
         // Configure viewport based on mode
         if headless {
             // For headless mode, set explicit viewport size
@@ -184,13 +449,100 @@ impl BrowserManager {
 
         // Store browser, page, and headless state
         *self.browser.lock().await = Some(browser);
+        self.owns_browser.store(cdp_url.is_none(), Ordering::SeqCst);
         *self.headless.lock().await = headless;
+        *self.viewport.lock().await = viewport;
         let mut pages = self.pages.lock().await;
         pages.clear(); // Clear any old pages
         pages.push(page);
         *self.active_tab.lock().await = 0;
 
-        tracing::info!("Browser launched (single window, headless={})", headless);
+        match &cdp_url {
+            Some(url) => tracing::info!("Connected to running browser at {}", url),
+            None => tracing::info!("Browser launched (single window, headless={})", headless),
+        }
+        Ok(())
+    }
+
+    /// Adopt tabs the page opens itself (popups, `target=_blank`) and drop tabs it closes
+    async fn watch_targets(&self, browser: &Browser) -> Result<()> {
+        let mut created = browser.event_listener::<EventTargetCreated>().await
+            .map_err(|e| anyhow!("Failed to create target listener: {}", e))?;
+        let mut destroyed = browser.event_listener::<EventTargetDestroyed>().await
+            .map_err(|e| anyhow!("Failed to create target listener: {}", e))?;
+
+        let browser_slot = Arc::clone(&self.browser);
+        let pages = Arc::clone(&self.pages);
+        let active_tab = Arc::clone(&self.active_tab);
+        let headless = Arc::clone(&self.headless);
+        let viewport = Arc::clone(&self.viewport);
+        let emulation = Arc::clone(&self.emulation);
+        let proxy = Arc::clone(&self.proxy);
+        let monitors = self.monitors.clone();
+        let tab_events = self.tab_events.clone();
+        let follow_new_tabs = Arc::clone(&self.follow_new_tabs);
+        tokio::spawn(async move {
+            while let Some(event) = created.next().await {
+                let info = &event.target_info;
+                // Tabs we create ourselves (launch, new_tab) have no opener
+                let Some(opener) = info.opener_id.as_ref().filter(|_| info.r#type == "page") else {
+                    continue;
+                };
+                // In a connected browser, popups from the user's own tabs aren't ours
+                if !pages.lock().await.iter().any(|p| p.target_id() == opener) {
+                    continue;
+                }
+
+                let Some(page) = wait_for_page(&browser_slot, info.target_id.clone()).await else {
+                    tracing::warn!("New tab {} opened by the page never became available", info.url);
+                    continue;
+                };
+                let viewport = viewport.lock().await.clone();
+                prepare_new_tab(
+                    &page,
+                    *headless.lock().await,
+                    emulation.lock().await.as_ref(),
+                    &viewport,
+                    proxy.lock().await.as_ref(),
+                ).await;
+                monitors.attach_new_page(&page).await;
+
+                let mut pages = pages.lock().await;
+                if pages.iter().any(|p| p.target_id() == page.target_id()) {
+                    continue;
+                }
+                pages.push(page);
+                let index = pages.len() - 1;
+                let switched = follow_new_tabs.load(Ordering::SeqCst);
+                if switched {
+                    *active_tab.lock().await = index;
+                }
+                drop(pages);
+
+                tracing::info!("Adopted tab {} opened by the page: {}", index, info.url);
+                tab_events.push(TabEvent::Opened { index, url: info.url.clone(), switched });
+            }
+        });
+
+        let pages = Arc::clone(&self.pages);
+        let active_tab = Arc::clone(&self.active_tab);
+        let tab_events = self.tab_events.clone();
+        tokio::spawn(async move {
+            while let Some(event) = destroyed.next().await {
+                let mut pages = pages.lock().await;
+                // Tabs closed through close_tab are already gone
+                let Some(index) = pages.iter().position(|p| p.target_id() == &event.target_id) else {
+                    continue;
+                };
+                pages.remove(index);
+
+                let mut active = active_tab.lock().await;
+                *active = active_after_close(*active, index, pages.len());
+                tracing::info!("Tab {} was closed by the page, active is now {}", index, *active);
+                tab_events.push(TabEvent::Closed { index, active: *active });
+            }
+        });
+
         Ok(())
     }
 
@@ -211,18 +563,25 @@ impl BrowserManager {
         let page = browser.new_page(url).await
             .map_err(|e| anyhow!("Failed to create new tab: {}", e))?;
 
-        // For headed mode, clear any viewport emulation so tab uses natural window size
-        let is_headless = *self.headless.lock().await;
-        if !is_headless {
-            use chromiumoxide::cdp::browser_protocol::emulation::ClearDeviceMetricsOverrideParams;
-            page.execute(ClearDeviceMetricsOverrideParams::default())
-                .await
-                .ok(); // Ignore errors - best-effort cleanup
-        }
+        let viewport = self.viewport.lock().await.clone();
+        prepare_new_tab(
+            &page,
+            *self.headless.lock().await,
+            self.emulation.lock().await.as_ref(),
+            &viewport,
+            self.proxy.lock().await.as_ref(),
+        ).await;
+        self.monitors.attach_new_page(&page).await;
 
         let mut pages = self.pages.lock().await;
-        pages.push(page);
-        let tab_index = pages.len() - 1;
+        // The target watcher may have adopted it already
+        let tab_index = match pages.iter().position(|p| p.target_id() == page.target_id()) {
+            Some(index) => index,
+            None => {
+                pages.push(page);
+                pages.len() - 1
+            }
+        };
         drop(pages);
 
         *self.active_tab.lock().await = tab_index;
@@ -258,9 +617,7 @@ impl BrowserManager {
 
         // Adjust active tab if needed
         let mut active = self.active_tab.lock().await;
-        if *active >= pages.len() {
-            *active = pages.len() - 1;
-        }
+        *active = active_after_close(*active, index, pages.len());
 
         tracing::info!("Closed tab {}, active is now {}", index, *active);
         Ok(())
@@ -301,42 +658,372 @@ impl BrowserManager {
 
     /// Take a screenshot with options
     pub async fn screenshot_with_options(&self, full_page: bool) -> Result<String> {
+        self.capture_screenshot(full_page, false, &[], None).await
+    }
+
+    /// Take a screenshot cropped to one element (scrolled into view first)
+    pub async fn screenshot_element(&self, backend_id: BackendNodeId) -> Result<String> {
+        self.scroll_to_backend_id(backend_id).await?;
+        let page = self.get_active_page().await?;
+        let box_params = GetBoxModelParams {
+            node_id: None,
+            backend_node_id: Some(CdpBackendNodeId::new(backend_id)),
+            object_id: None,
+        };
+        let box_result = page.execute(box_params).await
+            .map_err(|e| anyhow!("Element {} has no layout box (hidden?): {}", backend_id, e))?;
+
+        // Border quad is [x1,y1, x2,y2, x3,y3, x4,y4] in viewport CSS pixels
+        let quad = box_result.result.model.border.inner();
+        let xs = [quad[0], quad[2], quad[4], quad[6]];
+        let ys = [quad[1], quad[3], quad[5], quad[7]];
+        let x = xs.iter().cloned().fold(f64::INFINITY, f64::min);
+        let y = ys.iter().cloned().fold(f64::INFINITY, f64::min);
+        let width = xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max) - x;
+        let height = ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max) - y;
+        if width < 1.0 || height < 1.0 {
+            return Err(anyhow!("Element {} has an empty box", backend_id));
+        }
+
+        self.capture_screenshot(false, false, &[], Some(ClipRegion { x, y, width, height })).await
+    }
+
+    /// Set format, quality, size limits and clip region for the following screenshots
+    pub async fn set_screenshot_settings(&self, settings: ScreenshotSettings) {
+        *self.screenshot_settings.lock().await = settings;
+    }
+
+    /// Image format screenshots are returned in
+    pub async fn screenshot_format(&self) -> ImageFormat {
+        self.screenshot_settings.lock().await.format
+    }
+
+    /// Print the active tab to PDF
+    pub async fn print_pdf(&self, options: &PdfOptions) -> Result<Vec<u8>> {
         let page = self.get_active_page().await?;
+        document::print_pdf(&page, options).await
+    }
+
+    /// Save the active tab as an MHTML archive
+    pub async fn capture_mhtml(&self) -> Result<Vec<u8>> {
+        let page = self.get_active_page().await?;
+        document::capture_mhtml(&page).await
+    }
+
+    /// Convert the active tab, or one element's subtree, to markdown
+    pub async fn page_markdown(&self, root: Option<BackendNodeId>, drop_boilerplate: bool) -> Result<String> {
+        let page = self.get_active_page().await?;
+        markdown::page_markdown(&page, root, drop_boilerplate).await
+    }
+
+    /// Read rows from a table, grid or repeated list on the active tab
+    pub async fn extract_table(&self, target: &TableTarget) -> Result<Option<ExtractedTable>> {
+        let page = self.get_active_page().await?;
+        table::extract_table(&page, target).await
+    }
+
+    /// Click the active tab's "next page" control; false when there is none
+    pub async fn click_next_page(&self, css: Option<&str>) -> Result<bool> {
+        let page = self.get_active_page().await?;
+        table::click_next_page(&page, css).await
+    }
+
+    /// Take a viewport screenshot with password inputs blacked out
+    pub async fn screenshot_masked(&self) -> Result<String> {
+        self.capture_screenshot(false, true, &[], None).await
+    }
+
+    /// Take a viewport screenshot with each element's box and index drawn on it (set-of-marks)
+    pub async fn screenshot_annotated(&self, elements: &[SimplifiedElement], mask_passwords: bool) -> Result<String> {
+        self.capture_screenshot(false, mask_passwords, elements, None).await
+    }
+
+    /// Capture, optionally mask password inputs, resize, annotate and encode a screenshot.
+    /// `region` is in viewport CSS pixels (None = the run's clip, or the whole viewport).
+    async fn capture_screenshot(
+        &self,
+        full_page: bool,
+        mask_passwords: bool,
+        marks: &[SimplifiedElement],
+        region: Option<ClipRegion>,
+    ) -> Result<String> {
+        let page = self.get_active_page().await?;
+        let settings = self.screenshot_settings.lock().await.clone();
+        let region = if full_page { None } else { region.or(settings.clip) };
+
+        let mask_rects = if mask_passwords {
+            match self.password_field_rects().await {
+                Ok((_, rects)) => rects,
+                Err(e) => {
+                    tracing::warn!("Failed to locate password fields for masking: {}", e);
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+        let needs_drawing = !mask_rects.is_empty() || !marks.is_empty();
+
+        // Capture straight in the output format, unless we draw on it - then PNG (lossless for editing)
+        let capture_format = if needs_drawing { ImageFormat::Png } else { settings.format };
+        let mut params = chromiumoxide::page::ScreenshotParams::builder()
+            .format(capture_format.cdp())
+            .capture_beyond_viewport(full_page);
+        if capture_format.is_lossy() {
+            params = params.quality(settings.quality() as i64);
+        }
+        if let Some(region) = &region {
+            // CDP clips are in document coordinates
+            let metrics = page.layout_metrics().await
+                .map_err(|e| anyhow!("Failed to read scroll position: {}", e))?;
+            params = params.clip(CdpViewport {
+                x: region.x + metrics.css_layout_viewport.page_x as f64,
+                y: region.y + metrics.css_layout_viewport.page_y as f64,
+                width: region.width,
+                height: region.height,
+                scale: 1.0,
+            });
+        }
 
-        // Capture as PNG first (lossless for resizing)
         let screenshot_bytes = page
-            .screenshot(
-                chromiumoxide::page::ScreenshotParams::builder()
-                    .format(CaptureScreenshotFormat::Png)
-                    .capture_beyond_viewport(full_page)
-                    .build(),
-            )
+            .screenshot(params.build())
             .await
             .map_err(|e| anyhow!("Failed to take screenshot: {}", e))?;
 
-        // Load and resize the image to reduce token usage
-        // Target: 1280px wide (or less), maintaining aspect ratio
+        // Nothing to draw and already small enough: send it as captured
+        if !needs_drawing {
+            let (width, height) = screenshot::dimensions(&screenshot_bytes)?;
+            if settings.target_size(width, height).is_none() {
+                return Ok(base64::engine::general_purpose::STANDARD.encode(screenshot_bytes));
+            }
+        }
+
         let img = image::load_from_memory(&screenshot_bytes)
             .map_err(|e| anyhow!("Failed to decode screenshot: {}", e))?;
-
         let (width, height) = img.dimensions();
-        let max_width = 1280u32;
 
-        let resized = if width > max_width {
-            let scale = max_width as f32 / width as f32;
-            let new_height = (height as f32 * scale) as u32;
-            img.resize(max_width, new_height, image::imageops::FilterType::Lanczos3)
+        // Width in CSS pixels of what was captured, to map element boxes onto the image
+        let (css_width, origin) = match &region {
+            Some(region) => (region.width, (region.x, region.y)),
+            None if needs_drawing => {
+                let viewport_width = self.evaluate("window.innerWidth").await
+                    .map(|w| w.as_f64().unwrap_or(0.0))
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to read viewport width for screenshot overlays: {}", e);
+                        0.0
+                    });
+                (viewport_width, (0.0, 0.0))
+            }
+            None => (0.0, (0.0, 0.0)),
+        };
+
+        let img = if !mask_rects.is_empty() && css_width > 0.0 {
+            // Rects are in CSS pixels; the capture is in device pixels
+            let scale = width as f64 / css_width;
+            let mut rgb = img.to_rgb8();
+            for rect in &mask_rects {
+                let (x, y) = (rect.x - origin.0, rect.y - origin.1);
+                let x0 = (x * scale).max(0.0) as u32;
+                let y0 = (y * scale).max(0.0) as u32;
+                let x1 = ((((x + rect.width) * scale).ceil()).max(0.0) as u32).min(width);
+                let y1 = ((((y + rect.height) * scale).ceil()).max(0.0) as u32).min(height);
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        rgb.put_pixel(x, y, image::Rgb([0, 0, 0]));
+                    }
+                }
+            }
+            image::DynamicImage::ImageRgb8(rgb)
         } else {
             img
         };
 
-        // Encode as JPEG with good quality
-        let mut jpeg_bytes = Vec::new();
-        let mut cursor = std::io::Cursor::new(&mut jpeg_bytes);
-        resized.write_to(&mut cursor, image::ImageFormat::Jpeg)
-            .map_err(|e| anyhow!("Failed to encode screenshot as JPEG: {}", e))?;
+        let resized = match settings.target_size(width, height) {
+            Some((new_width, new_height)) => {
+                img.resize(new_width, new_height, image::imageops::FilterType::Lanczos3)
+            }
+            None => img,
+        };
+
+        // Draw marks after resizing so labels stay legible
+        let resized = if !marks.is_empty() && css_width > 0.0 {
+            let mut rgb = resized.to_rgb8();
+            let scale = rgb.width() as f64 / css_width;
+            if origin == (0.0, 0.0) {
+                annotate::draw_marks(&mut rgb, marks, scale);
+            } else {
+                let shifted: Vec<SimplifiedElement> = marks.iter().cloned().map(|mut element| {
+                    element.bounds.x -= origin.0;
+                    element.bounds.y -= origin.1;
+                    element
+                }).collect();
+                annotate::draw_marks(&mut rgb, &shifted, scale);
+            }
+            image::DynamicImage::ImageRgb8(rgb)
+        } else {
+            resized
+        };
+
+        let encoded = settings.encode(&resized)?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(encoded))
+    }
+
+    /// Get viewport width and bounding rects (CSS pixels) of visible password inputs
+    async fn password_field_rects(&self) -> Result<(f64, Vec<DOMRect>)> {
+        let result = self
+            .evaluate(
+                r#"(() => ({
+                    width: window.innerWidth,
+                    rects: Array.from(document.querySelectorAll('input[type="password"]'))
+                        .map(el => el.getBoundingClientRect())
+                        .filter(r => r.width > 0 && r.height > 0)
+                        .map(r => ({ x: r.x, y: r.y, width: r.width, height: r.height }))
+                }))()"#,
+            )
+            .await?;
+
+        let viewport_width = result.get("width").and_then(|w| w.as_f64()).unwrap_or(0.0);
+        let rects: Vec<DOMRect> = result
+            .get("rects")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default();
 
-        Ok(base64::engine::general_purpose::STANDARD.encode(jpeg_bytes))
+        Ok((viewport_width, rects))
+    }
+
+    /// Start recording network traffic on all open tabs (and tabs opened later)
+    pub async fn start_network_capture(&self) -> Result<()> {
+        let mut capture = self.monitors.network_capture.lock().await;
+        if *capture {
+            return Ok(());
+        }
+
+        let pages = self.pages.lock().await;
+        for page in pages.iter() {
+            self.monitors.network.attach(page).await?;
+        }
+        *capture = true;
+
+        tracing::info!("Network capture started on {} tab(s)", pages.len());
+        Ok(())
+    }
+
+    /// Get the network recorder
+    pub fn network(&self) -> &NetworkRecorder {
+        &self.monitors.network
+    }
+
+    /// Start recording console messages and page errors on all open tabs (and tabs opened later)
+    pub async fn start_console_capture(&self) -> Result<()> {
+        let mut capture = self.monitors.console_capture.lock().await;
+        if *capture {
+            return Ok(());
+        }
+
+        let pages = self.pages.lock().await;
+        for page in pages.iter() {
+            self.monitors.console.attach(page).await?;
+        }
+        *capture = true;
+
+        tracing::info!("Console capture started on {} tab(s)", pages.len());
+        Ok(())
+    }
+
+    /// Get the console recorder
+    pub fn console(&self) -> &ConsoleRecorder {
+        &self.monitors.console
+    }
+
+    /// Start watching for native JS dialogs on all open tabs (and tabs opened later)
+    pub async fn start_dialog_handling(&self, policy: DialogPolicy) -> Result<()> {
+        self.monitors.dialogs.set_policy(policy);
+
+        let mut handling = self.monitors.dialog_handling.lock().await;
+        if *handling {
+            return Ok(());
+        }
+
+        let pages = self.pages.lock().await;
+        for page in pages.iter() {
+            self.monitors.dialogs.attach(page).await?;
+        }
+        *handling = true;
+
+        tracing::info!("Dialog handling ({:?}) started on {} tab(s)", policy, pages.len());
+        Ok(())
+    }
+
+    /// Get the dialog watcher
+    pub fn dialogs(&self) -> &DialogWatcher {
+        &self.monitors.dialogs
+    }
+
+    /// Route the next launch through a proxy (None = direct connection).
+    /// Proxy auth challenges are answered with the configured credentials.
+    pub async fn set_proxy(&self, proxy: Option<ProxyConfig>, bypass: Vec<String>) {
+        if let Some(p) = &proxy {
+            if p.server.starts_with("socks") && (p.username.is_some() || p.server.contains('@')) {
+                tracing::warn!("Chrome does not support SOCKS proxy authentication - credentials for {} are ignored", proxy::display(p));
+            }
+        }
+        *self.proxy.lock().await = proxy.as_ref().map(proxy::normalize);
+        *self.proxy_bypass.lock().await = bypass;
+    }
+
+    /// Connect the next launch to an already running Chrome instead of starting one
+    /// (None = launch a new Chrome). Accepts the `ws://` debugger URL or the
+    /// `http://host:port` of a Chrome started with `--remote-debugging-port`.
+    pub async fn set_cdp_url(&self, url: Option<String>) {
+        *self.cdp_url.lock().await = url.filter(|u| !u.trim().is_empty());
+    }
+
+    /// Start the next launched Chrome in this locale (None = en_US), so requests made before
+    /// the emulation is applied and navigator.languages agree with the emulated locale
+    pub async fn set_launch_locale(&self, locale: Option<String>) {
+        *self.launch_locale.lock().await = locale.filter(|l| !l.trim().is_empty());
+    }
+
+    /// Whether the browser process was started (and will be shut down) by us
+    pub fn owns_browser(&self) -> bool {
+        self.owns_browser.load(Ordering::SeqCst)
+    }
+
+    /// Emulate a device, locale, timezone, geolocation and network conditions on every
+    /// open tab, and on tabs opened later
+    pub async fn set_emulation(&self, emulation: BrowserEmulation) -> Result<()> {
+        if emulation.geolocation.is_some() {
+            let browser_guard = self.browser.lock().await;
+            let browser = browser_guard.as_ref().ok_or_else(|| anyhow!("Browser not launched"))?;
+            let grant = GrantPermissionsParams {
+                permissions: vec![PermissionType::Geolocation],
+                origin: None,
+                browser_context_id: None,
+            };
+            browser.execute(grant).await
+                .map_err(|e| anyhow!("Failed to grant geolocation permission: {}", e))?;
+        }
+
+        let viewport = self.viewport.lock().await.clone();
+        for page in self.pages.lock().await.iter() {
+            emulation::apply(page, &emulation, &viewport).await?;
+        }
+
+        tracing::info!("Browser emulation applied: {:?}", emulation);
+        *self.emulation.lock().await = Some(emulation);
+        Ok(())
+    }
+
+    /// Whether tabs opened by the page become the active tab (default: true)
+    pub fn set_follow_new_tabs(&self, follow: bool) {
+        self.follow_new_tabs.store(follow, Ordering::SeqCst);
+    }
+
+    /// Tabs opened or closed by the page since the last call
+    pub fn take_tab_events(&self) -> Vec<TabEvent> {
+        self.tab_events.take()
     }
 
     /// Get the DOM content of the page
@@ -405,11 +1092,20 @@ impl BrowserManager {
             .map_err(|e| anyhow!("Failed to parse script result: {}", e))
     }
 
+    /// Run a model-written script on the active tab, with a time limit. `read_only` runs it
+    /// in an isolated world that can read the DOM but not change it, navigate or use the network.
+    pub async fn run_script(&self, script: &str, read_only: bool, timeout: Duration) -> Result<serde_json::Value> {
+        let page = self.get_active_page().await?;
+        let proxy_auth = self.proxy.lock().await.as_ref().is_some_and(|p| p.username.is_some());
+        script::run_script(&page, script, read_only, timeout, &self.read_only_worlds, proxy_auth).await
+    }
+
     /// Get indexed interactive elements using CDP-based extraction
     /// Returns elements with backend_node_id for stable interaction
     /// Waits for page to be ready and retries if no elements found
+    /// With `include_outline`, the result also carries an accessibility outline of the page
     #[instrument(skip(self))]
-    pub async fn get_indexed_elements(&self) -> Result<DOMExtractionResult> {
+    pub async fn get_indexed_elements(&self, include_outline: bool) -> Result<DOMExtractionResult> {
         let page = self.get_active_page().await
             .context("Failed to get active page for DOM extraction")?;
 
@@ -434,7 +1130,7 @@ impl BrowserManager {
         let mut backoff_ms = 50u64;
 
         for attempt in 0..MAX_RETRIES {
-            let result = cdp_dom::extract_dom(&page).await?;
+            let result = cdp_dom::extract_dom(&page, include_outline).await?;
 
             // Success if we have any interactive elements
             if !result.selector_map.ordered_elements.is_empty() {
@@ -456,12 +1152,41 @@ impl BrowserManager {
         // Return last result even if empty (let caller handle it)
         // This allows the agent to see the page state even without interactive elements
         tracing::warn!("DOM extraction found no interactive elements after {} retries", MAX_RETRIES);
-        cdp_dom::extract_dom(&page).await
+        cdp_dom::extract_dom(&page, include_outline).await
     }
 
     /// Click element by backend_node_id with fallback strategies
     #[instrument(skip(self), fields(backend_id = backend_id))]
     pub async fn click_by_backend_id(&self, backend_id: BackendNodeId) -> Result<()> {
+        self.click_by_backend_id_with_modifiers(backend_id, 0).await
+    }
+
+    /// Click element while holding modifier keys (keyboard::MODIFIER_* bitmask), e.g. Ctrl+Click
+    pub async fn click_by_backend_id_with_modifiers(&self, backend_id: BackendNodeId, modifiers: i64) -> Result<()> {
+        self.mouse_click_by_backend_id(backend_id, MouseButton::Left, 1, modifiers).await
+    }
+
+    /// Double-click element by backend_node_id
+    #[instrument(skip(self), fields(backend_id = backend_id))]
+    pub async fn double_click_by_backend_id(&self, backend_id: BackendNodeId) -> Result<()> {
+        self.mouse_click_by_backend_id(backend_id, MouseButton::Left, 2, 0).await
+    }
+
+    /// Right-click element by backend_node_id to open its context menu
+    #[instrument(skip(self), fields(backend_id = backend_id))]
+    pub async fn right_click_by_backend_id(&self, backend_id: BackendNodeId) -> Result<()> {
+        self.mouse_click_by_backend_id(backend_id, MouseButton::Right, 1, 0).await
+    }
+
+    /// Click an element's center with the given button and click count, falling back to
+    /// synthetic JS events when the element has no box model
+    async fn mouse_click_by_backend_id(
+        &self,
+        backend_id: BackendNodeId,
+        button: MouseButton,
+        click_count: i64,
+        modifiers: i64,
+    ) -> Result<()> {
         let page = self.get_active_page().await
             .context("Failed to get active page for click")?;
 
@@ -479,70 +1204,16 @@ impl BrowserManager {
         self.highlight_element(&page, backend_id).await;
 
         // Try geometry-based click first (GetBoxModel)
-        let box_params = GetBoxModelParams {
-            node_id: None,
-            backend_node_id: Some(CdpBackendNodeId::new(backend_id)),
-            object_id: None,
-        };
-
-        match page.execute(box_params).await {
-            Ok(box_result) => {
-                let model = box_result.result.model;
-                let content = model.content.inner();
-
-                // Calculate center point (content quad is [x1,y1, x2,y2, x3,y3, x4,y4])
-                let center_x = (content[0] + content[2] + content[4] + content[6]) / 4.0;
-                let center_y = (content[1] + content[3] + content[5] + content[7]) / 4.0;
-
-                // Dispatch mouse events
-                let mouse_down = DispatchMouseEventParams {
-                    r#type: DispatchMouseEventType::MousePressed,
-                    x: center_x,
-                    y: center_y,
-                    button: Some(MouseButton::Left),
-                    click_count: Some(1),
-                    modifiers: None,
-                    timestamp: None,
-                    delta_x: None,
-                    delta_y: None,
-                    pointer_type: None,
-                    buttons: None,
-                    tangential_pressure: None,
-                    tilt_x: None,
-                    tilt_y: None,
-                    twist: None,
-                    force: None,
-                };
-
-                let mouse_up = DispatchMouseEventParams {
-                    r#type: DispatchMouseEventType::MouseReleased,
-                    x: center_x,
-                    y: center_y,
-                    button: Some(MouseButton::Left),
-                    click_count: Some(1),
-                    modifiers: None,
-                    timestamp: None,
-                    delta_x: None,
-                    delta_y: None,
-                    pointer_type: None,
-                    buttons: None,
-                    tangential_pressure: None,
-                    tilt_x: None,
-                    tilt_y: None,
-                    twist: None,
-                    force: None,
-                };
-
-                page.execute(mouse_down).await
-                    .map_err(|e| anyhow!("Failed to dispatch mousedown: {}", e))?;
-                page.execute(mouse_up).await
-                    .map_err(|e| anyhow!("Failed to dispatch mouseup: {}", e))?;
-
-                Ok(())
+        match element_center(&page, backend_id).await {
+            Ok((center_x, center_y)) => {
+                pointer::click_at_point(&page, center_x, center_y, button, click_count, modifiers).await
             }
             Err(box_err) => {
                 // Fallback: Use JavaScript click via Runtime.callFunctionOn
                 tracing::debug!("Box model failed for {}, trying JS click fallback: {}", backend_id, box_err);
+                if modifiers != 0 {
+                    tracing::warn!("JS click fallback ignores modifier keys for {}", backend_id);
+                }
 
                 // Resolve backend_node_id to remote object
                 let resolve_params = ResolveNodeParams {
@@ -558,10 +1229,15 @@ impl BrowserManager {
                 let object_id = resolve_result.result.object.object_id
                     .ok_or_else(|| anyhow!("Node {} has no object ID", backend_id))?;
 
-                // Call click() on the element
+                let function = match (&button, click_count) {
+                    (MouseButton::Right, _) => "function() { this.scrollIntoView({block: 'center'}); this.dispatchEvent(new MouseEvent('contextmenu', {bubbles: true, cancelable: true, button: 2})); }",
+                    (_, 2..) => "function() { this.scrollIntoView({block: 'center'}); this.click(); this.click(); this.dispatchEvent(new MouseEvent('dblclick', {bubbles: true, cancelable: true, detail: 2})); }",
+                    _ => "function() { this.scrollIntoView({block: 'center'}); this.click(); }",
+                };
+
                 let call_params = CallFunctionOnParams::builder()
                     .object_id(object_id)
-                    .function_declaration("function() { this.scrollIntoView({block: 'center'}); this.click(); }")
+                    .function_declaration(function)
                     .build()
                     .map_err(|e| anyhow!("Failed to build call params: {}", e))?;
 
@@ -573,6 +1249,90 @@ impl BrowserManager {
         }
     }
 
+    /// Click at a viewport point in CSS pixels (canvas apps, maps, charts)
+    #[instrument(skip(self))]
+    pub async fn click_at(&self, x: f64, y: f64, button: MouseButton, click_count: i64) -> Result<()> {
+        let page = self.get_active_page().await
+            .context("Failed to get active page for click")?;
+        pointer::click_at_point(&page, x, y, button, click_count, 0).await
+    }
+
+    /// Drag an element onto another element or a viewport point, moving the mouse in small steps
+    /// so pointer-based libraries see a real drag. Native HTML5 drag-and-drop is intercepted and
+    /// replayed through Input.dispatchDragEvent, since synthetic mouse events don't start one.
+    #[instrument(skip(self), fields(from = from))]
+    pub async fn drag_and_drop(&self, from: BackendNodeId, to: DragTarget) -> Result<()> {
+        let page = self.get_active_page().await
+            .context("Failed to get active page for drag")?;
+
+        let scroll_params = ScrollIntoViewIfNeededParams {
+            node_id: None,
+            backend_node_id: Some(CdpBackendNodeId::new(from)),
+            object_id: None,
+            rect: None,
+        };
+        let _ = page.execute(scroll_params).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.highlight_element(&page, from).await;
+
+        let start = element_center(&page, from).await
+            .map_err(|e| anyhow!("Drag source has no geometry: {}", e))?;
+        let end = match to {
+            DragTarget::Element(target) => element_center(&page, target).await
+                .map_err(|e| anyhow!("Drop target has no geometry: {}", e))?,
+            DragTarget::Point { x, y } => (x, y),
+        };
+
+        let mut intercepted = page.event_listener::<EventDragIntercepted>().await
+            .map_err(|e| anyhow!("Failed to create drag listener: {}", e))?;
+        page.execute(SetInterceptDragsParams::new(true)).await
+            .map_err(|e| anyhow!("Failed to intercept drags: {}", e))?;
+
+        let result = async {
+            let (x, y) = start;
+            page.execute(pointer::mouse_event(DispatchMouseEventType::MouseMoved, x, y, None, None, 0)).await
+                .map_err(|e| anyhow!("Failed to dispatch mousemove: {}", e))?;
+            page.execute(pointer::mouse_event(DispatchMouseEventType::MousePressed, x, y, Some(MouseButton::Left), Some(1), 0)).await
+                .map_err(|e| anyhow!("Failed to dispatch mousedown: {}", e))?;
+
+            let mut drag_data = None;
+            for (x, y) in pointer::interpolate(start, end, pointer::DRAG_STEPS) {
+                page.execute(pointer::mouse_event(DispatchMouseEventType::MouseMoved, x, y, Some(MouseButton::Left), None, 0)).await
+                    .map_err(|e| anyhow!("Failed to dispatch mousemove: {}", e))?;
+                tokio::time::sleep(Duration::from_millis(16)).await;
+
+                if let Ok(Some(event)) = timeout(Duration::from_millis(1), intercepted.next()).await {
+                    tracing::debug!("Native drag started, switching to drag events");
+                    drag_data = Some(event.data.clone());
+                    break;
+                }
+            }
+
+            let (x, y) = end;
+            if let Some(data) = drag_data {
+                for r#type in [DispatchDragEventType::DragEnter, DispatchDragEventType::DragOver, DispatchDragEventType::Drop] {
+                    let event = DispatchDragEventParams {
+                        r#type,
+                        x,
+                        y,
+                        data: data.clone(),
+                        modifiers: None,
+                    };
+                    page.execute(event).await
+                        .map_err(|e| anyhow!("Failed to dispatch drag event: {}", e))?;
+                }
+            }
+
+            page.execute(pointer::mouse_event(DispatchMouseEventType::MouseReleased, x, y, Some(MouseButton::Left), Some(1), 0)).await
+                .map_err(|e| anyhow!("Failed to dispatch mouseup: {}", e))?;
+            Ok(())
+        }
+        .await;
+
+        let _ = page.execute(SetInterceptDragsParams::new(false)).await;
+        result
+    }
+
     /// Hover over element by backend_node_id to trigger hover states/tooltips
     #[instrument(skip(self), fields(backend_id = backend_id))]
     pub async fn hover_by_backend_id(&self, backend_id: BackendNodeId) -> Result<()> {
@@ -840,7 +1600,6 @@ impl BrowserManager {
         let page = self.get_active_page().await?;
 
         // Use insertText for reliable text input
-        use chromiumoxide::cdp::browser_protocol::input::{InsertTextParams};
         let params = InsertTextParams {
             text: text.to_string(),
         };
@@ -851,66 +1610,22 @@ impl BrowserManager {
         Ok(())
     }
 
-    /// Press a key using CDP Input.dispatchKeyEvent (more reliable than JS events)
-    pub async fn press_key(&self, key: &str) -> Result<()> {
+    /// Press keys using CDP Input.dispatchKeyEvent (more reliable than JS events).
+    /// Accepts chords ("Control+A", "Shift+Tab") and whitespace-separated sequences;
+    /// other text (IME/unicode words like "日本語") is inserted as text.
+    pub async fn press_key(&self, keys: &str) -> Result<()> {
+        let inputs = keyboard::parse_key_sequence(keys)?;
         let page = self.get_active_page().await?;
 
-        // Map key names to CDP key codes and text
-        let (key_code, code, text, key_name) = match key.to_lowercase().as_str() {
-            "enter" | "return" => (13, "Enter", "\r", "Enter"),
-            "tab" => (9, "Tab", "", "Tab"),
-            "escape" | "esc" => (27, "Escape", "", "Escape"),
-            "backspace" => (8, "Backspace", "", "Backspace"),
-            "delete" => (46, "Delete", "", "Delete"),
-            "arrowup" | "up" => (38, "ArrowUp", "", "ArrowUp"),
-            "arrowdown" | "down" => (40, "ArrowDown", "", "ArrowDown"),
-            "arrowleft" | "left" => (37, "ArrowLeft", "", "ArrowLeft"),
-            "arrowright" | "right" => (39, "ArrowRight", "", "ArrowRight"),
-            "space" => (32, "Space", " ", " "),
-            "home" => (36, "Home", "", "Home"),
-            "end" => (35, "End", "", "End"),
-            "pageup" => (33, "PageUp", "", "PageUp"),
-            "pagedown" => (34, "PageDown", "", "PageDown"),
-            _ => {
-                // For single characters, use their char code
-                if key.len() == 1 {
-                    let c = key.chars().next().unwrap();
-                    (c as i64, &format!("Key{}", c.to_uppercase())[..], key, key)
-                } else {
-                    return Err(anyhow!("Unknown key: {}", key));
+        for input in inputs {
+            match input {
+                KeyInput::Chord(chord) => press_chord(&page, &chord).await?,
+                KeyInput::Text(text) => {
+                    page.execute(InsertTextParams { text }).await
+                        .map_err(|e| anyhow!("Failed to insert text: {}", e))?;
                 }
             }
-        };
-
-        // Key down event
-        let key_down = DispatchKeyEventParams::builder()
-            .r#type(DispatchKeyEventType::KeyDown)
-            .key(key_name)
-            .code(code)
-            .windows_virtual_key_code(key_code)
-            .native_virtual_key_code(key_code);
-
-        let key_down = if !text.is_empty() {
-            key_down.text(text).build()
-        } else {
-            key_down.build()
-        }.map_err(|e| anyhow!("Failed to build key down params: {}", e))?;
-
-        page.execute(key_down).await
-            .map_err(|e| anyhow!("Failed to dispatch key down: {}", e))?;
-
-        // Key up event
-        let key_up = DispatchKeyEventParams::builder()
-            .r#type(DispatchKeyEventType::KeyUp)
-            .key(key_name)
-            .code(code)
-            .windows_virtual_key_code(key_code)
-            .native_virtual_key_code(key_code)
-            .build()
-            .map_err(|e| anyhow!("Failed to build key up params: {}", e))?;
-
-        page.execute(key_up).await
-            .map_err(|e| anyhow!("Failed to dispatch key up: {}", e))?;
+        }
 
         Ok(())
     }
@@ -1097,17 +1812,24 @@ impl BrowserManager {
         let mut pages = self.pages.lock().await;
         let mut browser_guard = self.browser.lock().await;
 
-        // Close all pages first
+        // Close all pages first - for a connected browser these are only the tabs we opened
         for page in pages.drain(..) {
             let _ = page.close().await;
         }
 
-        // Then close browser
+        // Then close browser, unless it isn't ours: dropping the handle just disconnects
         if let Some(mut browser) = browser_guard.take() {
-            let _ = browser.close().await;
+            if self.owns_browser.load(Ordering::SeqCst) {
+                let _ = browser.close().await;
+            } else {
+                tracing::info!("Disconnected from browser (left running)");
+            }
         }
 
         *self.active_tab.lock().await = 0;
+        self.monitors.reset().await;
+        self.tab_events.clear();
+        *self.emulation.lock().await = None;
         tracing::info!("Browser closed");
         Ok(())
     }
@@ -1248,3 +1970,44 @@ impl Default for BrowserManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_launch_locale_sets_lang() {
+        let browser = BrowserManager::new();
+        assert_eq!(lang_arg(browser.launch_locale.lock().await.as_deref()), "--lang=en_US");
+
+        browser.set_launch_locale(Some("de-DE".to_string())).await;
+        assert_eq!(lang_arg(browser.launch_locale.lock().await.as_deref()), "--lang=de-DE");
+
+        browser.set_launch_locale(Some(" ".to_string())).await;
+        assert_eq!(*browser.launch_locale.lock().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_set_cdp_url_ignores_blank_urls() {
+        let browser = BrowserManager::new();
+
+        browser.set_cdp_url(Some("   ".to_string())).await;
+        assert_eq!(*browser.cdp_url.lock().await, None);
+
+        browser.set_cdp_url(Some("http://127.0.0.1:9222".to_string())).await;
+        assert_eq!(browser.cdp_url.lock().await.as_deref(), Some("http://127.0.0.1:9222"));
+
+        browser.set_cdp_url(None).await;
+        assert_eq!(*browser.cdp_url.lock().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_cdp_url_is_not_launched() {
+        // Nothing listens on port 1: the connect fails instead of falling back to a launch
+        let browser = BrowserManager::new();
+        browser.set_cdp_url(Some("http://127.0.0.1:1".to_string())).await;
+        let error = browser.launch("about:blank", true, None).await.unwrap_err().to_string();
+        assert!(error.contains("127.0.0.1:1"), "{}", error);
+        assert!(browser.browser.lock().await.is_none());
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::dom::{BackendNodeId as CdpBackendNodeId, ResolveNodeParams};
+use chromiumoxide::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+use chromiumoxide::Page;
+
+use super::cdp_dom::BackendNodeId;
+
+/// Converts the element bound to `this` (and its open shadow roots) to markdown.
+/// Hidden elements, scripts and form controls are skipped; with `dropBoilerplate`,
+/// navigation, headers, footers, sidebars and cookie/ad banners are dropped too,
+/// and the page's `<main>` is used when there is one.
+const TO_MARKDOWN_JS: &str = r#"
+function(dropBoilerplate) {
+    const SKIP = new Set(['SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE', 'SVG', 'CANVAS', 'IFRAME', 'HEAD',
+        'BUTTON', 'INPUT', 'SELECT', 'OPTION', 'TEXTAREA', 'DIALOG']);
+    const BLOCK = new Set(['P', 'DIV', 'SECTION', 'ARTICLE', 'MAIN', 'HEADER', 'FOOTER', 'NAV', 'ASIDE',
+        'FIGURE', 'FIGCAPTION', 'FORM', 'FIELDSET', 'ADDRESS', 'DETAILS', 'SUMMARY', 'DL', 'DT', 'DD',
+        'CAPTION', 'LABEL']);
+    const BOILERPLATE_TAGS = new Set(['NAV', 'ASIDE']);
+    const BOILERPLATE_ROLES = new Set(['navigation', 'banner', 'contentinfo', 'complementary', 'search']);
+    const NOISE = /(^|[-_\s])(cookie|consent|gdpr|advert|ads?|sponsored|newsletter|popup|share|social)([-_\s]|$)/i;
+
+    let root = this;
+    if (dropBoilerplate && root === document.body) {
+        const main = document.querySelector('main, [role=main]');
+        const articles = document.querySelectorAll('article');
+        if (main && main.innerText.trim()) root = main;
+        else if (articles.length === 1) root = articles[0];
+    }
+
+    const isHidden = (el) => {
+        if (el.getAttribute('aria-hidden') === 'true') return true;
+        const style = getComputedStyle(el);
+        return style.display === 'none' || style.visibility === 'hidden';
+    };
+    const isBoilerplate = (el) => {
+        if (!dropBoilerplate || el === root) return false;
+        if (BOILERPLATE_TAGS.has(el.tagName) || BOILERPLATE_ROLES.has(el.getAttribute('role'))) return true;
+        const inContent = el.closest('main, article, [role=main]');
+        if ((el.tagName === 'HEADER' || el.tagName === 'FOOTER') && !inContent) return true;
+        const cls = typeof el.className === 'string' ? el.className : '';
+        return NOISE.test(el.id + ' ' + cls);
+    };
+
+    const childNodes = (el) => el.tagName === 'SLOT'
+        ? el.assignedNodes({ flatten: true })
+        : Array.from((el.shadowRoot || el).childNodes);
+    const renderChildren = (el) => childNodes(el).map(render).join('');
+    const oneLine = (s) => s.replace(/\s*\n\s*/g, ' ').trim();
+
+    function list(el) {
+        const ordered = el.tagName === 'OL';
+        let n = parseInt(el.getAttribute('start') || '1', 10) || 1;
+        const items = [];
+        for (const li of el.children) {
+            if (li.tagName !== 'LI' || isHidden(li)) continue;
+            const marker = ordered ? `${n++}. ` : '- ';
+            const body = renderChildren(li).trim().replace(/\n{2,}/g, '\n');
+            if (!body) continue;
+            items.push(body.split('\n')
+                .map((line, i) => (i === 0 ? marker : ' '.repeat(marker.length)) + line)
+                .join('\n'));
+        }
+        return items.length ? '\n\n' + items.join('\n') + '\n\n' : '';
+    }
+
+    function table(el) {
+        const rows = Array.from(el.rows)
+            .filter(row => !isHidden(row))
+            .map(row => Array.from(row.cells).map(cell => oneLine(renderChildren(cell)).replace(/\|/g, '\\|')))
+            .filter(cells => cells.some(c => c));
+        if (!rows.length) return '';
+        // Layout tables with a single column are just stacked blocks
+        const width = Math.max(...rows.map(r => r.length));
+        if (width === 1) return '\n\n' + rows.map(r => r[0]).join('\n\n') + '\n\n';
+        const line = (r) => '| ' + r.concat(Array(width - r.length).fill('')).join(' | ') + ' |';
+        const lines = [line(rows[0]), '|' + ' --- |'.repeat(width), ...rows.slice(1).map(line)];
+        return '\n\n' + lines.join('\n') + '\n\n';
+    }
+
+    function render(node) {
+        if (node.nodeType === Node.TEXT_NODE) return node.textContent.replace(/\s+/g, ' ');
+        if (node.nodeType !== Node.ELEMENT_NODE) return '';
+        const el = node;
+        const tag = el.tagName.toUpperCase();
+        if (SKIP.has(tag) || isHidden(el) || isBoilerplate(el)) return '';
+
+        switch (tag) {
+            case 'H1': case 'H2': case 'H3': case 'H4': case 'H5': case 'H6': {
+                const text = oneLine(renderChildren(el));
+                return text ? `\n\n${'#'.repeat(Number(tag[1]))} ${text}\n\n` : '';
+            }
+            case 'BR': return '\n';
+            case 'HR': return '\n\n---\n\n';
+            case 'A': {
+                const text = oneLine(renderChildren(el));
+                const href = el.href;
+                if (!text) return '';
+                if (!href || href.startsWith('javascript:')) return text;
+                return `[${text}](${href})`;
+            }
+            case 'IMG': {
+                const alt = (el.getAttribute('alt') || '').trim();
+                return alt ? `![${alt}](${el.src})` : '';
+            }
+            case 'STRONG': case 'B': {
+                const text = renderChildren(el).trim();
+                return text ? `**${text}**` : '';
+            }
+            case 'EM': case 'I': {
+                const text = renderChildren(el).trim();
+                return text ? `_${text}_` : '';
+            }
+            case 'CODE': return el.closest('pre') ? el.textContent : '`' + el.textContent.trim() + '`';
+            case 'PRE': return '\n\n```\n' + el.innerText.replace(/\n+$/, '') + '\n```\n\n';
+            case 'BLOCKQUOTE': {
+                const text = renderChildren(el).trim();
+                return text ? '\n\n' + text.split('\n').map(l => '> ' + l).join('\n') + '\n\n' : '';
+            }
+            case 'UL': case 'OL': return list(el);
+            case 'LI': return '\n- ' + renderChildren(el).trim() + '\n';
+            case 'TABLE': return table(el);
+            case 'TR': return '\n' + renderChildren(el) + '\n';
+            case 'TD': case 'TH': return ' ' + renderChildren(el) + ' ';
+            default: {
+                const content = renderChildren(el);
+                return BLOCK.has(tag) ? '\n\n' + content.trim() + '\n\n' : content;
+            }
+        }
+    }
+
+    return render(root)
+        .replace(/[ \t]+\n/g, '\n')
+        .replace(/\n (?=\S)/g, '\n')
+        .replace(/\n{3,}/g, '\n\n')
+        .trim();
+}
+"#;
+
+/// Convert the page (or the subtree of one element) to markdown
+pub async fn page_markdown(page: &Page, root: Option<BackendNodeId>, drop_boilerplate: bool) -> Result<String> {
+    let Some(backend_id) = root else {
+        let expression = format!("({}).call(document.body, {})", TO_MARKDOWN_JS, drop_boilerplate);
+        return page.evaluate_expression(expression).await
+            .map_err(|e| anyhow!("Failed to convert page to markdown: {}", e))?
+            .into_value::<String>()
+            .map_err(|e| anyhow!("Failed to read markdown: {}", e));
+    };
+
+    let params = ResolveNodeParams {
+        node_id: None,
+        backend_node_id: Some(CdpBackendNodeId::new(backend_id)),
+        object_group: Some("extract-content".to_string()),
+        execution_context_id: None,
+    };
+    let object_id = page.execute(params).await
+        .map_err(|e| anyhow!("Failed to resolve element: {}", e))?
+        .result.object.object_id
+        .ok_or_else(|| anyhow!("Element has no object ID"))?;
+
+    let params = CallFunctionOnParams::builder()
+        .object_id(object_id)
+        .function_declaration(TO_MARKDOWN_JS)
+        .argument(CallArgument::builder().value(serde_json::json!(drop_boilerplate)).build())
+        .return_by_value(true)
+        .build()
+        .map_err(|e| anyhow!("Failed to build markdown conversion call: {}", e))?;
+
+    let result = page.execute(params).await
+        .map_err(|e| anyhow!("Failed to convert element to markdown: {}", e))?;
+    if let Some(exception) = &result.result.exception_details {
+        return Err(anyhow!("Failed to convert element to markdown: {}", exception.text));
+    }
+    Ok(result.result.result.value.as_ref()
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Split markdown into chunks of at most `max_chars` characters, preferring
+/// paragraph and then line boundaries. Never splits inside a UTF-8 character.
+pub fn chunk_markdown(markdown: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = markdown.trim();
+
+    while !rest.is_empty() {
+        // Byte offset of the `max_chars`-th character, or the end of the text
+        let limit = match rest.char_indices().nth(max_chars) {
+            Some((offset, _)) => offset,
+            None => {
+                chunks.push(rest.to_string());
+                break;
+            }
+        };
+        let window = &rest[..limit];
+        let cut = window
+            .rfind("\n\n")
+            .or_else(|| window.rfind('\n'))
+            .filter(|&i| i > 0)
+            .unwrap_or(limit);
+
+        chunks.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_markdown_prefers_paragraphs() {
+        let markdown = "# Title\n\nFirst paragraph.\n\nSecond paragraph.";
+        assert_eq!(chunk_markdown(markdown, 100), vec![markdown]);
+        assert_eq!(
+            chunk_markdown(markdown, 30),
+            vec!["# Title\n\nFirst paragraph.", "Second paragraph."]
+        );
+    }
+
+    #[test]
+    fn test_chunk_markdown_multibyte() {
+        // No boundaries to split on - must cut between characters, not bytes
+        let markdown = "価格は一万円です".repeat(3);
+        let chunks = chunk_markdown(&markdown, 5);
+        assert_eq!(chunks.len(), 5);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 5));
+        assert_eq!(chunks.concat(), markdown);
+    }
+}
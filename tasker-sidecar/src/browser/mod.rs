@@ -1,5 +1,25 @@
+pub mod annotate;
 pub mod cdp_dom;
+pub mod console;
+pub mod dialog;
+pub mod document;
+pub mod emulation;
+pub mod keyboard;
 pub mod manager;
+pub mod markdown;
+pub mod network;
+pub mod pointer;
+pub mod proxy;
+pub mod screenshot;
+pub mod script;
+pub mod table;
+pub mod tabs;
 
-pub use cdp_dom::{DOMExtractionResult, SelectorMap, BackendNodeId};
+pub use cdp_dom::{DOMExtractionResult, SelectorMap, BackendNodeId, SimplifiedElement};
+pub use console::{ConsoleEntry, ConsoleLevel, ConsoleRecorder};
+pub use dialog::{DialogInfo, DialogWatcher, HandledDialog};
+pub use document::PdfOptions;
 pub use manager::BrowserManager;
+pub use network::{NetworkEntry, NetworkRecorder};
+pub use pointer::DragTarget;
+pub use tabs::TabEvent;
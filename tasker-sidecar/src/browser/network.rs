@@ -0,0 +1,689 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent,
+    EventResponseReceived, GetRequestPostDataParams, GetResponseBodyParams, ResourceTiming,
+    Response,
+};
+use chromiumoxide::Page;
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Maximum number of requests kept per run (oldest are dropped first)
+const MAX_ENTRIES: usize = 2000;
+
+/// Maximum response/request body size kept per entry (larger bodies are skipped)
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Maximum size of all response bodies kept per run - the oldest bodies are
+/// dropped first (their entries stay, just without a body)
+const MAX_TOTAL_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// Resource types whose response bodies are kept (images, fonts, media etc. are skipped)
+const BODY_RESOURCE_TYPES: &[&str] = &["Document", "XHR", "Fetch", "EventSource", "Other"];
+
+/// A single captured request/response pair
+#[derive(Debug, Clone)]
+pub struct NetworkEntry {
+    /// Capture order - increases monotonically for the lifetime of the recorder
+    pub seq: u64,
+    pub request_id: String,
+    pub method: String,
+    pub url: String,
+    pub resource_type: Option<String>,
+    pub request_headers: Value,
+    pub post_data: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub status: Option<i64>,
+    pub status_text: String,
+    pub response_headers: Value,
+    pub mime_type: String,
+    pub protocol: Option<String>,
+    pub encoded_data_length: f64,
+    pub body: Option<String>,
+    pub body_base64: bool,
+    pub error: Option<String>,
+    pub finished: bool,
+    /// Monotonic timestamps (seconds) used for HAR timings
+    start_ts: f64,
+    end_ts: Option<f64>,
+    timing: Option<ResourceTiming>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    /// Consecutive sequence numbers, oldest first
+    entries: VecDeque<NetworkEntry>,
+    /// Live request id -> sequence number of its entry
+    index: HashMap<String, u64>,
+    next_seq: u64,
+    /// Size of all stored response bodies
+    body_bytes: usize,
+}
+
+impl RecorderState {
+    fn get_mut(&mut self, request_id: &str) -> Option<&mut NetworkEntry> {
+        let seq = *self.index.get(request_id)?;
+        let first = self.entries.front()?.seq;
+        let pos = seq.checked_sub(first)? as usize;
+        self.entries.get_mut(pos)
+    }
+
+    fn push(&mut self, mut entry: NetworkEntry) {
+        entry.seq = self.next_seq;
+        self.next_seq += 1;
+
+        if self.entries.len() >= MAX_ENTRIES {
+            if let Some(oldest) = self.entries.pop_front() {
+                self.body_bytes -= oldest.body.as_ref().map_or(0, String::len);
+                if self.index.get(&oldest.request_id) == Some(&oldest.seq) {
+                    self.index.remove(&oldest.request_id);
+                }
+            }
+        }
+        self.index.insert(entry.request_id.clone(), entry.seq);
+        self.entries.push_back(entry);
+    }
+
+    /// Store a response body, dropping the oldest bodies to stay within MAX_TOTAL_BODY_BYTES
+    fn set_body(&mut self, request_id: &str, body: String, base64: bool) {
+        let len = body.len();
+        let Some(entry) = self.get_mut(request_id) else {
+            return;
+        };
+        let replaced = entry.body.replace(body).map_or(0, |b| b.len());
+        entry.body_base64 = base64;
+        let seq = entry.seq;
+        self.body_bytes = self.body_bytes - replaced + len;
+
+        for oldest in self.entries.iter_mut() {
+            if self.body_bytes <= MAX_TOTAL_BODY_BYTES {
+                break;
+            }
+            if oldest.seq == seq {
+                continue;
+            }
+            if let Some(dropped) = oldest.body.take() {
+                self.body_bytes -= dropped.len();
+                oldest.body_base64 = false;
+            }
+        }
+    }
+}
+
+/// Records network traffic of browser pages via CDP Network events
+#[derive(Clone, Default)]
+pub struct NetworkRecorder {
+    state: Arc<Mutex<RecorderState>>,
+    /// Signalled whenever a request finishes (successfully or not)
+    finished_notify: Arc<Notify>,
+    /// Whether response bodies are fetched - off until a HAR or a network tool needs them
+    capture_bodies: Arc<AtomicBool>,
+}
+
+impl NetworkRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch and keep response bodies of requests that finish from now on
+    pub fn enable_body_capture(&self) {
+        self.capture_bodies.store(true, Ordering::SeqCst);
+    }
+
+    /// Enable the Network domain on a page and start recording its traffic
+    pub async fn attach(&self, page: &Page) -> Result<()> {
+        page.execute(EnableParams::default())
+            .await
+            .map_err(|e| anyhow!("Failed to enable network domain: {}", e))?;
+
+        let mut requests = page.event_listener::<EventRequestWillBeSent>().await
+            .map_err(|e| anyhow!("Failed to create request listener: {}", e))?;
+        let mut responses = page.event_listener::<EventResponseReceived>().await
+            .map_err(|e| anyhow!("Failed to create response listener: {}", e))?;
+        let mut finished = page.event_listener::<EventLoadingFinished>().await
+            .map_err(|e| anyhow!("Failed to create loading finished listener: {}", e))?;
+        let mut failed = page.event_listener::<EventLoadingFailed>().await
+            .map_err(|e| anyhow!("Failed to create loading failed listener: {}", e))?;
+
+        let recorder = self.clone();
+        let req_page = page.clone();
+        tokio::spawn(async move {
+            while let Some(event) = requests.next().await {
+                recorder.on_request(&req_page, &event);
+            }
+        });
+
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = responses.next().await {
+                recorder.on_response(event.request_id.as_ref(), &event.response);
+            }
+        });
+
+        let recorder = self.clone();
+        let body_page = page.clone();
+        tokio::spawn(async move {
+            while let Some(event) = finished.next().await {
+                recorder.on_finished(&body_page, &event);
+            }
+        });
+
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = failed.next().await {
+                {
+                    let mut state = recorder.state.lock().unwrap();
+                    if let Some(entry) = state.get_mut(event.request_id.as_ref()) {
+                        entry.error = Some(event.error_text.clone());
+                        entry.end_ts = Some(*event.timestamp.inner());
+                        entry.finished = true;
+                    }
+                }
+                recorder.finished_notify.notify_waiters();
+            }
+        });
+
+        tracing::debug!("Network recorder attached to page");
+        Ok(())
+    }
+
+    fn on_request(&self, page: &Page, event: &EventRequestWillBeSent) {
+        let request_id = event.request_id.as_ref().to_string();
+
+        // Redirects reuse the request id - close the previous hop with the redirect response
+        if let Some(redirect) = &event.redirect_response {
+            self.on_response(&request_id, redirect);
+            let mut state = self.state.lock().unwrap();
+            if let Some(entry) = state.get_mut(&request_id) {
+                entry.end_ts = Some(*event.timestamp.inner());
+                entry.finished = true;
+            }
+            state.index.remove(&request_id);
+        }
+
+        let started_at = Utc
+            .timestamp_millis_opt((*event.wall_time.inner() * 1000.0) as i64)
+            .single()
+            .unwrap_or_else(Utc::now);
+
+        // Insert right away so response events always find the entry
+        self.state.lock().unwrap().push(NetworkEntry {
+            seq: 0,
+            request_id: request_id.clone(),
+            method: event.request.method.clone(),
+            url: event.request.url.clone(),
+            resource_type: event.r#type.as_ref().map(|t| t.as_ref().to_string()),
+            request_headers: event.request.headers.inner().clone(),
+            post_data: None,
+            started_at,
+            status: None,
+            status_text: String::new(),
+            response_headers: Value::Null,
+            mime_type: String::new(),
+            protocol: None,
+            encoded_data_length: 0.0,
+            body: None,
+            body_base64: false,
+            error: None,
+            finished: false,
+            start_ts: *event.timestamp.inner(),
+            end_ts: None,
+            timing: None,
+        });
+
+        // Fetch the body in the background - awaiting it here would hold up the next requests
+        if event.request.has_post_data == Some(true) {
+            let recorder = self.clone();
+            let page = page.clone();
+            let cdp_request_id = event.request_id.clone();
+            tokio::spawn(async move {
+                let post_data = page
+                    .execute(GetRequestPostDataParams::new(cdp_request_id))
+                    .await
+                    .ok()
+                    .map(|r| r.result.post_data.clone())
+                    .filter(|data| data.len() <= MAX_BODY_BYTES);
+
+                let mut state = recorder.state.lock().unwrap();
+                if let Some(entry) = state.get_mut(&request_id) {
+                    entry.post_data = post_data;
+                }
+            });
+        }
+    }
+
+    fn on_response(&self, request_id: &str, response: &Response) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(entry) = state.get_mut(request_id) {
+            entry.status = Some(response.status);
+            entry.status_text = response.status_text.clone();
+            entry.response_headers = response.headers.inner().clone();
+            entry.mime_type = response.mime_type.clone();
+            entry.protocol = response.protocol.clone();
+            entry.timing = response.timing.clone();
+        }
+    }
+
+    fn on_finished(&self, page: &Page, event: &EventLoadingFinished) {
+        let request_id = event.request_id.as_ref().to_string();
+        let fetch_body = {
+            let mut state = self.state.lock().unwrap();
+            match state.get_mut(&request_id) {
+                Some(entry) => {
+                    entry.encoded_data_length = event.encoded_data_length;
+                    entry.end_ts = Some(*event.timestamp.inner());
+                    let wants_body = entry
+                        .resource_type
+                        .as_deref()
+                        .map(|t| BODY_RESOURCE_TYPES.contains(&t))
+                        .unwrap_or(true);
+                    self.capture_bodies.load(Ordering::SeqCst)
+                        && wants_body
+                        && (event.encoded_data_length as usize) <= MAX_BODY_BYTES
+                }
+                None => return,
+            }
+        };
+
+        if !fetch_body {
+            self.mark_finished(&request_id);
+            return;
+        }
+
+        // Fetch the body in the background - awaiting it here would hold up the next events.
+        // The entry only counts as finished once its body is stored.
+        let recorder = self.clone();
+        let page = page.clone();
+        let cdp_request_id = event.request_id.clone();
+        tokio::spawn(async move {
+            let body = page
+                .execute(GetResponseBodyParams::new(cdp_request_id))
+                .await
+                .ok()
+                .map(|r| (r.result.body.clone(), r.result.base64_encoded))
+                .filter(|(body, _)| body.len() <= MAX_BODY_BYTES);
+
+            if let Some((body, base64)) = body {
+                recorder.state.lock().unwrap().set_body(&request_id, body, base64);
+            }
+            recorder.mark_finished(&request_id);
+        });
+    }
+
+    fn mark_finished(&self, request_id: &str) {
+        if let Some(entry) = self.state.lock().unwrap().get_mut(request_id) {
+            entry.finished = true;
+        }
+        self.finished_notify.notify_waiters();
+    }
+
+    /// Snapshot of all captured entries, oldest first
+    pub fn entries(&self) -> Vec<NetworkEntry> {
+        self.state.lock().unwrap().entries.iter().cloned().collect()
+    }
+
+    /// Sequence number the next captured request will get
+    pub fn next_seq(&self) -> u64 {
+        self.state.lock().unwrap().next_seq
+    }
+
+    /// Most recent finished request matching the URL pattern (and method, if given)
+    pub fn find_latest(&self, url_pattern: &str, method: Option<&str>, min_seq: u64) -> Option<NetworkEntry> {
+        let state = self.state.lock().unwrap();
+        state
+            .entries
+            .iter()
+            .rev()
+            .take_while(|e| e.seq >= min_seq)
+            .find(|e| {
+                e.finished
+                    && url_matches(&e.url, url_pattern)
+                    && method.map(|m| e.method.eq_ignore_ascii_case(m)).unwrap_or(true)
+            })
+            .cloned()
+    }
+
+    /// Wait until a request matching the URL pattern has finished.
+    /// Only requests with `seq >= min_seq` count; pass `next_seq()` to ignore past traffic.
+    pub async fn wait_for(
+        &self,
+        url_pattern: &str,
+        method: Option<&str>,
+        min_seq: u64,
+        timeout: Duration,
+    ) -> Option<NetworkEntry> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Register for notification before checking to avoid missing a wakeup
+            let notified = self.finished_notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(entry) = self.find_latest(url_pattern, method, min_seq) {
+                return Some(entry);
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return None;
+            }
+        }
+    }
+
+    /// Number of captured entries
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Whether nothing has been captured
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Export captured traffic as a HAR 1.2 document
+    pub fn to_har(&self) -> Value {
+        let entries: Vec<Value> = self.entries().iter().map(entry_to_har).collect();
+        json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "Tasker", "version": env!("CARGO_PKG_VERSION") },
+                "pages": [],
+                "entries": entries,
+            }
+        })
+    }
+}
+
+/// Match a URL against a pattern: `*` wildcards if present, otherwise a substring match
+pub fn url_matches(url: &str, pattern: &str) -> bool {
+    if !pattern.contains('*') {
+        return url.contains(pattern);
+    }
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut rest = url;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            // Pattern without leading '*' must match the start
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            // Pattern without trailing '*' must match the end
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+impl NetworkEntry {
+    /// Response body as text (base64 bodies are decoded if they are valid UTF-8)
+    pub fn body_text(&self) -> Option<String> {
+        let body = self.body.as_ref()?;
+        if !self.body_base64 {
+            return Some(body.clone());
+        }
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD
+            .decode(body)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    }
+}
+
+/// Convert CDP headers object to HAR name/value list
+fn headers_to_har(headers: &Value) -> Vec<Value> {
+    headers
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .map(|(name, value)| {
+                    json!({ "name": name, "value": value.as_str().unwrap_or_default() })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Percent-decode a query string component, keeping the raw text if it is not valid UTF-8
+fn decode_component(raw: &str) -> String {
+    let raw = raw.replace('+', " ");
+    urlencoding::decode(&raw)
+        .map(|c| c.into_owned())
+        .unwrap_or(raw)
+}
+
+/// Duration between two ResourceTiming offsets (ms), or -1 if not applicable
+fn phase(start: f64, end: f64) -> f64 {
+    if start >= 0.0 && end >= start {
+        end - start
+    } else {
+        -1.0
+    }
+}
+
+fn entry_to_har(entry: &NetworkEntry) -> Value {
+    let total_ms = entry
+        .end_ts
+        .map(|end| ((end - entry.start_ts) * 1000.0).max(0.0))
+        .unwrap_or(0.0);
+
+    let timings = match &entry.timing {
+        Some(t) => {
+            let wait = phase(t.send_end, t.receive_headers_end);
+            let receive = entry
+                .end_ts
+                .map(|end| ((end - t.request_time) * 1000.0 - t.receive_headers_end).max(0.0))
+                .unwrap_or(0.0);
+            json!({
+                "blocked": -1,
+                "dns": phase(t.dns_start, t.dns_end),
+                "connect": phase(t.connect_start, t.connect_end),
+                "ssl": phase(t.ssl_start, t.ssl_end),
+                "send": phase(t.send_start, t.send_end).max(0.0),
+                "wait": wait.max(0.0),
+                "receive": receive,
+            })
+        }
+        None => json!({ "send": 0, "wait": total_ms, "receive": 0 }),
+    };
+
+    let http_version = entry.protocol.clone().unwrap_or_else(|| "HTTP/1.1".to_string());
+
+    let query_string: Vec<Value> = entry
+        .url
+        .split_once('?')
+        .map(|(_, query)| query.split('#').next().unwrap_or_default())
+        .map(|query| {
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    json!({ "name": decode_component(name), "value": decode_component(value) })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut request = json!({
+        "method": entry.method,
+        "url": entry.url,
+        "httpVersion": http_version,
+        "cookies": [],
+        "headers": headers_to_har(&entry.request_headers),
+        "queryString": query_string,
+        "headersSize": -1,
+        "bodySize": entry.post_data.as_ref().map(|d| d.len() as i64).unwrap_or(0),
+    });
+    if let Some(post_data) = &entry.post_data {
+        let mime_type = entry
+            .request_headers
+            .as_object()
+            .and_then(|h| h.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type")))
+            .and_then(|(_, v)| v.as_str())
+            .unwrap_or_default();
+        request["postData"] = json!({ "mimeType": mime_type, "text": post_data });
+    }
+
+    let mut content = json!({
+        "size": entry.body.as_ref().map(|b| b.len() as i64).unwrap_or(entry.encoded_data_length as i64),
+        "mimeType": entry.mime_type,
+    });
+    if let Some(body) = &entry.body {
+        content["text"] = json!(body);
+        if entry.body_base64 {
+            content["encoding"] = json!("base64");
+        }
+    }
+
+    let redirect_url = entry
+        .response_headers
+        .as_object()
+        .and_then(|h| h.iter().find(|(k, _)| k.eq_ignore_ascii_case("location")))
+        .and_then(|(_, v)| v.as_str())
+        .unwrap_or_default();
+
+    let mut response = json!({
+        "status": entry.status.unwrap_or(0),
+        "statusText": entry.status_text,
+        "httpVersion": http_version,
+        "cookies": [],
+        "headers": headers_to_har(&entry.response_headers),
+        "content": content,
+        "redirectURL": redirect_url,
+        "headersSize": -1,
+        "bodySize": entry.encoded_data_length as i64,
+    });
+    if let Some(error) = &entry.error {
+        response["_error"] = json!(error);
+    }
+
+    let mut har_entry = json!({
+        "startedDateTime": entry.started_at.to_rfc3339(),
+        "time": total_ms,
+        "request": request,
+        "response": response,
+        "cache": {},
+        "timings": timings,
+    });
+    if let Some(resource_type) = &entry.resource_type {
+        har_entry["_resourceType"] = json!(resource_type);
+    }
+    har_entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(request_id: &str) -> NetworkEntry {
+        NetworkEntry {
+            seq: 0,
+            request_id: request_id.to_string(),
+            method: "GET".to_string(),
+            url: format!("https://example.com/{}", request_id),
+            resource_type: Some("Fetch".to_string()),
+            request_headers: Value::Null,
+            post_data: None,
+            started_at: Utc::now(),
+            status: None,
+            status_text: String::new(),
+            response_headers: Value::Null,
+            mime_type: String::new(),
+            protocol: None,
+            encoded_data_length: 0.0,
+            body: None,
+            body_base64: false,
+            error: None,
+            finished: false,
+            start_ts: 0.0,
+            end_ts: None,
+            timing: None,
+        }
+    }
+
+    #[test]
+    fn test_entry_cap_drops_oldest() {
+        let mut state = RecorderState::default();
+        for i in 0..MAX_ENTRIES + 5 {
+            state.push(entry(&format!("r{}", i)));
+        }
+        assert_eq!(state.entries.len(), MAX_ENTRIES);
+        assert_eq!(state.entries.front().unwrap().request_id, "r5");
+        assert!(state.get_mut("r4").is_none());
+
+        // Live entries are still found after the buffer shifted
+        let last = format!("r{}", MAX_ENTRIES + 4);
+        state.get_mut(&last).unwrap().status = Some(200);
+        assert_eq!(state.entries.back().unwrap().status, Some(200));
+        assert_eq!(state.get_mut("r5").unwrap().seq, 5);
+    }
+
+    #[tokio::test]
+    async fn test_finished_requests_wake_waiters() {
+        let recorder = NetworkRecorder::new();
+        assert!(!recorder.capture_bodies.load(Ordering::SeqCst));
+        recorder.state.lock().unwrap().push(entry("r1"));
+
+        let waiter = recorder.clone();
+        let wait = tokio::spawn(async move {
+            waiter.wait_for("example.com/r1", None, 0, Duration::from_secs(5)).await
+        });
+        tokio::task::yield_now().await;
+        recorder.mark_finished("r1");
+
+        let found = wait.await.unwrap().expect("waiter should see the finished request");
+        assert_eq!(found.request_id, "r1");
+    }
+
+    #[test]
+    fn test_total_body_size_is_bounded() {
+        let mut state = RecorderState::default();
+        let body_len = MAX_BODY_BYTES;
+        let fits = MAX_TOTAL_BODY_BYTES / body_len;
+        for i in 0..fits + 3 {
+            let id = format!("r{}", i);
+            state.push(entry(&id));
+            state.set_body(&id, "x".repeat(body_len), false);
+        }
+
+        assert!(state.body_bytes <= MAX_TOTAL_BODY_BYTES);
+        let stored: usize = state.entries.iter().filter_map(|e| e.body.as_ref()).map(String::len).sum();
+        assert_eq!(stored, state.body_bytes);
+        // The oldest bodies were dropped, their entries kept
+        assert_eq!(state.entries.len(), fits + 3);
+        assert!(state.entries.iter().take(3).all(|e| e.body.is_none()));
+        assert!(state.entries.back().unwrap().body.is_some());
+    }
+
+    #[test]
+    fn test_url_matches_substring() {
+        assert!(url_matches("https://shop.example.com/api/prices?id=1", "/api/prices"));
+        assert!(!url_matches("https://shop.example.com/api/stock", "/api/prices"));
+    }
+
+    #[test]
+    fn test_url_matches_wildcards() {
+        let url = "https://shop.example.com/api/v2/prices?id=1";
+        assert!(url_matches(url, "*/api/*/prices*"));
+        assert!(url_matches(url, "https://shop.example.com/*"));
+        assert!(url_matches(url, "*id=1"));
+        assert!(!url_matches(url, "*id=2"));
+        assert!(!url_matches(url, "http://*"));
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::input::{
+    DispatchMouseEventParams, DispatchMouseEventType, MouseButton,
+};
+use chromiumoxide::Page;
+
+use crate::browser::cdp_dom::BackendNodeId;
+
+/// Number of intermediate mouse moves between drag start and drop
+pub const DRAG_STEPS: usize = 12;
+
+/// Where a drag ends: another element, or a viewport point in CSS pixels
+#[derive(Debug, Clone, Copy)]
+pub enum DragTarget {
+    Element(BackendNodeId),
+    Point { x: f64, y: f64 },
+}
+
+/// Parse a button name ("left", "right", "middle")
+pub fn parse_button(name: &str) -> Result<MouseButton> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "" | "left" => Ok(MouseButton::Left),
+        "right" => Ok(MouseButton::Right),
+        "middle" => Ok(MouseButton::Middle),
+        other => Err(anyhow!("Unknown mouse button '{}'", other)),
+    }
+}
+
+/// `buttons` bitmask for a pressed button (MouseEvent.buttons)
+fn buttons_mask(button: &MouseButton) -> i64 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Right => 2,
+        MouseButton::Middle => 4,
+        MouseButton::Back => 8,
+        MouseButton::Forward => 16,
+        MouseButton::None => 0,
+    }
+}
+
+/// Build a mouse event; `button` is the button being pressed/released or held during a move
+pub fn mouse_event(
+    r#type: DispatchMouseEventType,
+    x: f64,
+    y: f64,
+    button: Option<MouseButton>,
+    click_count: Option<i64>,
+    modifiers: i64,
+) -> DispatchMouseEventParams {
+    DispatchMouseEventParams {
+        r#type,
+        x,
+        y,
+        buttons: button.as_ref().map(buttons_mask),
+        button,
+        click_count,
+        modifiers: Some(modifiers),
+        timestamp: None,
+        delta_x: None,
+        delta_y: None,
+        pointer_type: None,
+        tangential_pressure: None,
+        tilt_x: None,
+        tilt_y: None,
+        twist: None,
+        force: None,
+    }
+}
+
+/// Press and release `button` at a point `click_count` times (2 = double-click).
+/// Each press carries its running click count so the page sees click, click, dblclick.
+pub async fn click_at_point(
+    page: &Page,
+    x: f64,
+    y: f64,
+    button: MouseButton,
+    click_count: i64,
+    modifiers: i64,
+) -> Result<()> {
+    page.execute(mouse_event(DispatchMouseEventType::MouseMoved, x, y, None, None, modifiers))
+        .await
+        .map_err(|e| anyhow!("Failed to dispatch mousemove: {}", e))?;
+
+    for count in 1..=click_count.max(1) {
+        page.execute(mouse_event(DispatchMouseEventType::MousePressed, x, y, Some(button.clone()), Some(count), modifiers))
+            .await
+            .map_err(|e| anyhow!("Failed to dispatch mousedown: {}", e))?;
+        page.execute(mouse_event(DispatchMouseEventType::MouseReleased, x, y, Some(button.clone()), Some(count), modifiers))
+            .await
+            .map_err(|e| anyhow!("Failed to dispatch mouseup: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Evenly spaced points from `from` (exclusive) to `to` (inclusive)
+pub fn interpolate(from: (f64, f64), to: (f64, f64), steps: usize) -> Vec<(f64, f64)> {
+    let steps = steps.max(1);
+    (1..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_ends_on_target() {
+        let points = interpolate((0.0, 0.0), (100.0, 50.0), 4);
+        assert_eq!(points, vec![(25.0, 12.5), (50.0, 25.0), (75.0, 37.5), (100.0, 50.0)]);
+        assert_eq!(interpolate((1.0, 1.0), (2.0, 2.0), 0), vec![(2.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_parse_button() {
+        assert!(matches!(parse_button("Right").unwrap(), MouseButton::Right));
+        assert!(matches!(parse_button("").unwrap(), MouseButton::Left));
+        assert!(parse_button("thumb").is_err());
+    }
+}
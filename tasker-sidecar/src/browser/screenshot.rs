@@ -0,0 +1,116 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageReader};
+use std::io::Cursor;
+
+use crate::models::{ImageFormat, ScreenshotSettings};
+
+/// Quality used when a run doesn't set one
+pub const DEFAULT_QUALITY: u8 = 80;
+
+impl ImageFormat {
+    pub fn cdp(&self) -> CaptureScreenshotFormat {
+        match self {
+            ImageFormat::Jpeg => CaptureScreenshotFormat::Jpeg,
+            ImageFormat::Png => CaptureScreenshotFormat::Png,
+            ImageFormat::Webp => CaptureScreenshotFormat::Webp,
+        }
+    }
+
+    /// Whether the quality setting applies
+    pub fn is_lossy(&self) -> bool {
+        !matches!(self, ImageFormat::Png)
+    }
+}
+
+impl ScreenshotSettings {
+    pub fn quality(&self) -> u8 {
+        self.quality.unwrap_or(DEFAULT_QUALITY).clamp(1, 100)
+    }
+
+    /// Size to downscale a `width` x `height` image to, or None when it already fits
+    pub fn target_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let mut scale = 1.0f64;
+        if self.max_width > 0 && width > self.max_width {
+            scale = scale.min(self.max_width as f64 / width as f64);
+        }
+        if let Some(max_height) = self.max_height.filter(|h| *h > 0) {
+            if height > max_height {
+                scale = scale.min(max_height as f64 / height as f64);
+            }
+        }
+        if scale >= 1.0 {
+            return None;
+        }
+        let scaled = |v: u32| ((v as f64 * scale).round() as u32).max(1);
+        Some((scaled(width), scaled(height)))
+    }
+
+    /// Encode an image in the configured format. WebP re-encodes are lossless
+    /// (the image crate has no lossy WebP encoder).
+    pub fn encode(&self, img: &DynamicImage) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        match self.format {
+            ImageFormat::Jpeg => {
+                // JPEG has no alpha channel
+                let encoder = JpegEncoder::new_with_quality(&mut bytes, self.quality());
+                DynamicImage::ImageRgb8(img.to_rgb8()).write_with_encoder(encoder)
+            }
+            ImageFormat::Png => img.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png),
+            ImageFormat::Webp => {
+                DynamicImage::ImageRgba8(img.to_rgba8()).write_with_encoder(WebPEncoder::new_lossless(&mut bytes))
+            }
+        }
+        .map_err(|e| anyhow!("Failed to encode screenshot as {:?}: {}", self.format, e))?;
+        Ok(bytes)
+    }
+}
+
+/// Image dimensions read from the header, without decoding the pixels
+pub fn dimensions(bytes: &[u8]) -> Result<(u32, u32)> {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| anyhow!("Failed to read screenshot header: {}", e))?
+        .into_dimensions()
+        .map_err(|e| anyhow!("Failed to read screenshot size: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_size() {
+        let settings = ScreenshotSettings::default();
+        assert_eq!(settings.target_size(1280, 5000), None);
+        assert_eq!(settings.target_size(2560, 1440), Some((1280, 720)));
+
+        let bounded = ScreenshotSettings { max_width: 0, max_height: Some(500), ..Default::default() };
+        assert_eq!(bounded.target_size(2000, 1000), Some((1000, 500)));
+        assert_eq!(bounded.target_size(2000, 400), None);
+    }
+
+    #[test]
+    fn test_encode_and_read_dimensions() {
+        let img = DynamicImage::new_rgba8(40, 20);
+        for format in [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::Webp] {
+            let settings = ScreenshotSettings { format, ..Default::default() };
+            let bytes = settings.encode(&img).unwrap();
+            assert_eq!(dimensions(&bytes).unwrap(), (40, 20), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_settings_deserialize() {
+        let settings: ScreenshotSettings = serde_json::from_value(serde_json::json!({
+            "format": "jpg",
+            "quality": 60
+        }))
+        .unwrap();
+        assert_eq!(settings.format, ImageFormat::Jpeg);
+        assert_eq!(settings.max_width, 1280);
+        assert_eq!(settings.quality(), 60);
+    }
+}
@@ -0,0 +1,407 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    self, EventRequestPaused, FailRequestParams, RequestPattern, RequestStage,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::cdp::browser_protocol::page::{
+    CreateIsolatedWorldParams, EventFrameRequestedNavigation, GetFrameTreeParams, StopLoadingParams,
+};
+use chromiumoxide::cdp::js_protocol::runtime::{
+    EvaluateParams, ExceptionDetails, ExecutionContextId, RemoteObjectType, TimeDelta,
+};
+use chromiumoxide::listeners::EventStream;
+use chromiumoxide::Page;
+use futures_util::{FutureExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+/// Name of the isolated world read-only scripts run in
+const READ_ONLY_WORLD: &str = "tasker-read-only";
+
+/// Installed once in the isolated world of each document before its first read-only script.
+/// Prototypes and globals are per world, so none of this affects the page. Locks network,
+/// storage, timers, code loading, DOM writes and simulated input, then defines
+/// `__taskerRun(source)`, which runs the script as strict code with `window`, `document` and
+/// `location` shadowed by proxies that refuse navigation. Navigation through a leaked real
+/// Location is the one thing JS can't lock (it is unforgeable); run_script blocks it over CDP.
+const READ_ONLY_GUARD_JS: &str = r#"
+(() => {
+    const deny = (what) => function () {
+        throw new Error(`${what} is not allowed - JavaScript is read-only in this workflow`);
+    };
+    const lock = (obj, name, what) => {
+        const d = obj && Object.getOwnPropertyDescriptor(obj, name);
+        if (!d || !d.configurable) return;
+        Object.defineProperty(obj, name, { configurable: false, get: deny(what || name), set: deny(what || name) });
+    };
+
+    const realWindow = window;
+    const realDocument = document;
+    const realLocation = location;
+    const RealFunction = Function;
+
+    // Every setter and these methods on DOM prototypes: page changes, input, history, storage
+    const METHODS = new Set(['appendChild', 'insertBefore', 'removeChild', 'replaceChild', 'append', 'prepend',
+        'after', 'before', 'replaceWith', 'replaceChildren', 'remove', 'setAttribute', 'setAttributeNS',
+        'setAttributeNode', 'setAttributeNodeNS', 'removeAttribute', 'removeAttributeNS', 'removeAttributeNode',
+        'toggleAttribute', 'setNamedItem', 'setNamedItemNS', 'removeNamedItem', 'insertAdjacentHTML',
+        'insertAdjacentElement', 'insertAdjacentText', 'attachShadow', 'setHTMLUnsafe', 'click', 'focus', 'blur',
+        'submit', 'requestSubmit', 'reset', 'dispatchEvent', 'addEventListener', 'showModal', 'show', 'close',
+        'showPopover', 'hidePopover', 'togglePopover', 'play', 'pause', 'load', 'select', 'setRangeText',
+        'setSelectionRange', 'setCustomValidity', 'stepUp', 'stepDown', 'showPicker', 'requestFullscreen',
+        'requestPointerLock', 'scroll', 'scrollTo', 'scrollBy', 'scrollIntoView', 'write', 'writeln', 'open',
+        'execCommand', 'pushState', 'replaceState', 'back', 'forward', 'go', 'add', 'toggle', 'replace',
+        'setProperty', 'removeProperty', 'insertRule', 'deleteRule', 'addRule', 'removeRule', 'deleteContents',
+        'extractContents', 'insertNode', 'surroundContents', 'addRange', 'removeRange', 'removeAllRanges',
+        'collapse', 'extend', 'selectAllChildren', 'setBaseAndExtent', 'deleteFromDocument', 'importNode',
+        'adoptNode', 'normalize', 'setItem', 'removeItem', 'clear', 'addModule']);
+    const PROTOS = Object.getOwnPropertyNames(realWindow)
+        .filter(n => /^(EventTarget|Node|Element|CharacterData|Text|Document|DocumentFragment|ShadowRoot|Attr|NamedNodeMap|History|DOMTokenList|CSSStyleDeclaration|CSSStyleSheet|CSSRule|AbstractRange|Range|Selection|Storage|Worklet|SVGAnimatedString|(HTML|SVG)\w*Element)$/.test(n))
+        .map(n => realWindow[n] && realWindow[n].prototype)
+        .filter(Boolean);
+    for (const proto of PROTOS) {
+        for (const name of Object.getOwnPropertyNames(proto)) {
+            const d = Object.getOwnPropertyDescriptor(proto, name);
+            if (!d.configurable) continue;
+            if (d.set) Object.defineProperty(proto, name, { ...d, set: deny(`Setting ${name}`) });
+            else if (typeof d.value === 'function' && METHODS.has(name)) {
+                Object.defineProperty(proto, name, { ...d, value: deny(`${name}()`) });
+            }
+        }
+    }
+    for (const name of Object.getOwnPropertyNames(realWindow)) {
+        const d = Object.getOwnPropertyDescriptor(realWindow, name);
+        if (name.startsWith('on') && d.configurable && d.set) {
+            Object.defineProperty(realWindow, name, { ...d, set: deny(`Setting ${name}`) });
+        }
+    }
+
+    // Network, storage, messaging, deferred execution and code loading
+    for (const name of ['fetch', 'fetchLater', 'XMLHttpRequest', 'WebSocket', 'WebSocketStream', 'WebTransport',
+        'EventSource', 'RTCPeerConnection', 'Worker', 'SharedWorker', 'BroadcastChannel', 'FontFace', 'Image', 'Audio',
+        'Option', 'open', 'postMessage', 'alert', 'confirm', 'prompt', 'print', 'scroll', 'scrollTo', 'scrollBy',
+        'localStorage', 'sessionStorage', 'indexedDB', 'caches', 'cookieStore', 'setTimeout', 'setInterval',
+        'requestAnimationFrame', 'requestIdleCallback', 'queueMicrotask', 'MutationObserver', 'IntersectionObserver',
+        'ResizeObserver', 'PerformanceObserver', 'eval', 'Function']) {
+        lock(realWindow, name);
+    }
+    for (const fn of [function () {}, async function () {}, function* () {}, async function* () {}]) {
+        lock(Object.getPrototypeOf(fn), 'constructor', 'Function');
+    }
+    for (const name of ['sendBeacon', 'serviceWorker', 'clipboard', 'credentials']) lock(Navigator.prototype, name);
+    lock(Document.prototype, 'cookie');
+    for (const C of [realWindow.HTMLIFrameElement, realWindow.HTMLFrameElement, realWindow.HTMLObjectElement, realWindow.HTMLEmbedElement]) {
+        for (const name of ['contentWindow', 'contentDocument', 'getSVGDocument']) lock(C && C.prototype, name, 'Frame access');
+    }
+
+    // Stand-ins for the real window, document and location
+    const safeLocation = {};
+    for (const key of ['href', 'origin', 'protocol', 'host', 'hostname', 'port', 'pathname', 'search', 'hash']) {
+        Object.defineProperty(safeLocation, key, { get: () => realLocation[key], set: deny('Navigation'), enumerable: true });
+    }
+    for (const key of ['assign', 'replace', 'reload']) safeLocation[key] = deny('Navigation');
+    safeLocation.toString = () => realLocation.href;
+    Object.freeze(safeLocation);
+
+    const shield = (target, overrides, allowNew) => new Proxy(target, {
+        get(t, key) {
+            if (Object.hasOwn(overrides, key)) return overrides[key];
+            if (typeof key === 'string' && /^\d+$/.test(key)) return deny('Frame access')();
+            const value = Reflect.get(t, key, t);
+            // Methods need the real object as `this`; constructors stay unbound for `new` and instanceof
+            return typeof value === 'function' && typeof key === 'string' && !/^[A-Z]/.test(key) ? value.bind(t) : value;
+        },
+        set(t, key, value) {
+            if (!allowNew || Object.hasOwn(overrides, key) || key in t) return deny(`Setting ${String(key)}`)();
+            return Reflect.set(t, key, value, t);
+        },
+    });
+    const windowOverrides = { location: safeLocation, opener: null };
+    const safeWindow = shield(realWindow, windowOverrides, true);
+    const safeDocument = shield(realDocument, { location: safeLocation, defaultView: safeWindow }, false);
+    Object.assign(windowOverrides, {
+        window: safeWindow, self: safeWindow, globalThis: safeWindow, top: safeWindow, parent: safeWindow,
+        frames: safeWindow, document: safeDocument,
+    });
+    const guard = (value) => value === realDocument ? safeDocument : value === realWindow ? safeWindow : value;
+
+    // DOM getters that can hand out the real document or window
+    const shieldGetter = (C, name) => {
+        const d = C && Object.getOwnPropertyDescriptor(C.prototype, name);
+        if (!d || !d.configurable) return;
+        if (d.get) Object.defineProperty(C.prototype, name, { ...d, get() { return guard(d.get.call(this)); } });
+        else if (typeof d.value === 'function') {
+            const method = d.value;
+            Object.defineProperty(C.prototype, name, { ...d, value(...args) { return guard(method.apply(this, args)); } });
+        }
+    };
+    for (const [C, names] of [
+        [Node, ['ownerDocument', 'parentNode', 'getRootNode']],
+        [Document, ['defaultView']],
+        [realWindow.AbstractRange, ['startContainer', 'endContainer']],
+        [Range, ['commonAncestorContainer']],
+        [Selection, ['anchorNode', 'focusNode']],
+        [XPathResult, ['singleNodeValue', 'iterateNext', 'snapshotItem']],
+        [NodeIterator, ['root', 'referenceNode', 'nextNode', 'previousNode']],
+        [TreeWalker, ['root', 'currentNode', 'parentNode', 'nextNode', 'previousNode']],
+        [Event, ['target', 'currentTarget', 'srcElement']],
+        [UIEvent, ['view']],
+    ]) {
+        for (const name of names) shieldGetter(C, name);
+    }
+
+    const NAMES = ['location', 'window', 'self', 'globalThis', 'top', 'parent', 'frames', 'opener', 'document'];
+    const VALUES = NAMES.map(n => windowOverrides[n]);
+    Object.defineProperty(realWindow, '__taskerRun', {
+        value: (source) => {
+            // An expression (or function literal) returns its value; statements need `return`
+            let body;
+            try {
+                body = new RealFunction(...NAMES, '"use strict";\nreturn (' + source.replace(/[\s;]+$/, '') + '\n);');
+            } catch (e) {
+                if (!(e instanceof SyntaxError)) throw e;
+                body = new RealFunction(...NAMES, '"use strict";\n' + source);
+            }
+            const value = body.apply(undefined, VALUES);
+            return typeof value === 'function' ? value.call(undefined) : value;
+        },
+    });
+    return true;
+})()
+"#;
+
+/// Guarded isolated worlds, one per document: the guard is installed once and reused
+/// until the frame loads another document
+#[derive(Debug, Default)]
+pub struct ReadOnlyWorlds {
+    /// Frame id -> loader id of its document and the world's execution context
+    worlds: std::sync::Mutex<HashMap<String, (String, ExecutionContextId)>>,
+}
+
+impl ReadOnlyWorlds {
+    /// The guarded world of the page's current document, created on first use
+    async fn context(&self, page: &Page, timeout: Duration) -> Result<ExecutionContextId> {
+        let frame = page
+            .execute(GetFrameTreeParams::default())
+            .await
+            .map_err(|e| anyhow!("Failed to get frame tree: {}", e))?
+            .result
+            .frame_tree
+            .frame
+            .clone();
+        let frame_id = frame.id.as_ref().to_string();
+        let loader_id = frame.loader_id.as_ref().to_string();
+
+        let cached = self.worlds.lock().unwrap().get(&frame_id).cloned();
+        if let Some((loader, context_id)) = cached {
+            if loader == loader_id {
+                return Ok(context_id);
+            }
+        }
+
+        let world = CreateIsolatedWorldParams {
+            frame_id: frame.id,
+            world_name: Some(READ_ONLY_WORLD.to_string()),
+            grant_univeral_access: Some(false),
+        };
+        let context_id = page.execute(world).await
+            .map_err(|e| anyhow!("Failed to create isolated world: {}", e))?
+            .result.execution_context_id;
+        evaluate(page, READ_ONLY_GUARD_JS, Some(context_id), timeout).await?;
+
+        self.worlds.lock().unwrap().insert(frame_id, (loader_id, context_id));
+        Ok(context_id)
+    }
+}
+
+/// Run a model-written script in the page and return its (awaited) value; function
+/// literals are called. With `read_only`, the script runs in the page's guarded world
+/// (see ReadOnlyWorlds) while document requests are intercepted and failed, so any
+/// navigation it still manages to start never loads. `proxy_auth` restores proxy
+/// auth handling when the interception ends.
+pub async fn run_script(
+    page: &Page,
+    script: &str,
+    read_only: bool,
+    timeout: Duration,
+    worlds: &ReadOnlyWorlds,
+    proxy_auth: bool,
+) -> Result<Value> {
+    if !read_only {
+        // Global eval keeps top-level declarations and the completion value of statements;
+        // a function completion value is called in the same evaluation
+        let expression = format!("(r => typeof r === 'function' ? r() : r)((0, eval)({}))", json!(script));
+        return match evaluate(page, &expression, None, timeout).await? {
+            ScriptValue::Value(value) => Ok(value),
+            ScriptValue::Function => Ok(Value::Null),
+        };
+    }
+
+    if uses_dynamic_import(script) {
+        return Err(anyhow!("import() is not allowed - JavaScript is read-only in this workflow"));
+    }
+
+    let context_id = worlds.context(page, timeout).await?;
+
+    // Subscribe before intercepting so no paused request or navigation is missed
+    let mut paused = page.event_listener::<EventRequestPaused>().await?;
+    let mut navigations = page.event_listener::<EventFrameRequestedNavigation>().await?;
+    let pattern = RequestPattern {
+        url_pattern: Some("*".to_string()),
+        resource_type: Some(ResourceType::Document),
+        request_stage: Some(RequestStage::Request),
+    };
+    page.execute(
+        fetch::EnableParams::builder()
+            .pattern(pattern)
+            .handle_auth_requests(proxy_auth)
+            .build(),
+    )
+    .await
+    .map_err(|e| anyhow!("Failed to intercept navigations: {}", e))?;
+
+    let mut blocked = None;
+    let expression = format!("__taskerRun({})", json!(script));
+    let run = evaluate(page, &expression, Some(context_id), timeout);
+    let result = block_navigations(page, &mut paused, &mut blocked, run).await;
+    // One more round trip to the page, so a navigation the script started has reached the browser
+    let settle = evaluate(page, "0", Some(context_id), timeout);
+    let _ = block_navigations(page, &mut paused, &mut blocked, settle).await;
+    while let Some(Some(event)) = paused.next().now_or_never() {
+        fail_navigation(page, &event, &mut blocked).await;
+    }
+
+    // Navigations that never request a document (about:, data:, javascript: URLs)
+    let mut requested = None;
+    while let Some(Some(event)) = navigations.next().now_or_never() {
+        requested.get_or_insert(event.url.clone());
+    }
+
+    let restored = if proxy_auth {
+        page.execute(
+            fetch::EnableParams::builder()
+                .pattern(RequestPattern::builder().url_pattern("*").build())
+                .handle_auth_requests(true)
+                .build(),
+        )
+        .await
+        .map(|_| ())
+    } else {
+        page.execute(fetch::DisableParams::default()).await.map(|_| ())
+    };
+    if let Err(e) = restored {
+        tracing::warn!("Failed to end navigation interception: {}", e);
+    }
+
+    if let Some(url) = blocked.or(requested) {
+        page.execute(StopLoadingParams::default()).await
+            .map_err(|e| anyhow!("Failed to cancel navigation to {}: {}", url, e))?;
+        return Err(anyhow!(
+            "Navigation to {} is not allowed - JavaScript is read-only in this workflow",
+            url
+        ));
+    }
+
+    match result? {
+        ScriptValue::Value(value) => Ok(value),
+        ScriptValue::Function => Ok(Value::Null),
+    }
+}
+
+/// Drive `future` to completion, failing every document request paused meanwhile
+async fn block_navigations<T>(
+    page: &Page,
+    paused: &mut EventStream<EventRequestPaused>,
+    blocked: &mut Option<String>,
+    future: impl Future<Output = T>,
+) -> T {
+    tokio::pin!(future);
+    loop {
+        tokio::select! {
+            output = &mut future => return output,
+            Some(event) = paused.next() => fail_navigation(page, &event, blocked).await,
+        }
+    }
+}
+
+async fn fail_navigation(page: &Page, event: &EventRequestPaused, blocked: &mut Option<String>) {
+    blocked.get_or_insert(event.request.url.clone());
+    let fail = FailRequestParams::new(event.request_id.clone(), ErrorReason::Aborted);
+    if let Err(e) = page.execute(fail).await {
+        tracing::warn!("Failed to block navigation to {}: {}", event.request.url, e);
+    }
+}
+
+enum ScriptValue {
+    Value(Value),
+    /// The script evaluated to a function (returned by value it would be lost)
+    Function,
+}
+
+async fn evaluate(
+    page: &Page,
+    expression: &str,
+    context_id: Option<ExecutionContextId>,
+    timeout: Duration,
+) -> Result<ScriptValue> {
+    let mut params = EvaluateParams::builder()
+        .expression(expression)
+        .await_promise(true)
+        .return_by_value(true)
+        .timeout(TimeDelta::new(timeout.as_millis() as f64))
+        .build()
+        .map_err(|e| anyhow!("Failed to build script evaluation: {}", e))?;
+    params.context_id = context_id;
+
+    // The CDP timeout only stops synchronous execution - also bound waiting on promises
+    let result = tokio::time::timeout(timeout, page.execute(params))
+        .await
+        .map_err(|_| anyhow!("Script timed out after {}ms", timeout.as_millis()))?
+        .map_err(|e| anyhow!("Failed to evaluate script: {}", e))?;
+
+    if let Some(exception) = &result.result.exception_details {
+        return Err(anyhow!("{}", exception_message(exception)));
+    }
+    let object = &result.result.result;
+    if object.r#type == RemoteObjectType::Function {
+        return Ok(ScriptValue::Function);
+    }
+    Ok(ScriptValue::Value(object.value.clone().unwrap_or(Value::Null)))
+}
+
+/// First line of the thrown error ("TypeError: x is not a function"), not just "Uncaught"
+fn exception_message(exception: &ExceptionDetails) -> String {
+    exception
+        .exception
+        .as_ref()
+        .and_then(|e| e.description.as_deref())
+        .and_then(|d| d.lines().next())
+        .unwrap_or(&exception.text)
+        .to_string()
+}
+
+/// Whether the script uses the `import` keyword, which loads code over the network and
+/// cannot be locked from JS
+fn uses_dynamic_import(script: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    script.match_indices("import").any(|(i, word)| {
+        let before = script[..i].chars().next_back();
+        let after = script[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uses_dynamic_import() {
+        assert!(uses_dynamic_import("import('https://example.com/x.js')"));
+        assert!(uses_dynamic_import("(async () => await import ( url ))()"));
+        assert!(!uses_dynamic_import("document.importNode(node)"));
+        assert!(!uses_dynamic_import("document.querySelectorAll('.important').length"));
+    }
+}
@@ -0,0 +1,376 @@
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::dom::{BackendNodeId as CdpBackendNodeId, ResolveNodeParams};
+use chromiumoxide::cdp::js_protocol::runtime::CallFunctionOnParams;
+use chromiumoxide::Page;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::cdp_dom::BackendNodeId;
+
+/// Reads rows out of `root` (an HTML table, ARIA grid or a container of repeated cards),
+/// or out of the most substantial one inside it / the element matching `css` / the page.
+/// Returns `{kind, headers, rows, selector}` or null when nothing row-like is found.
+const EXTRACT_TABLE_JS: &str = r#"
+function(root, css) {
+    const clean = (s) => (s || '').replace(/\s+/g, ' ').trim();
+    const visible = (el) => {
+        const style = getComputedStyle(el);
+        return style.display !== 'none' && style.visibility !== 'hidden' && el.getClientRects().length > 0;
+    };
+    const GRID = '[role=grid], [role=table], [role=treegrid]';
+
+    function cssPath(el) {
+        const parts = [];
+        for (let e = el; e && e.nodeType === 1; e = e.parentElement) {
+            if (e === document.body) { parts.unshift('body'); break; }
+            if (e.id && document.querySelectorAll('#' + CSS.escape(e.id)).length === 1) {
+                parts.unshift('#' + CSS.escape(e.id));
+                break;
+            }
+            let part = e.tagName.toLowerCase();
+            const same = e.parentElement ? Array.from(e.parentElement.children).filter(s => s.tagName === e.tagName) : [];
+            if (same.length > 1) part += `:nth-of-type(${same.indexOf(e) + 1})`;
+            parts.unshift(part);
+        }
+        return parts.join(' > ');
+    }
+
+    const expand = (row) => Array.from(row.cells)
+        .flatMap(c => [clean(c.innerText), ...Array(Math.max(0, (c.colSpan || 1) - 1)).fill('')]);
+
+    function fromTable(table) {
+        const rows = Array.from(table.rows).filter(visible);
+        let headRow = null;
+        if (table.tHead && table.tHead.rows.length) headRow = table.tHead.rows[table.tHead.rows.length - 1];
+        else if (rows.length && Array.from(rows[0].cells).every(c => c.tagName === 'TH')) headRow = rows[0];
+        const body = rows.filter(r => r !== headRow && !(table.tHead && table.tHead.contains(r)));
+        return {
+            kind: 'table',
+            headers: headRow ? expand(headRow) : [],
+            rows: body.map(expand).filter(r => r.some(c => c)),
+        };
+    }
+
+    function fromGrid(grid) {
+        const rows = Array.from(grid.querySelectorAll('[role=row]'))
+            .filter(r => r.closest(GRID) === grid && visible(r));
+        const cellsOf = (r) => Array.from(r.querySelectorAll('[role=cell], [role=gridcell], [role=columnheader], [role=rowheader]'))
+            .filter(c => c.closest('[role=row]') === r);
+        let headers = [];
+        let body = rows;
+        if (rows.length && cellsOf(rows[0]).length && cellsOf(rows[0]).every(c => c.getAttribute('role') === 'columnheader')) {
+            headers = cellsOf(rows[0]).map(c => clean(c.innerText));
+            body = rows.slice(1);
+        }
+        return { kind: 'grid', headers, rows: body.map(r => cellsOf(r).map(c => clean(c.innerText))).filter(r => r.some(c => c)) };
+    }
+
+    // Children sharing the most common tag + class signature (generated, digit-bearing classes ignored)
+    function repeatedChildren(container) {
+        const groups = new Map();
+        for (const child of container.children) {
+            if (!visible(child) || !clean(child.innerText)) continue;
+            const sig = child.tagName + '.' + Array.from(child.classList).filter(c => !/\d/.test(c)).sort().join('.');
+            groups.set(sig, (groups.get(sig) || []).concat([child]));
+        }
+        let best = [];
+        for (const items of groups.values()) if (items.length > best.length) best = items;
+        return best;
+    }
+
+    function fieldName(el) {
+        const cls = Array.from(el.classList).find(c => !/\d/.test(c) && c.length > 1);
+        return (cls || el.tagName.toLowerCase()).replace(/[-_]+/g, ' ');
+    }
+
+    function fromList(container) {
+        const headers = [];
+        const records = repeatedChildren(container).map(item => {
+            const record = {};
+            const add = (name, value) => {
+                if (!value) return;
+                let key = name;
+                for (let n = 2; key in record; n++) key = `${name} ${n}`;
+                record[key] = value;
+                if (!headers.includes(key)) headers.push(key);
+            };
+            (function walk(el) {
+                for (const child of el.children) {
+                    if (!visible(child)) continue;
+                    const ownText = Array.from(child.childNodes).some(n => n.nodeType === 3 && clean(n.textContent));
+                    if (ownText || !child.children.length) add(fieldName(child), clean(child.innerText));
+                    else walk(child);
+                }
+            })(item);
+            if (!Object.keys(record).length) add('text', clean(item.innerText));
+            const link = item.matches('a[href]') ? item : item.querySelector('a[href]');
+            if (link) add('link', link.href);
+            const img = item.querySelector('img[src]');
+            if (img) add('image', img.src);
+            return record;
+        });
+        return { kind: 'list', headers, rows: records.map(r => headers.map(h => r[h] || '')) };
+    }
+
+    function fromElement(el) {
+        if (el.tagName === 'TABLE') return fromTable(el);
+        if (el.matches(GRID)) return fromGrid(el);
+        return fromList(el);
+    }
+
+    // The candidate with the most text wins; tables and grids are preferred over card lists
+    function detect(scope) {
+        let best = null;
+        let bestScore = 0;
+        const consider = (el, minRows, weight) => {
+            const data = fromElement(el);
+            if (data.rows.length < minRows) return;
+            const score = weight * data.rows.reduce((n, r) => n + r.join('').length, 0);
+            if (score > bestScore) { best = Object.assign(data, { selector: cssPath(el) }); bestScore = score; }
+        };
+        for (const el of scope.querySelectorAll('table')) if (visible(el)) consider(el, 1, 2);
+        for (const el of scope.querySelectorAll(GRID)) if (el.tagName !== 'TABLE' && visible(el)) consider(el, 1, 2);
+        for (const el of [scope, ...scope.querySelectorAll('*')]) {
+            if (el.children.length >= 3 && el.tagName !== 'TABLE' && !el.matches(GRID) && visible(el)
+                && repeatedChildren(el).length >= 3) consider(el, 3, 1);
+        }
+        return best;
+    }
+
+    if (!root && css) root = document.querySelector(css);
+    if (css && !root) return null;
+    if (!root) return detect(document.body);
+    if (root.tagName === 'TABLE' || root.matches(GRID) || repeatedChildren(root).length >= 2) {
+        return Object.assign(fromElement(root), { selector: css || cssPath(root) });
+    }
+    return detect(root);
+}
+"#;
+
+/// Clicks the "next page" control (`css`, or a rel=next / "Next" / "›" link or button).
+/// Returns false when there is none or it is disabled.
+const CLICK_NEXT_JS: &str = r#"
+function(css) {
+    const clean = (s) => (s || '').replace(/\s+/g, ' ').trim();
+    const visible = (el) => el.getClientRects().length > 0;
+    const disabled = (el) => el.disabled || el.getAttribute('aria-disabled') === 'true'
+        || /(^|\s)disabled(\s|$)/.test(typeof el.className === 'string' ? el.className : '');
+    const LABEL = /^(next|next page|more results)?\s*[›»>→]*$/i;
+
+    let next = css ? document.querySelector(css) : document.querySelector('a[rel~=next]');
+    if (!next && !css) {
+        next = Array.from(document.querySelectorAll('a, button, [role=button], [role=link]')).find(el => {
+            const label = clean(el.getAttribute('aria-label') || el.innerText || el.title);
+            return label && LABEL.test(label) && visible(el);
+        });
+    }
+    if (!next && !css) {
+        const link = document.querySelector('link[rel~=next][href]');
+        if (link) { location.href = link.href; return true; }
+    }
+    if (!next || disabled(next)) return false;
+    next.scrollIntoView({ block: 'center' });
+    next.click();
+    return true;
+}
+"#;
+
+/// Where to read rows from
+#[derive(Debug, Clone)]
+pub enum TableTarget {
+    /// The most substantial table, grid or list on the page
+    Auto,
+    Css(String),
+    Element(BackendNodeId),
+}
+
+/// Rows read from an HTML table, ARIA grid or repeated card list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractedTable {
+    /// table | grid | list
+    pub kind: String,
+    #[serde(default)]
+    pub headers: Vec<String>,
+    #[serde(default)]
+    pub rows: Vec<Vec<String>>,
+    /// CSS selector of the container, to find it again on the next page
+    #[serde(default)]
+    pub selector: Option<String>,
+}
+
+impl ExtractedTable {
+    /// Column names: the headers, with blanks and duplicates made unique
+    pub fn columns(&self) -> Vec<String> {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0).max(self.headers.len());
+        let mut columns: Vec<String> = Vec::with_capacity(width);
+        for i in 0..width {
+            let base = self
+                .headers
+                .get(i)
+                .filter(|h| !h.is_empty())
+                .cloned()
+                .unwrap_or_else(|| format!("column_{}", i + 1));
+            let mut name = base.clone();
+            let mut n = 2;
+            while columns.contains(&name) {
+                name = format!("{}_{}", base, n);
+                n += 1;
+            }
+            columns.push(name);
+        }
+        columns
+    }
+
+    /// Append the rows of the next page, lining columns up by header name
+    pub fn append(&mut self, other: ExtractedTable) {
+        if other.headers.is_empty() || other.headers == self.headers {
+            self.rows.extend(other.rows);
+            return;
+        }
+        let positions: Vec<usize> = other
+            .headers
+            .iter()
+            .map(|h| match self.headers.iter().position(|s| s == h) {
+                Some(i) => i,
+                None => {
+                    self.headers.push(h.clone());
+                    self.headers.len() - 1
+                }
+            })
+            .collect();
+        for row in other.rows {
+            let mut aligned = vec![String::new(); self.headers.len()];
+            for (value, &i) in row.into_iter().zip(&positions) {
+                aligned[i] = value;
+            }
+            self.rows.push(aligned);
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![csv_line(&self.columns())];
+        lines.extend(self.rows.iter().map(|r| csv_line(r)));
+        lines.join("\r\n") + "\r\n"
+    }
+
+    /// Rows as an array of objects keyed by column name
+    pub fn to_json(&self) -> Value {
+        let columns = self.columns();
+        Value::Array(
+            self.rows
+                .iter()
+                .map(|row| {
+                    let record: Map<String, Value> = columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| (c.clone(), json!(row.get(i).cloned().unwrap_or_default())))
+                        .collect();
+                    Value::Object(record)
+                })
+                .collect(),
+        )
+    }
+}
+
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            let f = f.as_ref();
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Read rows from the page; None when there is no table, grid or repeated list
+pub async fn extract_table(page: &Page, target: &TableTarget) -> Result<Option<ExtractedTable>> {
+    let value: Value = match target {
+        TableTarget::Element(backend_id) => {
+            let params = ResolveNodeParams {
+                node_id: None,
+                backend_node_id: Some(CdpBackendNodeId::new(*backend_id)),
+                object_group: Some("extract-table".to_string()),
+                execution_context_id: None,
+            };
+            let object_id = page.execute(params).await
+                .map_err(|e| anyhow!("Failed to resolve element: {}", e))?
+                .result.object.object_id
+                .ok_or_else(|| anyhow!("Element has no object ID"))?;
+            let params = CallFunctionOnParams::builder()
+                .object_id(object_id)
+                .function_declaration(format!("function() {{ return ({})(this, null); }}", EXTRACT_TABLE_JS))
+                .return_by_value(true)
+                .build()
+                .map_err(|e| anyhow!("Failed to build table extraction call: {}", e))?;
+            let result = page.execute(params).await
+                .map_err(|e| anyhow!("Failed to extract table: {}", e))?;
+            if let Some(exception) = &result.result.exception_details {
+                return Err(anyhow!("Failed to extract table: {}", exception.text));
+            }
+            result.result.result.value.clone().unwrap_or(Value::Null)
+        }
+        TableTarget::Css(css) => evaluate(page, &format!("({})(null, {})", EXTRACT_TABLE_JS, json!(css))).await?,
+        TableTarget::Auto => evaluate(page, &format!("({})(null, null)", EXTRACT_TABLE_JS)).await?,
+    };
+
+    if value.is_null() {
+        return Ok(None);
+    }
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|e| anyhow!("Failed to read extracted table: {}", e))
+}
+
+/// Click the page's "next" control; false when there is no (enabled) next page
+pub async fn click_next_page(page: &Page, css: Option<&str>) -> Result<bool> {
+    let value = evaluate(page, &format!("({})({})", CLICK_NEXT_JS, json!(css))).await?;
+    Ok(value.as_bool().unwrap_or(false))
+}
+
+async fn evaluate(page: &Page, expression: &str) -> Result<Value> {
+    page.evaluate_expression(expression).await
+        .map_err(|e| anyhow!("Failed to evaluate table script: {}", e))?
+        .into_value::<Value>()
+        .map_err(|e| anyhow!("Failed to read table script result: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(headers: &[&str], rows: &[&[&str]]) -> ExtractedTable {
+        ExtractedTable {
+            kind: "table".to_string(),
+            headers: headers.iter().map(|s| s.to_string()).collect(),
+            rows: rows.iter().map(|r| r.iter().map(|s| s.to_string()).collect()).collect(),
+            selector: None,
+        }
+    }
+
+    #[test]
+    fn test_to_csv_and_json() {
+        let t = table(&["Plan", "", "Plan"], &[&["Pro", "$10, monthly", "say \"hi\""]]);
+        assert_eq!(t.columns(), vec!["Plan", "column_2", "Plan_2"]);
+        assert_eq!(t.to_csv(), "Plan,column_2,Plan_2\r\nPro,\"$10, monthly\",\"say \"\"hi\"\"\"\r\n");
+        assert_eq!(
+            t.to_json(),
+            json!([{ "Plan": "Pro", "column_2": "$10, monthly", "Plan_2": "say \"hi\"" }])
+        );
+    }
+
+    #[test]
+    fn test_append_aligns_columns() {
+        let mut t = table(&["name", "price"], &[&["A", "1"]]);
+        t.append(table(&["name", "price"], &[&["B", "2"]]));
+        t.append(table(&["price", "name", "rating"], &[&["3", "C", "5"]]));
+        assert_eq!(t.headers, vec!["name", "price", "rating"]);
+        assert_eq!(t.rows, vec![vec!["A", "1"], vec!["B", "2"], vec!["C", "3", "5"]]);
+        // Short rows are padded in the output
+        assert_eq!(t.to_csv(), "name,price,rating\r\nA,1\r\nB,2\r\nC,3,5\r\n");
+    }
+}
@@ -0,0 +1,85 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// A tab opened or closed by the page itself (popup, `target=_blank`, `window.close()`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TabEvent {
+    Opened {
+        index: usize,
+        url: String,
+        /// Whether the new tab became the active tab
+        switched: bool,
+    },
+    Closed {
+        index: usize,
+        /// Active tab index after the close
+        active: usize,
+    },
+}
+
+impl TabEvent {
+    /// One-line description for the LLM page state
+    pub fn describe(&self) -> String {
+        match self {
+            TabEvent::Opened { index, url, switched: true } => {
+                format!("The page opened a new tab {} ({}) - it is now the active tab", index, url)
+            }
+            TabEvent::Opened { index, url, switched: false } => {
+                format!("The page opened a new tab {} ({}) - use switch_tab to work in it", index, url)
+            }
+            TabEvent::Closed { index, active } => {
+                format!("Tab {} was closed by the page - active tab is now {}", index, active)
+            }
+        }
+    }
+}
+
+/// Tab events not yet reported to the agent
+#[derive(Clone, Default)]
+pub struct TabEvents {
+    events: Arc<Mutex<Vec<TabEvent>>>,
+}
+
+impl TabEvents {
+    pub fn push(&self, event: TabEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    /// Events since the last call
+    pub fn take(&self) -> Vec<TabEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+}
+
+/// Active tab index after the tab at `closed` is removed, leaving `remaining` tabs.
+/// Tabs after the closed one shift down; closing the active tab activates its left neighbour.
+pub fn active_after_close(active: usize, closed: usize, remaining: usize) -> usize {
+    let active = if closed < active || (closed == active && active > 0) {
+        active - 1
+    } else {
+        active
+    };
+    active.min(remaining.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_after_close() {
+        // Closing a tab before the active one shifts it down
+        assert_eq!(active_after_close(2, 0, 2), 1);
+        // Closing a tab after the active one leaves it alone
+        assert_eq!(active_after_close(0, 1, 1), 0);
+        // Closing the active popup returns to the opener on its left
+        assert_eq!(active_after_close(1, 1, 1), 0);
+        // Closing the first, active tab keeps index 0
+        assert_eq!(active_after_close(0, 0, 2), 0);
+    }
+}
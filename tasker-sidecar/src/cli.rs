@@ -0,0 +1,170 @@
+// Command-line modes of the sidecar binary besides the HTTP API
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::handlers::batches::launch_batch;
+use crate::api::state::AppState;
+use crate::mcp::workflows::find_saved_workflow;
+use crate::models::StartBatchRequest;
+use crate::runs::batch::results_csv;
+use crate::runs::BatchStatus;
+
+pub const BATCH_USAGE: &str = "Usage: tasker-sidecar batch <workflow name or id> <dataset.csv|.jsonl|.json> \
+[--concurrency N] [--headless] [--output results.csv]";
+
+/// Options of `tasker-sidecar batch`
+#[derive(Debug, PartialEq)]
+pub struct BatchArgs {
+    pub workflow: String,
+    pub dataset: String,
+    pub concurrency: Option<usize>,
+    pub headless: bool,
+    /// Where the results CSV goes (None = stdout)
+    pub output: Option<String>,
+}
+
+impl BatchArgs {
+    /// Parse the arguments after `batch`
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut positional = Vec::new();
+        let mut concurrency = None;
+        let mut headless = false;
+        let mut output = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--concurrency" | "-c" => {
+                    let value = args.next().ok_or_else(|| anyhow!("--concurrency needs a number"))?;
+                    concurrency = Some(value.parse().map_err(|_| anyhow!("Invalid concurrency '{}'", value))?);
+                }
+                "--headless" => headless = true,
+                "--output" | "-o" => {
+                    output = Some(args.next().ok_or_else(|| anyhow!("--output needs a file path"))?.clone());
+                }
+                flag if flag.starts_with('-') => bail!("Unknown option '{}'", flag),
+                value => positional.push(value.to_string()),
+            }
+        }
+
+        let [workflow, dataset] = <[String; 2]>::try_from(positional).map_err(|_| anyhow!(BATCH_USAGE))?;
+        Ok(Self {
+            workflow,
+            dataset,
+            concurrency,
+            headless,
+            output,
+        })
+    }
+}
+
+/// Run a saved workflow once per dataset row and write the consolidated results CSV.
+/// Ctrl+C cancels the rows still waiting and stops the running ones.
+pub async fn run_batch(state: Arc<AppState>, args: BatchArgs) -> Result<()> {
+    let saved = find_saved_workflow(&args.workflow)?;
+    let dataset = std::fs::read_to_string(&args.dataset).with_context(|| format!("Failed to read {}", args.dataset))?;
+
+    // Rows override the workflow's defaults
+    let defaults: serde_json::Map<String, serde_json::Value> = saved
+        .variables
+        .iter()
+        .filter_map(|v| v.default_value.clone().map(|value| (v.name.clone(), value)))
+        .collect();
+
+    let request: StartBatchRequest = serde_json::from_value(json!({
+        "workflow": saved.workflow,
+        "variables": defaults,
+        "secret_variables": saved.secret_variables(),
+        "headless": args.headless,
+        "llm_provider": crate::config::get_default_provider(),
+        "llm_model": crate::config::get_default_model(),
+        "dataset": dataset,
+        "concurrency": args.concurrency,
+    }))?;
+
+    let batch = launch_batch(&state, request).await.map_err(|(_, e)| anyhow!(e))?;
+    eprintln!(
+        "Batch {}: {} rows of '{}', {} at a time",
+        batch.id,
+        batch.total_rows,
+        saved.workflow.name,
+        batch.concurrency
+    );
+
+    let repo = state
+        .runs_repository
+        .as_ref()
+        .ok_or_else(|| anyhow!("Runs repository not initialized"))?;
+    let mut cancelled = false;
+    let mut last_progress = String::new();
+    let batch = loop {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(2)) => {}
+            _ = tokio::signal::ctrl_c(), if !cancelled => {
+                eprintln!("Cancelling batch...");
+                if let Some((_, token)) = state.active_batches.remove(&batch.id) {
+                    token.cancel();
+                }
+                cancelled = true;
+            }
+        }
+
+        let current = repo.get_batch(&batch.id, false)?.ok_or_else(|| anyhow!("Batch record missing"))?;
+        let count = |status: &str| current.counts.get(status).copied().unwrap_or(0);
+        let progress = format!(
+            "{}/{} done, {} failed, {} running",
+            count("completed") + count("failed") + count("cancelled"),
+            current.total_rows,
+            count("failed"),
+            count("running")
+        );
+        if progress != last_progress {
+            eprintln!("{}", progress);
+            last_progress = progress;
+        }
+        if current.status != BatchStatus::Running {
+            break repo.get_batch(&batch.id, true)?.ok_or_else(|| anyhow!("Batch record missing"))?;
+        }
+    };
+
+    let csv = results_csv(&batch.rows)?;
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, csv).with_context(|| format!("Failed to write {}", path))?;
+            eprintln!("Results written to {}", path);
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parses_batch_arguments() {
+        let parsed = BatchArgs::parse(&args(&["Supplier lookup", "suppliers.csv", "-c", "4", "--headless", "-o", "out.csv"])).unwrap();
+        assert_eq!(
+            parsed,
+            BatchArgs {
+                workflow: "Supplier lookup".to_string(),
+                dataset: "suppliers.csv".to_string(),
+                concurrency: Some(4),
+                headless: true,
+                output: Some("out.csv".to_string()),
+            }
+        );
+
+        assert!(BatchArgs::parse(&args(&["only-workflow"])).is_err());
+        assert!(BatchArgs::parse(&args(&["wf", "data.csv", "--concurrency", "many"])).is_err());
+        assert!(BatchArgs::parse(&args(&["wf", "data.csv", "--verbose"])).is_err());
+    }
+}
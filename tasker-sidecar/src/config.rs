@@ -4,6 +4,8 @@ use std::sync::Mutex;
 use rusqlite::Connection;
 use serde::Deserialize;
 
+use crate::models::McpServerConfig;
+
 // SECURITY: Mutex to synchronize environment variable modifications
 // This prevents race conditions when multiple threads try to set API keys
 static ENV_MUTEX: Mutex<()> = Mutex::new(());
@@ -73,7 +75,7 @@ struct ApiKeys {
 }
 
 /// Get the path to the Tauri app's database
-fn get_db_path() -> Option<PathBuf> {
+pub(crate) fn get_db_path() -> Option<PathBuf> {
     // Standard Tauri data directory location
     let data_dir = dirs::data_dir()?;
     let db_path = data_dir.join("com.tasker.app").join("tasker.db");
@@ -198,3 +200,40 @@ pub fn get_default_model() -> Option<String> {
 
     config.default_model
 }
+
+/// Get the MCP servers every run connects to from settings
+pub fn get_mcp_servers() -> Vec<McpServerConfig> {
+    let Some(db_path) = get_db_path() else {
+        return Vec::new();
+    };
+
+    let conn = match Connection::open(&db_path) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Failed to open settings database: {}", e);
+            return Vec::new();
+        }
+    };
+
+    // The column is missing until the app has migrated its settings table
+    let servers_json: Option<String> = match conn.query_row(
+        "SELECT mcp_servers_json FROM app_settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::debug!("No MCP servers in settings: {}", e);
+            return Vec::new();
+        }
+    };
+
+    match servers_json.map(|json| serde_json::from_str(&json)) {
+        Some(Ok(servers)) => servers,
+        Some(Err(e)) => {
+            tracing::error!("Failed to parse MCP servers: {}", e);
+            Vec::new()
+        }
+        None => Vec::new(),
+    }
+}
@@ -1,9 +1,11 @@
 pub mod agent;
 pub mod api;
 pub mod browser;
+pub mod cli;
 pub mod config;
 pub mod error;
 pub mod llm;
+pub mod mcp;
 pub mod models;
 pub mod recording;
 pub mod runs;
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use tasker_sidecar::api::{routes::create_router, state::AppState};
+use tasker_sidecar::{cli, mcp};
 
 #[tokio::main]
 async fn main() {
@@ -14,8 +15,9 @@ async fn main() {
         .add_directive("chromiumoxide::conn=off".parse().unwrap())
         .add_directive("chromiumoxide::handler=off".parse().unwrap());
 
+    // stderr keeps stdout free for the MCP stdio transport
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .with(filter)
         .init();
 
@@ -25,6 +27,31 @@ async fn main() {
     // Create application state
     let state = Arc::new(AppState::new());
 
+    // `--mcp`: serve one MCP client over stdio instead of the HTTP API
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--mcp") {
+        let headless = args.iter().any(|a| a == "--headless") || mcp::headless_from_env();
+        if let Err(e) = mcp::serve_stdio(Arc::clone(&state), headless).await {
+            tracing::error!("MCP server error: {}", e);
+        }
+        state.shutdown().await;
+        return;
+    }
+
+    // `batch <workflow> <dataset>`: run a saved workflow over a dataset and print the results CSV
+    if args.get(1).map(String::as_str) == Some("batch") {
+        let result = match cli::BatchArgs::parse(&args[2..]) {
+            Ok(batch_args) => cli::run_batch(Arc::clone(&state), batch_args).await,
+            Err(e) => Err(e),
+        };
+        state.shutdown().await;
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Build router
     let app = create_router(Arc::clone(&state));
 
@@ -0,0 +1,542 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+use crate::models::McpServerConfig;
+use crate::tools::{Tool, ToolContext, ToolDefinition, ToolResult};
+
+use super::http::SESSION_HEADER;
+use super::protocol::{CallToolResult, McpTool, METHOD_NOT_FOUND, SUPPORTED_VERSIONS};
+
+/// Between the server name and the tool name: "crm" + "find_customer" = "crm__find_customer"
+pub const TOOL_NAME_SEPARATOR: &str = "__";
+
+/// Function names longer than this are rejected by some LLM providers
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Tool output handed to the model is cut off after this many characters
+const MAX_RESULT_CHARS: usize = 20_000;
+
+/// Whether a tool name belongs to an external MCP server (built-in tools never contain the separator)
+pub fn is_mcp_tool(name: &str) -> bool {
+    name.contains(TOOL_NAME_SEPARATOR)
+}
+
+/// Namespaced, provider-safe name for a server's tool
+pub fn proxy_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+            .collect()
+    };
+    let mut name = format!("{}{}{}", sanitize(server), TOOL_NAME_SEPARATOR, sanitize(tool));
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+/// Newline-delimited JSON-RPC over a child process's stdin/stdout
+struct StdioTransport {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+/// Streamable HTTP: every message is a POST, answered with JSON or a short event stream
+struct HttpTransport {
+    http: reqwest::Client,
+    url: String,
+    session_id: Option<String>,
+    protocol_version: Option<String>,
+}
+
+enum Transport {
+    Stdio(Box<StdioTransport>),
+    Http(HttpTransport),
+}
+
+/// Connection to one external MCP server
+pub struct McpClient {
+    config: McpServerConfig,
+    transport: Mutex<Transport>,
+    next_id: AtomicU64,
+    timeout: Duration,
+}
+
+impl McpClient {
+    /// Start (or reach) the server and complete the initialize handshake
+    pub async fn connect(config: &McpServerConfig) -> Result<Self> {
+        let transport = match (&config.command, &config.url) {
+            (Some(command), None) => Transport::Stdio(Box::new(spawn_stdio(config, command)?)),
+            (None, Some(url)) => Transport::Http(HttpTransport {
+                http: http_client(config)?,
+                url: url.clone(),
+                session_id: None,
+                protocol_version: None,
+            }),
+            _ => bail!("MCP server '{}' needs either a command or a url", config.name),
+        };
+
+        let client = Self {
+            config: config.clone(),
+            transport: Mutex::new(transport),
+            next_id: AtomicU64::new(1),
+            timeout: Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+        };
+
+        let init = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": SUPPORTED_VERSIONS[0],
+                    "capabilities": {},
+                    "clientInfo": { "name": "tasker", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await
+            .context("initialize failed")?;
+        if let Transport::Http(http) = &mut *client.transport.lock().await {
+            http.protocol_version = init["protocolVersion"].as_str().map(str::to_string);
+        }
+        client.notify("notifications/initialized").await?;
+
+        Ok(client)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// All tools the server offers (following `nextCursor` pages)
+    pub async fn list_tools(&self) -> Result<Vec<McpTool>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request("tools/list", params).await?;
+            let listed: Vec<McpTool> = serde_json::from_value(page["tools"].clone())
+                .map_err(|e| anyhow!("Invalid tools/list result: {}", e))?;
+            tools.extend(listed);
+            match page["nextCursor"].as_str() {
+                Some(next) if !next.is_empty() => cursor = Some(next.to_string()),
+                _ => break,
+            }
+        }
+        Ok(tools)
+    }
+
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult> {
+        let result = self
+            .request("tools/call", json!({ "name": name, "arguments": arguments }))
+            .await?;
+        serde_json::from_value(result).map_err(|e| anyhow!("Invalid tools/call result: {}", e))
+    }
+
+    /// End the session (the stdio server is killed when the client is dropped)
+    pub async fn close(&self) {
+        if let Transport::Http(http) = &*self.transport.lock().await {
+            if let Some(session_id) = &http.session_id {
+                let _ = http
+                    .http
+                    .delete(&http.url)
+                    .header(SESSION_HEADER, session_id)
+                    .timeout(Duration::from_secs(5))
+                    .send()
+                    .await;
+            }
+        }
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+
+        let mut transport = self.transport.lock().await;
+        let response = tokio::time::timeout(self.timeout, async {
+            match &mut *transport {
+                Transport::Stdio(stdio) => stdio.request(&message, id).await,
+                Transport::Http(http) => http.request(&message, id).await,
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("{} timed out after {}s", method, self.timeout.as_secs()))??;
+
+        if let Some(error) = response.get("error") {
+            bail!(
+                "{} ({})",
+                error["message"].as_str().unwrap_or("Unknown error"),
+                error["code"]
+            );
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    async fn notify(&self, method: &str) -> Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method });
+        match &mut *self.transport.lock().await {
+            Transport::Stdio(stdio) => stdio.send(&message).await,
+            Transport::Http(http) => http.post(&message).await.map(|_| ()),
+        }
+    }
+}
+
+fn spawn_stdio(config: &McpServerConfig, command: &str) -> Result<StdioTransport> {
+    let mut child = Command::new(command)
+        .args(&config.args)
+        .envs(&config.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start '{}'", command))?;
+
+    let stdin = child.stdin.take().ok_or_else(|| anyhow!("No stdin"))?;
+    let stdout = child.stdout.take().ok_or_else(|| anyhow!("No stdout"))?;
+
+    // Server diagnostics go to our log instead of interleaving with the sidecar's stderr
+    if let Some(stderr) = child.stderr.take() {
+        let server = config.name.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                tracing::debug!("[mcp:{}] {}", server, line);
+            }
+        });
+    }
+
+    Ok(StdioTransport {
+        _child: child,
+        stdin,
+        stdout: BufReader::new(stdout).lines(),
+    })
+}
+
+impl StdioTransport {
+    async fn send(&mut self, message: &Value) -> Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.flush().await?;
+        Ok(())
+    }
+
+    async fn request(&mut self, message: &Value, id: u64) -> Result<Value> {
+        self.send(message).await?;
+        loop {
+            let line = self
+                .stdout
+                .next_line()
+                .await?
+                .ok_or_else(|| anyhow!("MCP server exited"))?;
+            let Ok(incoming) = serde_json::from_str::<Value>(&line) else {
+                tracing::debug!("Ignoring non-JSON line from MCP server: {}", line);
+                continue;
+            };
+            if incoming.get("method").is_some() {
+                // A request from the server (e.g. ping) - we offer no client features beyond that
+                if let Some(request_id) = incoming.get("id") {
+                    let reply = if incoming["method"] == "ping" {
+                        json!({ "jsonrpc": "2.0", "id": request_id, "result": {} })
+                    } else {
+                        json!({
+                            "jsonrpc": "2.0",
+                            "id": request_id,
+                            "error": { "code": METHOD_NOT_FOUND, "message": "Not supported by this client" },
+                        })
+                    };
+                    self.send(&reply).await?;
+                }
+                continue;
+            }
+            if incoming["id"].as_u64() == Some(id) {
+                return Ok(incoming);
+            }
+        }
+    }
+}
+
+fn http_client(config: &McpServerConfig) -> Result<reqwest::Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in &config.headers {
+        headers.insert(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name '{}'", name))?,
+            reqwest::header::HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for header '{}'", name))?,
+        );
+    }
+    Ok(reqwest::Client::builder().default_headers(headers).build()?)
+}
+
+impl HttpTransport {
+    async fn post(&mut self, message: &Value) -> Result<reqwest::Response> {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .header(reqwest::header::ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        if let Some(session_id) = &self.session_id {
+            request = request.header(SESSION_HEADER, session_id);
+        }
+        if let Some(version) = &self.protocol_version {
+            request = request.header("mcp-protocol-version", version);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("HTTP {}: {}", status, body.chars().take(200).collect::<String>());
+        }
+        if let Some(session_id) = response.headers().get(SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+            self.session_id = Some(session_id.to_string());
+        }
+        Ok(response)
+    }
+
+    async fn request(&mut self, message: &Value, id: u64) -> Result<Value> {
+        let response = self.post(message).await?;
+        let is_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        let body = response.text().await?;
+
+        if !is_stream {
+            return serde_json::from_str(&body).map_err(|e| anyhow!("Invalid JSON-RPC response: {}", e));
+        }
+        sse_messages(&body)
+            .into_iter()
+            .find(|m| m["id"].as_u64() == Some(id) && m.get("method").is_none())
+            .ok_or_else(|| anyhow!("Event stream ended without a response"))
+    }
+}
+
+/// JSON messages carried by the `data:` lines of an event stream
+fn sse_messages(body: &str) -> Vec<Value> {
+    body.replace("\r\n", "\n")
+        .split("\n\n")
+        .filter_map(|event| {
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|d| d.strip_prefix(' ').unwrap_or(d))
+                .collect();
+            serde_json::from_str(&data.join("\n")).ok()
+        })
+        .collect()
+}
+
+/// A tool of an external MCP server, offered to the agent under a namespaced name
+pub struct McpProxyTool {
+    client: Arc<McpClient>,
+    remote_name: String,
+    definition: ToolDefinition,
+}
+
+impl McpProxyTool {
+    /// Proxies for the server's tools, minus those its config leaves out
+    pub fn for_tools(client: &Arc<McpClient>, tools: Vec<McpTool>) -> Vec<Self> {
+        let allowed = &client.config.tools;
+        let mut proxies: Vec<Self> = Vec::new();
+        for tool in tools {
+            if !allowed.is_empty() && !allowed.contains(&tool.name) {
+                continue;
+            }
+            let name = proxy_tool_name(client.name(), &tool.name);
+            if proxies.iter().any(|p| p.definition.name == name) {
+                tracing::warn!("Skipping MCP tool '{}': its name clashes with another tool as {}", tool.name, name);
+                continue;
+            }
+            let description = if tool.description.is_empty() {
+                format!("[{}] {}", client.name(), tool.name)
+            } else {
+                format!("[{}] {}", client.name(), tool.description)
+            };
+            proxies.push(Self {
+                client: Arc::clone(client),
+                remote_name: tool.name,
+                definition: ToolDefinition {
+                    name,
+                    description,
+                    parameters: parameters_schema(tool.input_schema),
+                },
+            });
+        }
+        proxies
+    }
+
+    /// Name the agent calls the tool by
+    pub fn name(&self) -> &str {
+        &self.definition.name
+    }
+}
+
+/// The server's input schema as a function-calling schema (always an object)
+fn parameters_schema(schema: Value) -> Value {
+    match schema {
+        Value::Object(mut schema) => {
+            schema.remove("$schema");
+            schema.entry("type").or_insert_with(|| json!("object"));
+            Value::Object(schema)
+        }
+        _ => json!({ "type": "object", "properties": {} }),
+    }
+}
+
+#[async_trait]
+impl Tool for McpProxyTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+        let arguments = if params.is_null() { json!({}) } else { params };
+        let result = match self.client.call_tool(&self.remote_name, arguments).await {
+            Ok(result) => result,
+            Err(e) => return Ok(ToolResult::error(format!("{} failed: {}", self.definition.name, e))),
+        };
+        Ok(to_tool_result(result))
+    }
+}
+
+/// Flatten an MCP tool result into what the agent sees
+fn to_tool_result(result: CallToolResult) -> ToolResult {
+    let mut text = result
+        .content
+        .iter()
+        .map(|part| match part["type"].as_str() {
+            Some("text") => part["text"].as_str().unwrap_or_default().to_string(),
+            Some("resource") => part["resource"]["text"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("[resource: {}]", part["resource"]["uri"].as_str().unwrap_or("?"))),
+            Some("resource_link") => format!("[resource: {}]", part["uri"].as_str().unwrap_or("?")),
+            Some(other) => format!("[{} content]", other),
+            None => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        if let Some(structured) = &result.structured_content {
+            text = structured.to_string();
+        }
+    }
+    if text.chars().count() > MAX_RESULT_CHARS {
+        text = text.chars().take(MAX_RESULT_CHARS).collect::<String>() + "\n[truncated]";
+    }
+
+    if result.is_error {
+        return ToolResult::error(if text.is_empty() { "Tool reported an error".to_string() } else { text });
+    }
+    match result.structured_content {
+        Some(data) => ToolResult::success_with_data(text, data),
+        None => ToolResult::success(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal stdio MCP server: answers initialize, lists one tool and echoes calls back
+    const STUB_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","method":"notifications/message","params":{}}\n'
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"stub","version":"1"}}}\n' "$id" ;;
+    *'"method":"tools/list"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"find.customer","description":"Look up a customer","inputSchema":{"$schema":"x","type":"object","properties":{"email":{"type":"string"}}}},{"name":"hidden","inputSchema":{}}]}}\n' "$id" ;;
+    *'"name":"find.customer"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"Ada Lovelace"}],"structuredContent":{"id":7}}}\n' "$id" ;;
+    *'"method":"tools/call"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"no such record"}],"isError":true}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    fn stub_config() -> McpServerConfig {
+        McpServerConfig {
+            name: "crm".to_string(),
+            command: Some("sh".to_string()),
+            args: vec!["-c".to_string(), STUB_SERVER.to_string()],
+            timeout_secs: Some(10),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tool_names_are_namespaced_and_provider_safe() {
+        assert_eq!(proxy_tool_name("crm", "find_customer"), "crm__find_customer");
+        assert_eq!(proxy_tool_name("my crm", "records/get.v2"), "my_crm__records_get_v2");
+        assert_eq!(proxy_tool_name("crm", &"x".repeat(100)).len(), MAX_TOOL_NAME_LEN);
+        assert!(is_mcp_tool("crm__find_customer"));
+        assert!(!is_mcp_tool("click_element"));
+    }
+
+    #[test]
+    fn test_reads_responses_from_event_stream() {
+        let body = "event: message\r\ndata: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\r\n\r\n\
+                    data: {\"jsonrpc\":\"2.0\",\"id\":3,\"result\":{}}\r\n\r\n";
+        let messages = sse_messages(body);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["id"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_discovers_and_calls_stdio_server_tools() {
+        let client = Arc::new(McpClient::connect(&stub_config()).await.unwrap());
+        let tools = McpProxyTool::for_tools(&client, client.list_tools().await.unwrap());
+
+        let names: Vec<String> = tools.iter().map(|t| t.definition().name).collect();
+        assert_eq!(names, vec!["crm__find_customer", "crm__hidden"]);
+        let find = &tools[0];
+        assert_eq!(find.definition().description, "[crm] Look up a customer");
+        assert!(find.definition().parameters.get("$schema").is_none());
+        assert_eq!(tools[1].definition().parameters["type"], "object");
+
+        let found = to_tool_result(client.call_tool("find.customer", json!({ "email": "ada@example.com" })).await.unwrap());
+        assert!(found.success);
+        assert_eq!(found.content.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(found.data, Some(json!({ "id": 7 })));
+
+        let missing = to_tool_result(client.call_tool("hidden", json!({})).await.unwrap());
+        assert!(!missing.success);
+        assert_eq!(missing.error.as_deref(), Some("no such record"));
+    }
+
+    #[tokio::test]
+    async fn test_config_can_limit_offered_tools() {
+        let config = McpServerConfig {
+            tools: vec!["find.customer".to_string()],
+            ..stub_config()
+        };
+        let client = Arc::new(McpClient::connect(&config).await.unwrap());
+        let tools = McpProxyTool::for_tools(&client, client.list_tools().await.unwrap());
+        assert_eq!(tools.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_server_without_command_or_url() {
+        let config = McpServerConfig {
+            name: "broken".to_string(),
+            ..Default::default()
+        };
+        assert!(McpClient::connect(&config).await.is_err());
+    }
+}
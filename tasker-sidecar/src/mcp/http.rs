@@ -0,0 +1,176 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::state::AppState;
+
+use super::session::{parse_error, McpSession};
+
+/// Header carrying the session ID assigned on `initialize`
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// HTTP sessions open at the same time - each may hold a Chrome
+const MAX_SESSIONS: usize = 8;
+
+/// Sessions without a request for this long are closed along with their browser
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// How often sessions are checked for idleness
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Streamable HTTP transport: one JSON-RPC message or batch per POST, answered with JSON
+pub async fn handle_post(State(state): State<Arc<AppState>>, headers: HeaderMap, body: Bytes) -> Response {
+    if !origin_allowed(&headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(m) => m,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(parse_error(&e))).into_response(),
+    };
+
+    // initialize opens a new session; everything else needs the ID it handed out
+    let (session_id, session) = if is_initialize(&message) {
+        if state.mcp_sessions.len() >= MAX_SESSIONS {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Too many MCP sessions (at most {}) - close one first", MAX_SESSIONS),
+            )
+                .into_response();
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        let session = Arc::new(McpSession::new(Arc::clone(&state), super::headless_from_env()));
+        state.mcp_sessions.insert(id.clone(), Arc::clone(&session));
+        tokio::spawn(close_when_idle(Arc::clone(&state), id.clone(), Arc::downgrade(&session)));
+        tracing::info!("MCP session {} opened", id);
+        (id, session)
+    } else {
+        let Some(id) = session_id(&headers) else {
+            return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+        };
+        match state.mcp_sessions.get(&id).map(|s| Arc::clone(s.value())) {
+            Some(session) => (id, session),
+            None => return (StatusCode::NOT_FOUND, "Unknown or expired MCP session").into_response(),
+        }
+    };
+
+    let mut response = match session.handle_message(message).await {
+        Some(reply) => Json(reply).into_response(),
+        // Only notifications or responses
+        None => StatusCode::ACCEPTED.into_response(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+    response
+}
+
+/// End a session and close its browser
+pub async fn handle_delete(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    if !origin_allowed(&headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    let Some(id) = session_id(&headers) else {
+        return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+    };
+    match state.mcp_sessions.remove(&id) {
+        Some((_, session)) => {
+            session.close().await;
+            tracing::info!("MCP session {} closed", id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Close a session (and its browser) once the client has gone quiet.
+/// Ends when the session is closed some other way.
+async fn close_when_idle(state: Arc<AppState>, id: String, session: std::sync::Weak<McpSession>) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(IDLE_CHECK_INTERVAL) => {}
+            _ = state.shutdown_token.cancelled() => return,
+        }
+        let Some(current) = session.upgrade() else {
+            return;
+        };
+        if current.idle_for().is_none_or(|idle| idle < SESSION_IDLE_TIMEOUT) {
+            continue;
+        }
+        // Only remove the entry if it is still this session
+        if let Some((_, removed)) = state.mcp_sessions.remove_if(&id, |_, s| Arc::ptr_eq(s, &current)) {
+            removed.close().await;
+            tracing::info!("MCP session {} closed after {} idle minutes", id, SESSION_IDLE_TIMEOUT.as_secs() / 60);
+        }
+        return;
+    }
+}
+
+/// No server-initiated stream: every reply comes back on its POST
+pub async fn handle_get() -> Response {
+    (StatusCode::METHOD_NOT_ALLOWED, [(header::ALLOW, "POST, DELETE")]).into_response()
+}
+
+fn session_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+fn is_initialize(message: &Value) -> bool {
+    match message {
+        Value::Array(batch) => batch.iter().any(is_initialize),
+        message => message["method"] == "initialize",
+    }
+}
+
+/// Browsers send Origin on cross-site requests - only accept local pages (DNS rebinding protection)
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return true;
+    };
+    let host = origin
+        .split("://")
+        .nth(1)
+        .unwrap_or_default()
+        .split(['/', ':'])
+        .next()
+        .unwrap_or_default();
+    matches!(host, "localhost" | "127.0.0.1" | "tauri.localhost")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ORIGIN, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_only_local_origins_are_allowed() {
+        assert!(origin_allowed(&HeaderMap::new()));
+        assert!(origin_allowed(&origin("http://localhost:1420")));
+        assert!(origin_allowed(&origin("http://127.0.0.1:5173")));
+        assert!(origin_allowed(&origin("tauri://localhost")));
+        assert!(!origin_allowed(&origin("https://evil.example")));
+        assert!(!origin_allowed(&origin("http://localhost.evil.example")));
+    }
+
+    #[test]
+    fn test_detects_initialize_in_batches() {
+        assert!(is_initialize(&serde_json::json!({ "method": "initialize" })));
+        assert!(is_initialize(&serde_json::json!([{ "method": "ping" }, { "method": "initialize" }])));
+        assert!(!is_initialize(&serde_json::json!({ "method": "tools/list" })));
+    }
+}
@@ -0,0 +1,31 @@
+// MCP (Model Context Protocol) - the server exposes the browser tools and saved workflows to other
+// agent hosts; the client lets runs call tools of external MCP servers
+
+pub mod client;
+pub mod http;
+pub mod protocol;
+pub mod session;
+pub mod stdio;
+pub mod workflows;
+
+pub use client::{is_mcp_tool, McpClient, McpProxyTool};
+pub use session::McpSession;
+pub use stdio::serve_stdio;
+
+use crate::models::JsPolicy;
+
+/// Whether MCP sessions launch Chrome without a window (`TASKER_MCP_HEADLESS=1`)
+pub fn headless_from_env() -> bool {
+    std::env::var("TASKER_MCP_HEADLESS")
+        .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// What execute_javascript may do in MCP sessions (`TASKER_MCP_JS_POLICY=disabled|read_only|full`).
+/// Read-only unless set - MCP clients are not the user's own agent.
+pub fn js_policy_from_env() -> JsPolicy {
+    std::env::var("TASKER_MCP_JS_POLICY")
+        .ok()
+        .and_then(|v| serde_json::from_value(serde_json::Value::String(v.trim().to_ascii_lowercase())).ok())
+        .unwrap_or(JsPolicy::ReadOnly)
+}
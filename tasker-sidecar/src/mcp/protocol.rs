@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Protocol revisions this server speaks, newest first (the client asks for the first)
+pub const SUPPORTED_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// An incoming JSON-RPC request, or a notification when `id` is absent
+#[derive(Debug, Deserialize)]
+pub struct RpcRequest {
+    #[serde(default)]
+    pub jsonrpc: String,
+    /// Present on requests, absent on notifications
+    pub id: Option<Value>,
+    /// Absent on responses the client sends back (we never send requests, so these are ignored)
+    #[serde(default)]
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// A JSON-RPC response: exactly one of `result` / `error` is set
+#[derive(Debug, Serialize)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    pub fn result(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(RpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+/// Pick the protocol version to answer `initialize` with
+pub fn negotiate_version(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|v| SUPPORTED_VERSIONS.iter().find(|s| **s == v))
+        .copied()
+        .unwrap_or(SUPPORTED_VERSIONS[0])
+}
+
+/// A tool as listed by `tools/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub input_schema: Value,
+}
+
+/// Result of `tools/call` (ours, or one from an external server)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CallToolResult {
+    pub content: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    pub is_error: bool,
+}
+
+impl CallToolResult {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: vec![text_content(text)],
+            ..Default::default()
+        }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self {
+            content: vec![text_content(text)],
+            is_error: true,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn text_content(text: impl Into<String>) -> Value {
+    json!({ "type": "text", "text": text.into() })
+}
+
+pub fn image_content(data: String, mime_type: &str) -> Value {
+    json!({ "type": "image", "data": data, "mimeType": mime_type })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiates_supported_version_or_latest() {
+        assert_eq!(negotiate_version(Some("2025-03-26")), "2025-03-26");
+        assert_eq!(negotiate_version(Some("1999-01-01")), SUPPORTED_VERSIONS[0]);
+        assert_eq!(negotiate_version(None), SUPPORTED_VERSIONS[0]);
+    }
+
+    #[test]
+    fn test_response_has_either_result_or_error() {
+        let ok = serde_json::to_value(RpcResponse::result(json!(1), json!({}))).unwrap();
+        assert_eq!(ok, json!({ "jsonrpc": "2.0", "id": 1, "result": {} }));
+
+        let err = serde_json::to_value(RpcResponse::error(json!("a"), METHOD_NOT_FOUND, "nope")).unwrap();
+        assert_eq!(err["error"]["code"], METHOD_NOT_FOUND);
+        assert!(err.get("result").is_none());
+    }
+}
@@ -0,0 +1,447 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::agent::UserMessageBuilder;
+use crate::api::handlers::replay::{launch_replay, wait_for_run};
+use crate::api::state::AppState;
+use crate::browser::{BrowserManager, SelectorMap};
+use crate::models::{DialogPolicy, JsPolicy, StartReplayRequest};
+use crate::runs::{Run, RunStatus};
+use crate::tools::{register_all_tools, ToolContext, ToolRegistry, ToolResult};
+
+use super::protocol::{
+    image_content, negotiate_version, text_content, CallToolResult, McpTool, RpcRequest, RpcResponse,
+    INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+use super::workflows::{load_saved_workflows, WORKFLOW_TOOL_PREFIX};
+
+/// Tool that reports the page and refreshes element indices (MCP clients have no agent loop doing it)
+const BROWSER_STATE_TOOL: &str = "browser_state";
+
+const INSTRUCTIONS: &str = "Tasker drives a real Chrome browser. Call browser_state first and after every \
+page change: element indices in its result are what click_element, input_text and the other element tools \
+take. workflow_* tools run a saved Tasker workflow end to end with its own AI agent and return the result.";
+
+/// Registry tools that only make sense inside Tasker's own agent loop
+fn exposed(name: &str) -> bool {
+    !matches!(
+        name,
+        "done"
+            | "ask_human"
+            | "run_workflow"
+            | "update_plan"
+            | "save_memory"
+            | "recall_memories"
+            | "delete_memory"
+    )
+}
+
+/// Browser launched on the first browser tool call, kept for the session
+struct BrowserSession {
+    browser: Arc<BrowserManager>,
+    ctx: ToolContext,
+}
+
+/// One MCP client connection: its browser, and access to the sidecar's runs for workflow tools
+pub struct McpSession {
+    state: Arc<AppState>,
+    registry: ToolRegistry,
+    headless: bool,
+    js_policy: JsPolicy,
+    browser: Mutex<Option<BrowserSession>>,
+    activity: std::sync::Mutex<Activity>,
+}
+
+/// Messages being handled, and when the last one finished
+struct Activity {
+    in_flight: usize,
+    last_active: Instant,
+}
+
+impl McpSession {
+    pub fn new(state: Arc<AppState>, headless: bool) -> Self {
+        let mut registry = ToolRegistry::new();
+        register_all_tools(&mut registry);
+
+        Self {
+            state,
+            registry,
+            headless,
+            js_policy: super::js_policy_from_env(),
+            browser: Mutex::new(None),
+            activity: std::sync::Mutex::new(Activity {
+                in_flight: 0,
+                last_active: Instant::now(),
+            }),
+        }
+    }
+
+    /// Time since the last message was handled (None while one is being handled)
+    pub fn idle_for(&self) -> Option<Duration> {
+        let activity = self.activity.lock().unwrap();
+        (activity.in_flight == 0).then(|| activity.last_active.elapsed())
+    }
+
+    /// Registry tools offered to the client
+    fn offers_tool(&self, name: &str) -> bool {
+        exposed(name) && !(name == "execute_javascript" && self.js_policy == JsPolicy::Disabled)
+    }
+
+    /// Handle one JSON-RPC message or batch. Returns the reply, if one is due.
+    pub async fn handle_message(&self, message: Value) -> Option<Value> {
+        self.activity.lock().unwrap().in_flight += 1;
+        let reply = self.dispatch(message).await;
+        let mut activity = self.activity.lock().unwrap();
+        activity.in_flight -= 1;
+        activity.last_active = Instant::now();
+        reply
+    }
+
+    async fn dispatch(&self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(batch) if batch.is_empty() => Some(reply(RpcResponse::error(
+                Value::Null,
+                INVALID_REQUEST,
+                "Empty batch",
+            ))),
+            Value::Array(batch) => {
+                let mut replies = Vec::new();
+                for message in batch {
+                    if let Some(r) = self.handle_single(message).await {
+                        replies.push(r);
+                    }
+                }
+                (!replies.is_empty()).then_some(Value::Array(replies))
+            }
+            message => self.handle_single(message).await,
+        }
+    }
+
+    async fn handle_single(&self, message: Value) -> Option<Value> {
+        let request: RpcRequest = match serde_json::from_value(message) {
+            Ok(r) => r,
+            Err(e) => return Some(reply(RpcResponse::error(Value::Null, INVALID_REQUEST, e.to_string()))),
+        };
+        // Responses to server requests and notifications get no reply
+        if request.method.is_empty() || request.is_notification() {
+            if !request.method.is_empty() {
+                tracing::debug!("MCP notification: {}", request.method);
+            }
+            return None;
+        }
+        if request.jsonrpc != "2.0" {
+            return Some(reply(RpcResponse::error(
+                request.id.unwrap_or_default(),
+                INVALID_REQUEST,
+                "jsonrpc must be \"2.0\"",
+            )));
+        }
+
+        let id = request.id.clone().unwrap_or_default();
+        let response = match request.method.as_str() {
+            "initialize" => RpcResponse::result(
+                id,
+                json!({
+                    "protocolVersion": negotiate_version(request.params["protocolVersion"].as_str()),
+                    "capabilities": { "tools": { "listChanged": false } },
+                    "serverInfo": { "name": "tasker", "version": env!("CARGO_PKG_VERSION") },
+                    "instructions": INSTRUCTIONS,
+                }),
+            ),
+            "ping" => RpcResponse::result(id, json!({})),
+            "tools/list" => RpcResponse::result(id, json!({ "tools": self.list_tools() })),
+            "tools/call" => {
+                let Some(name) = request.params["name"].as_str() else {
+                    return Some(reply(RpcResponse::error(id, INVALID_PARAMS, "Missing tool name")));
+                };
+                let arguments = match &request.params["arguments"] {
+                    Value::Null => json!({}),
+                    args => args.clone(),
+                };
+                match self.call_tool(name, arguments).await {
+                    Ok(result) => RpcResponse::result(id, serde_json::to_value(result).unwrap_or_default()),
+                    Err(message) => RpcResponse::error(id, INVALID_PARAMS, message),
+                }
+            }
+            method => RpcResponse::error(id, METHOD_NOT_FOUND, format!("Method not found: {}", method)),
+        };
+        Some(reply(response))
+    }
+
+    /// browser_state, the registry's browser tools, then one tool per saved workflow
+    pub fn list_tools(&self) -> Vec<McpTool> {
+        let mut tools = vec![McpTool {
+            name: BROWSER_STATE_TOOL.to_string(),
+            description: "Get the current page: URL, title, open tabs, dialogs and the numbered interactive \
+                elements, plus a screenshot. Call this before using element indices and after any action that \
+                changes the page."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "screenshot": {
+                        "type": "boolean",
+                        "description": "Include a screenshot",
+                        "default": true
+                    }
+                }
+            }),
+        }];
+
+        let mut registry_tools: Vec<McpTool> = self
+            .registry
+            .definitions()
+            .into_iter()
+            .filter(|def| self.offers_tool(&def.name))
+            .map(|def| McpTool {
+                name: def.name,
+                description: def.description,
+                input_schema: def.parameters,
+            })
+            .collect();
+        registry_tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools.extend(registry_tools);
+
+        match load_saved_workflows() {
+            Ok(workflows) => tools.extend(workflows.iter().map(|w| w.tool())),
+            Err(e) => tracing::warn!("Failed to load saved workflows for MCP: {}", e),
+        }
+
+        tools
+    }
+
+    /// Run a tool. Err = no such tool (a protocol error rather than a failed call).
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, String> {
+        if name == BROWSER_STATE_TOOL {
+            return Ok(self.browser_state(arguments["screenshot"].as_bool().unwrap_or(true)).await);
+        }
+        if name.starts_with(WORKFLOW_TOOL_PREFIX) {
+            return self.run_workflow(name, arguments).await;
+        }
+        if !self.offers_tool(name) || self.registry.get(name).is_none() {
+            return Err(format!("Unknown tool: {}", name));
+        }
+
+        let mut browser = self.browser.lock().await;
+        let session = match self.ensure_browser(&mut browser).await {
+            Ok(session) => session,
+            Err(e) => return Ok(CallToolResult::error(e)),
+        };
+        let result = match self.registry.execute(name, arguments, &session.ctx).await {
+            Ok(result) => result,
+            Err(e) => ToolResult::error(format!("Tool execution error: {}", e)),
+        };
+        Ok(tool_result(result))
+    }
+
+    /// Close the session's browser and finish its run record
+    pub async fn close(&self) {
+        if let Some(session) = self.browser.lock().await.take() {
+            let _ = session.browser.close().await;
+            if let Some(repo) = &self.state.runs_repository {
+                let _ = repo.update_run_status(&session.ctx.run_id, RunStatus::Completed, None);
+            }
+        }
+    }
+
+    /// Launch the browser on first use. Files the tools save go to a run record for this session.
+    async fn ensure_browser<'a>(&self, slot: &'a mut Option<BrowserSession>) -> Result<&'a BrowserSession, String> {
+        if slot.is_none() {
+            let browser = Arc::new(BrowserManager::new());
+            browser
+                .launch_incognito("about:blank", self.headless, None)
+                .await
+                .map_err(|e| format!("Failed to launch browser: {}", e))?;
+            if let Err(e) = browser.start_network_capture().await {
+                tracing::warn!("Network capture unavailable: {}", e);
+            }
+            if let Err(e) = browser.start_console_capture().await {
+                tracing::warn!("Console capture unavailable: {}", e);
+            }
+            if let Err(e) = browser.start_dialog_handling(DialogPolicy::Manual).await {
+                tracing::warn!("Dialog handling unavailable: {}", e);
+            }
+            browser.set_follow_new_tabs(true);
+
+            let mut run = Run::new(
+                None,
+                Some("MCP session".to_string()),
+                Some("Browser tools called by an MCP client".to_string()),
+                None,
+            );
+            run.status = RunStatus::Running;
+            let file_repository = self.state.runs_repository.as_ref().and_then(|repo| {
+                repo.create_run(&run)
+                    .map_err(|e| tracing::warn!("Failed to record MCP session run: {}", e))
+                    .ok()
+                    .map(|_| Arc::new(repo.clone()))
+            });
+
+            let ctx = ToolContext {
+                run_id: run.id,
+                workflow_id: None,
+                browser: Arc::clone(&browser),
+                selector_map: Arc::new(RwLock::new(SelectorMap::new())),
+                file_repository,
+                memories: Arc::new(RwLock::new(Vec::new())),
+                llm: None,
+                js_policy: self.js_policy,
+                human: None,
+                sub_workflows: None,
+                plan: None,
+            };
+            *slot = Some(BrowserSession { browser, ctx });
+        }
+        Ok(slot.as_ref().expect("browser session was just set"))
+    }
+
+    /// Page state as the agent sees it; refreshes the indices element tools use
+    async fn browser_state(&self, with_screenshot: bool) -> CallToolResult {
+        let mut browser = self.browser.lock().await;
+        let session = match self.ensure_browser(&mut browser).await {
+            Ok(session) => session,
+            Err(e) => return CallToolResult::error(e),
+        };
+        let browser = &session.browser;
+
+        let url = browser.current_url().await.unwrap_or_default();
+        let open_dialog = browser.dialogs().open_dialog();
+        let handled_dialogs = browser.dialogs().take_auto_handled();
+        let tab_events = browser.take_tab_events();
+
+        // A blocking dialog stalls DOM extraction - only report the dialog then
+        let (title, dom_result) = if open_dialog.is_none() {
+            let title = browser.get_title().await.unwrap_or_default();
+            let dom_result = browser.get_indexed_elements(false).await.unwrap_or_default();
+            *session.ctx.selector_map.write().await = dom_result.selector_map.clone();
+            (title, dom_result)
+        } else {
+            (String::new(), Default::default())
+        };
+
+        let text = UserMessageBuilder::new()
+            .with_tab_events(&tab_events)
+            .with_dialogs(open_dialog.as_ref(), &handled_dialogs)
+            .with_browser_state(&url, &title, &dom_result)
+            .build();
+
+        let mut result = CallToolResult::text(text);
+        if with_screenshot && open_dialog.is_none() {
+            match browser.screenshot_masked().await {
+                Ok(data) => {
+                    let format = browser.screenshot_format().await;
+                    result.content.push(image_content(data, format.mime_type()));
+                }
+                Err(e) => tracing::warn!("MCP screenshot failed: {}", e),
+            }
+        }
+        result
+    }
+
+    /// Run a saved workflow to completion with Tasker's own agent
+    async fn run_workflow(&self, name: &str, arguments: Value) -> Result<CallToolResult, String> {
+        let saved = load_saved_workflows()
+            .map_err(|e| format!("Failed to load saved workflows: {}", e))?
+            .into_iter()
+            .find(|w| w.tool_name == name)
+            .ok_or_else(|| format!("Unknown tool: {}", name))?;
+        let variables = match saved.variables_from(&arguments) {
+            Ok(v) => v,
+            Err(e) => return Ok(CallToolResult::error(e.to_string())),
+        };
+
+        let request: StartReplayRequest = serde_json::from_value(json!({
+            "workflow": saved.workflow,
+            "variables": variables,
+            "secret_variables": saved.secret_variables(),
+            "headless": self.headless,
+            "llm_provider": crate::config::get_default_provider(),
+            "llm_model": crate::config::get_default_model(),
+        }))
+        .map_err(|e| format!("Invalid workflow {}: {}", saved.workflow.name, e))?;
+
+        let run_id = match launch_replay(&self.state, request).await {
+            Ok(id) => id,
+            Err((_, e)) => return Ok(CallToolResult::error(format!("Failed to start workflow: {}", e))),
+        };
+        let Some(run) = wait_for_run(&self.state, &run_id).await else {
+            return Ok(CallToolResult::error(format!("Run {} disappeared before finishing", run_id)));
+        };
+
+        let summary = match run.status {
+            RunStatus::Completed => run.result.clone().unwrap_or_else(|| "Workflow completed".to_string()),
+            status => format!(
+                "Workflow {}: {}",
+                status.as_str(),
+                run.error.as_deref().unwrap_or("no details")
+            ),
+        };
+        Ok(CallToolResult {
+            content: vec![text_content(summary)],
+            structured_content: Some(json!({
+                "run_id": run.id,
+                "status": run.status.as_str(),
+                "result": run.result,
+                "error": run.error,
+            })),
+            is_error: run.status != RunStatus::Completed,
+        })
+    }
+}
+
+fn reply(response: RpcResponse) -> Value {
+    serde_json::to_value(response).unwrap_or_default()
+}
+
+/// Reply for a message that isn't valid JSON
+pub fn parse_error(e: &serde_json::Error) -> Value {
+    reply(RpcResponse::error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e)))
+}
+
+/// A registry ToolResult as MCP content
+fn tool_result(result: ToolResult) -> CallToolResult {
+    let text = if result.success {
+        result.content.unwrap_or_else(|| "Done".to_string())
+    } else {
+        result.error.unwrap_or_else(|| "Tool failed".to_string())
+    };
+    CallToolResult {
+        content: vec![text_content(text)],
+        // structuredContent must be an object
+        structured_content: result.data.filter(Value::is_object),
+        is_error: !result.success,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_only_tools_are_hidden() {
+        assert!(exposed("click_element"));
+        assert!(exposed("extract_table"));
+        assert!(!exposed("done"));
+        assert!(!exposed("ask_human"));
+        assert!(!exposed("run_workflow"));
+        assert!(!exposed("update_plan"));
+        assert!(!exposed("save_memory"));
+    }
+
+    #[test]
+    fn test_tool_results_map_to_mcp_content() {
+        let ok = tool_result(ToolResult::success_with_data("Clicked", json!({ "index": 3 })));
+        assert!(!ok.is_error);
+        assert_eq!(ok.content[0]["text"], "Clicked");
+        assert_eq!(ok.structured_content, Some(json!({ "index": 3 })));
+
+        let list = tool_result(ToolResult::success_with_data("Tabs", json!([1, 2])));
+        assert!(list.structured_content.is_none());
+
+        let failed = tool_result(ToolResult::error("Element 9 not found"));
+        assert!(failed.is_error);
+        assert_eq!(failed.content[0]["text"], "Element 9 not found");
+    }
+}
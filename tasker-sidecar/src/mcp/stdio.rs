@@ -0,0 +1,39 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::api::state::AppState;
+
+use super::session::{parse_error, McpSession};
+
+/// Serve one MCP client over stdin/stdout (newline-delimited JSON-RPC) until stdin closes.
+/// Logs must go to stderr in this mode - stdout carries only protocol messages.
+pub async fn serve_stdio(state: Arc<AppState>, headless: bool) -> Result<()> {
+    let session = McpSession::new(state, headless);
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    tracing::info!("MCP server listening on stdio");
+
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str(line) {
+            Ok(message) => session.handle_message(message).await,
+            Err(e) => Some(parse_error(&e)),
+        };
+        if let Some(reply) = reply {
+            let mut out = serde_json::to_vec(&reply)?;
+            out.push(b'\n');
+            stdout.write_all(&out).await?;
+            stdout.flush().await?;
+        }
+    }
+
+    tracing::info!("MCP client disconnected");
+    session.close().await;
+    Ok(())
+}
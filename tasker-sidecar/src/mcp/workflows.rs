@@ -0,0 +1,256 @@
+use anyhow::{anyhow, Result};
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::models::Workflow;
+
+use super::protocol::McpTool;
+
+/// Prefix of the tool names saved workflows are exposed under
+pub const WORKFLOW_TOOL_PREFIX: &str = "workflow_";
+
+/// MCP tool names are limited to 64 characters
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// A variable as the app stores it with a workflow
+#[derive(Debug, Clone, Deserialize)]
+pub struct SavedVariable {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub var_type: String,
+    #[serde(default)]
+    pub default_value: Option<Value>,
+    /// Value is masked in logs, steps and prompts
+    #[serde(default)]
+    pub sensitive: bool,
+}
+
+/// A workflow saved in the app, with the tool name it is exposed under
+#[derive(Debug, Clone)]
+pub struct SavedWorkflow {
+    pub tool_name: String,
+    pub workflow: Workflow,
+    pub variables: Vec<SavedVariable>,
+}
+
+impl SavedWorkflow {
+    /// Tool listing: the workflow's variables become its input schema
+    pub fn tool(&self) -> McpTool {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for var in &self.variables {
+            let mut schema = json!({
+                "type": match var.var_type.as_str() {
+                    "number" => "number",
+                    "boolean" => "boolean",
+                    _ => "string",
+                }
+            });
+            match &var.default_value {
+                Some(default) if !default.is_null() => schema["default"] = default.clone(),
+                _ => required.push(var.name.clone()),
+            }
+            properties.insert(var.name.clone(), schema);
+        }
+
+        let mut description = match self.workflow.task_description.as_deref() {
+            Some(task) if !task.trim().is_empty() => {
+                format!("Run the saved Tasker workflow \"{}\": {}", self.workflow.name, task.trim())
+            }
+            _ => format!("Run the saved Tasker workflow \"{}\"", self.workflow.name),
+        };
+        let start_url = self.workflow.metadata.start_url.as_deref().unwrap_or(&self.workflow.start_url);
+        if !start_url.is_empty() {
+            description.push_str(&format!(" (starts at {})", start_url));
+        }
+        description.push_str(". Returns the run's result when it finishes.");
+
+        McpTool {
+            name: self.tool_name.clone(),
+            description,
+            input_schema: json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            }),
+        }
+    }
+
+    /// Names of the variables flagged sensitive, sent as a run's `secret_variables`
+    pub fn secret_variables(&self) -> Vec<String> {
+        self.variables
+            .iter()
+            .filter(|v| v.sensitive)
+            .map(|v| v.name.clone())
+            .collect()
+    }
+
+    /// Check call arguments against the variables and fill in defaults
+    pub fn variables_from(&self, arguments: &Value) -> Result<std::collections::HashMap<String, Value>> {
+        let mut values = std::collections::HashMap::new();
+        for var in &self.variables {
+            match arguments.get(&var.name).filter(|v| !v.is_null()) {
+                Some(value) => {
+                    values.insert(var.name.clone(), value.clone());
+                }
+                None => match &var.default_value {
+                    Some(default) if !default.is_null() => {
+                        values.insert(var.name.clone(), default.clone());
+                    }
+                    _ => return Err(anyhow!("Missing required argument '{}'", var.name)),
+                },
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// Saved workflows from the app's database (empty when the app was never run)
+pub fn load_saved_workflows() -> Result<Vec<SavedWorkflow>> {
+    let Some(db_path) = crate::config::get_db_path() else {
+        return Ok(Vec::new());
+    };
+    let conn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, steps_json, variables_json, metadata_json, created_at, updated_at,
+               version, task_description, stop_when, max_steps
+        FROM workflows WHERE is_deleted = 0 ORDER BY name
+        "#,
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, String>(6)?,
+            row.get::<_, i32>(7)?,
+            row.get::<_, Option<String>>(8)?,
+            row.get::<_, Option<String>>(9)?,
+            row.get::<_, Option<i32>>(10)?,
+        ))
+    })?;
+
+    let mut saved: Vec<SavedWorkflow> = Vec::new();
+    for row in rows {
+        let (id, name, steps, variables, metadata, created_at, updated_at, version, task, stop_when, max_steps) = row?;
+        let parse = |s: &str| serde_json::from_str::<Value>(s).unwrap_or(Value::Null);
+
+        // Same shape the app sends with a replay request
+        let workflow: Workflow = match serde_json::from_value(json!({
+            "id": id,
+            "name": name,
+            "steps": parse(&steps),
+            "variables": parse(&variables),
+            "metadata": parse(&metadata),
+            "created_at": created_at,
+            "updated_at": updated_at,
+            "version": version,
+            "task_description": task,
+            "stop_when": stop_when,
+            "max_steps": max_steps,
+        })) {
+            Ok(w) => w,
+            Err(e) => {
+                tracing::warn!("Skipping workflow {} ({}): {}", name, id, e);
+                continue;
+            }
+        };
+        let variables: Vec<SavedVariable> = serde_json::from_str(&variables).unwrap_or_default();
+
+        let mut tool_name = tool_name_for(&workflow.name);
+        if saved.iter().any(|w| w.tool_name == tool_name) {
+            let suffix: String = workflow.id.chars().filter(|c| c.is_ascii_alphanumeric()).take(8).collect();
+            tool_name.truncate(MAX_TOOL_NAME_LEN - suffix.len() - 1);
+            tool_name = format!("{}_{}", tool_name, suffix);
+        }
+
+        saved.push(SavedWorkflow {
+            tool_name,
+            workflow,
+            variables,
+        });
+    }
+
+    Ok(saved)
+}
+
+/// A saved workflow by ID, name (case-insensitive) or MCP tool name
+pub fn find_saved_workflow(workflow: &str) -> Result<SavedWorkflow> {
+    load_saved_workflows()?
+        .into_iter()
+        .find(|w| {
+            w.workflow.id == workflow || w.workflow.name.eq_ignore_ascii_case(workflow) || w.tool_name == workflow
+        })
+        .ok_or_else(|| anyhow!("No saved workflow named '{}'", workflow))
+}
+
+/// `workflow_` + the name in snake_case, e.g. "Download invoices" -> `workflow_download_invoices`
+pub fn tool_name_for(workflow_name: &str) -> String {
+    let mut slug = String::new();
+    for c in workflow_name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('_') && !slug.is_empty() {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_end_matches('_');
+    let slug = if slug.is_empty() { "unnamed" } else { slug };
+
+    let mut name = format!("{}{}", WORKFLOW_TOOL_PREFIX, slug);
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(variables: Value) -> SavedWorkflow {
+        let workflow: Workflow = serde_json::from_value(json!({
+            "id": "wf-1",
+            "name": "Download invoices",
+            "start_url": "https://billing.example.com",
+            "task_description": "Download this month's invoices",
+        }))
+        .unwrap();
+        SavedWorkflow {
+            tool_name: tool_name_for(&workflow.name),
+            workflow,
+            variables: serde_json::from_value(variables).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_tool_names_are_snake_case_and_bounded() {
+        assert_eq!(tool_name_for("Download invoices"), "workflow_download_invoices");
+        assert_eq!(tool_name_for("  Über-Export (v2)! "), "workflow_ber_export_v2");
+        assert_eq!(tool_name_for("???"), "workflow_unnamed");
+        assert_eq!(tool_name_for(&"x".repeat(100)).len(), MAX_TOOL_NAME_LEN);
+    }
+
+    #[test]
+    fn test_variables_become_input_schema() {
+        let wf = saved(json!([
+            { "name": "month", "type": "string", "default_value": null },
+            { "name": "limit", "type": "number", "default_value": 10 },
+        ]));
+        let tool = wf.tool();
+        assert_eq!(tool.name, "workflow_download_invoices");
+        assert!(tool.description.contains("Download this month's invoices"));
+        assert!(tool.description.contains("https://billing.example.com"));
+        assert_eq!(tool.input_schema["properties"]["limit"], json!({ "type": "number", "default": 10 }));
+        assert_eq!(tool.input_schema["required"], json!(["month"]));
+
+        let values = wf.variables_from(&json!({ "month": "2026-09" })).unwrap();
+        assert_eq!(values["month"], json!("2026-09"));
+        assert_eq!(values["limit"], json!(10));
+        assert!(wf.variables_from(&json!({})).is_err());
+    }
+}
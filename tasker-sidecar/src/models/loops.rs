@@ -0,0 +1,279 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::recorded_action::RecordedAction;
+use super::workflow::{ActionType, WorkflowStep};
+
+/// Iterations of a loop that doesn't set `max_iterations`
+pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+/// Ceiling for any loop, whatever the Taskfile says
+pub const MAX_LOOP_ITERATIONS: usize = 1000;
+
+impl WorkflowStep {
+    /// Whether this step is a for_each or repeat_until loop
+    pub fn is_loop(&self) -> bool {
+        matches!(self.action.action_type, ActionType::ForEach | ActionType::RepeatUntil)
+    }
+
+    /// Steps inside a loop (empty for other steps)
+    pub fn loop_steps(&self) -> Vec<WorkflowStep> {
+        self.action
+            .options
+            .get("steps")
+            .cloned()
+            .and_then(|steps| serde_json::from_value(steps).ok())
+            .unwrap_or_default()
+    }
+
+    fn loop_option(&self, key: &str) -> Option<&str> {
+        self.action.options.get(key).and_then(|v| v.as_str())
+    }
+
+    /// Name the loop entry is substituted under
+    fn loop_item(&self) -> &str {
+        self.loop_option("item").filter(|s| !s.is_empty()).unwrap_or("item")
+    }
+
+    fn max_iterations(&self) -> usize {
+        self.action
+            .options
+            .get("max_iterations")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_ITERATIONS)
+            .min(MAX_LOOP_ITERATIONS)
+    }
+}
+
+/// Unroll every for_each whose list is already known from the run's variables,
+/// substituting `{{item}}` / `{{item.field}}` in the copied steps. Loops over lists
+/// produced during the run, and repeat_until loops, are kept for [`describe_loops`].
+pub fn expand_loops(steps: &[WorkflowStep], variables: &HashMap<String, Value>) -> Vec<WorkflowStep> {
+    let mut expanded = Vec::new();
+    for step in steps {
+        let items = match step.action.action_type {
+            ActionType::ForEach => step
+                .loop_option("items")
+                .and_then(|name| variables.get(name))
+                .and_then(list_items),
+            _ => None,
+        };
+        let Some(items) = items else {
+            expanded.push(step.clone());
+            continue;
+        };
+
+        let body = step.loop_steps();
+        let total = items.len().min(step.max_iterations());
+        for (n, item) in items.iter().take(total).enumerate() {
+            let iteration: Vec<WorkflowStep> = body
+                .iter()
+                .map(|inner| {
+                    let mut copy = substitute_item(inner, step.loop_item(), item);
+                    copy.id = format!("{}[{}].{}", step.id, n, inner.id);
+                    copy.name = format!("[{} {}/{}] {}", step.loop_item(), n + 1, total, copy.name);
+                    copy
+                })
+                .collect();
+            expanded.extend(expand_loops(&iteration, variables));
+        }
+    }
+
+    for (i, step) in expanded.iter_mut().enumerate() {
+        step.order = i as i32;
+    }
+    expanded
+}
+
+/// Entries of a list variable: a JSON array, a string holding one, or one entry per line
+fn list_items(value: &Value) -> Option<Vec<Value>> {
+    match value {
+        Value::Array(items) => Some(items.clone()),
+        Value::String(s) => match serde_json::from_str(s.trim()) {
+            Ok(Value::Array(items)) => Some(items),
+            _ if s.contains('\n') => Some(
+                s.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| Value::String(line.to_string()))
+                    .collect(),
+            ),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Copy of `step` with `{{name}}` and `{{name.field}}` replaced by the loop entry
+fn substitute_item(step: &WorkflowStep, name: &str, item: &Value) -> WorkflowStep {
+    let mut json = serde_json::to_value(step).unwrap_or(Value::Null);
+    replace_placeholders(&mut json, name, item);
+    serde_json::from_value(json).unwrap_or_else(|_| step.clone())
+}
+
+fn replace_placeholders(value: &mut Value, name: &str, item: &Value) {
+    match value {
+        Value::String(s) if s.contains("{{") => {
+            let mut text = s.replace(&format!("{{{{{}}}}}", name), &plain(item));
+            if let Value::Object(fields) = item {
+                for (field, field_value) in fields {
+                    text = text.replace(&format!("{{{{{}.{}}}}}", name, field), &plain(field_value));
+                }
+            }
+            *s = text;
+        }
+        Value::Array(values) => values.iter_mut().for_each(|v| replace_placeholders(v, name, item)),
+        Value::Object(map) => map.values_mut().for_each(|v| replace_placeholders(v, name, item)),
+        _ => {}
+    }
+}
+
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Structured description of the loops left after [`expand_loops`], for the agent's prompt
+pub fn describe_loops(steps: &[WorkflowStep]) -> Option<String> {
+    let mut lines = Vec::new();
+    for step in steps.iter().filter(|s| s.is_loop()) {
+        describe_loop(step, 0, &mut lines);
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn describe_loop(step: &WorkflowStep, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    let header = match step.action.action_type {
+        ActionType::ForEach => format!(
+            "Step {} \"{}\": for each entry of the list `{}` (at most {}), run these steps with the entry as {{{{{}}}}}:",
+            step.order,
+            step.name,
+            step.loop_option("items").unwrap_or_default(),
+            step.max_iterations(),
+            step.loop_item()
+        ),
+        _ => format!(
+            "Step {} \"{}\": repeat these steps until {} (at most {} times):",
+            step.order,
+            step.name,
+            describe_condition(step.action.options.get("until")),
+            step.max_iterations()
+        ),
+    };
+    lines.push(format!("{}{}", indent, header));
+
+    for (i, inner) in step.loop_steps().iter().enumerate() {
+        if inner.is_loop() {
+            describe_loop(inner, depth + 1, lines);
+        } else {
+            let mut action = RecordedAction::from(inner);
+            action.order = i as i32 + 1;
+            lines.push(format!("{}  {} - {}", indent, action.to_hint_string().trim_end(), inner.name));
+        }
+    }
+}
+
+fn describe_condition(condition: Option<&Value>) -> String {
+    let Some(condition) = condition else {
+        return "the goal of the loop is reached".to_string();
+    };
+    let field = |key: &str| condition.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    let element = || {
+        let selector = condition.get("selector");
+        ["css", "xpath", "text", "aria_label"]
+            .iter()
+            .find_map(|key| selector.and_then(|s| s.get(*key)).and_then(|v| v.as_str()))
+            .unwrap_or("the element")
+            .to_string()
+    };
+    match field("type") {
+        "url_match" => format!("the URL matches '{}'", field("value")),
+        "element_visible" => format!("'{}' is visible", element()),
+        "element_hidden" => format!("'{}' is gone (e.g. no more \"next\" link)", element()),
+        "request_complete" => format!("a request to '{}' has completed", field("url_pattern")),
+        _ => format!("this condition holds: {}", condition),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn step(value: Value) -> WorkflowStep {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn supplier_loop() -> WorkflowStep {
+        step(json!({
+            "id": "each",
+            "order": 1,
+            "name": "For each supplier",
+            "action": {
+                "type": "for_each",
+                "options": {
+                    "items": "suppliers",
+                    "item": "supplier",
+                    "max_iterations": 2,
+                    "steps": [
+                        { "id": "open", "name": "Open page", "action": { "type": "navigate", "url": "https://example.com/s/{{supplier.slug}}" } },
+                        { "id": "search", "name": "Search", "action": { "type": "type", "selector": { "css": "#q" }, "value": "{{supplier.name}}" } }
+                    ]
+                }
+            }
+        }))
+    }
+
+    #[test]
+    fn test_unrolls_loops_over_known_lists() {
+        let open = step(json!({ "id": "start", "action": { "type": "navigate", "url": "https://example.com" } }));
+        let variables = HashMap::from([(
+            "suppliers".to_string(),
+            json!([
+                { "slug": "acme", "name": "Acme" },
+                { "slug": "globex", "name": "Globex" },
+                { "slug": "initech", "name": "Initech" }
+            ]),
+        )]);
+
+        let steps = expand_loops(&[open, supplier_loop()], &variables);
+        assert_eq!(steps.len(), 5, "max_iterations caps the unrolled entries");
+        assert_eq!(steps[1].id, "each[0].open");
+        assert_eq!(steps[1].action.url.as_deref(), Some("https://example.com/s/acme"));
+        assert_eq!(steps[4].action.value.as_deref(), Some("Globex"));
+        assert_eq!(steps[4].name, "[supplier 2/2] Search");
+        assert_eq!(steps[4].order, 4);
+        assert!(describe_loops(&steps).is_none());
+
+        let lines = HashMap::from([("suppliers".to_string(), json!("acme\nglobex\n"))]);
+        assert_eq!(list_items(&lines["suppliers"]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_describes_loops_over_run_time_lists() {
+        let pages = step(json!({
+            "id": "pages",
+            "order": 2,
+            "name": "Next page",
+            "action": {
+                "type": "repeat_until",
+                "options": {
+                    "until": { "type": "element_hidden", "selector": { "css": "a.next" } },
+                    "steps": [{ "id": "next", "name": "Click next", "action": { "type": "click", "selector": { "css": "a.next" } } }]
+                }
+            }
+        }));
+
+        let steps = expand_loops(&[supplier_loop(), pages], &HashMap::new());
+        assert_eq!(steps.len(), 2);
+        let text = describe_loops(&steps).unwrap();
+        assert!(text.contains("for each entry of the list `suppliers` (at most 2), run these steps with the entry as {{supplier}}"));
+        assert!(text.contains("repeat these steps until 'a.next' is gone"));
+        assert!(text.contains("at most 10 times"));
+        assert!(text.contains("1. click_element  [selector: a.next] - Click next"));
+    }
+}
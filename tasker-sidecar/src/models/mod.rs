@@ -1,10 +1,12 @@
 pub mod workflow;
+pub mod loops;
 pub mod session;
 pub mod requests;
 pub mod responses;
 pub mod recorded_action;
 
 pub use workflow::*;
+pub use loops::*;
 pub use session::*;
 pub use requests::*;
 pub use responses::*;
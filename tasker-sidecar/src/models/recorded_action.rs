@@ -171,6 +171,135 @@ impl RecordedAction {
         }
     }
 
+    /// Create a double_click, right_click, drag_and_drop or click_at action from a workflow step
+    pub fn pointer(step: &WorkflowStep, timestamp: i64) -> Self {
+        let selector = step.action.selector.as_ref().map(|s| s.value.clone());
+        let target = step.action.target.as_ref().map(|s| s.value.clone());
+        let coords = step.action.coordinates.as_ref().map(|c| (c.x, c.y));
+
+        let (tool, params, description) = match step.action.action_type {
+            ActionType::DoubleClick => ("double_click", json!({}), "Double-click element".to_string()),
+            ActionType::RightClick => ("right_click", json!({}), "Right-click element".to_string()),
+            ActionType::DragAndDrop => match (&target, coords) {
+                (Some(target), _) => ("drag_and_drop", json!({}), format!("Drag element onto {}", target)),
+                (None, Some((x, y))) => ("drag_and_drop", json!({ "to_x": x, "to_y": y }), format!("Drag element to ({}, {})", x, y)),
+                (None, None) => ("drag_and_drop", json!({}), "Drag element".to_string()),
+            },
+            _ => {
+                let (x, y) = coords.unwrap_or_default();
+                ("click_at", json!({ "x": x, "y": y }), format!("Click at ({}, {})", x, y))
+            }
+        };
+
+        Self {
+            order: step.order,
+            tool: tool.to_string(),
+            params,
+            hints: Some(ActionHints {
+                css_selector: selector,
+                // click_at coordinates are already in params
+                coordinates: if tool == "click_at" { None } else { coords },
+                description: Some(if step.name.is_empty() { description } else { step.name.clone() }),
+                ..Default::default()
+            }),
+            screenshot: step.screenshot_after.clone(),
+            timestamp,
+        }
+    }
+
+    /// Create a save_pdf or save_mhtml action from a workflow step (`value` is the file path,
+    /// `options` the PDF options)
+    pub fn document(step: &WorkflowStep, timestamp: i64) -> Self {
+        let tool = if step.action.action_type == ActionType::SaveMhtml { "save_mhtml" } else { "save_pdf" };
+        let mut params = json!({});
+        if tool == "save_pdf" {
+            for (key, value) in &step.action.options {
+                params[key] = value.clone();
+            }
+        }
+        if let Some(path) = &step.action.value {
+            params["file_path"] = json!(path);
+        }
+
+        Self {
+            order: step.order,
+            tool: tool.to_string(),
+            params,
+            hints: Some(ActionHints {
+                description: Some(step.name.clone()),
+                ..Default::default()
+            }),
+            screenshot: None,
+            timestamp,
+        }
+    }
+
+    /// Create an extract_table action from an extract step in table mode (`value` is the
+    /// file path, `options` the format and page limit)
+    pub fn table(step: &WorkflowStep, timestamp: i64) -> Self {
+        let mut params = json!({});
+        for key in ["format", "max_pages", "next_css"] {
+            if let Some(value) = step.action.options.get(key) {
+                params[key] = value.clone();
+            }
+        }
+        if let Some(path) = &step.action.value {
+            params["file_path"] = json!(path);
+        }
+        let selector = step.action.selector.as_ref();
+        if let Some(selector) = selector.filter(|s| s.strategy == SelectorStrategy::Css) {
+            params["css"] = json!(selector.value);
+        }
+
+        Self {
+            order: step.order,
+            tool: "extract_table".to_string(),
+            params,
+            hints: Some(ActionHints {
+                css_selector: selector.map(|s| s.value.clone()),
+                description: Some(step.name.clone()),
+                ..Default::default()
+            }),
+            screenshot: None,
+            timestamp,
+        }
+    }
+
+    /// Create a get_response_body action from an extract_response step. The agent waits
+    /// for the request first (wait_for_request) and keeps the body under the variable name.
+    pub fn response(step: &WorkflowStep, timestamp: i64) -> Self {
+        let option = |key: &str| step.action.options.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+        let url_pattern = option("url_pattern").unwrap_or_default();
+        let mut params = json!({ "url_pattern": url_pattern });
+        if let Some(method) = option("method") {
+            params["method"] = json!(method);
+        }
+        let variable = option("variable").unwrap_or("extracted");
+
+        Self {
+            order: step.order,
+            tool: "get_response_body".to_string(),
+            params,
+            hints: Some(ActionHints {
+                description: Some(format!(
+                    "{} - call wait_for_request for '{}' first, then save the body to memory with key '{}'",
+                    if step.name.is_empty() { "Read an API response" } else { &step.name },
+                    url_pattern,
+                    variable
+                )),
+                ..Default::default()
+            }),
+            screenshot: None,
+            timestamp,
+        }
+    }
+
+    /// Whether an extract step reads a table rather than a single value
+    pub fn is_table_extract(step: &WorkflowStep) -> bool {
+        step.action.action_type == ActionType::Extract
+            && step.action.options.get("mode").and_then(|m| m.as_str()) == Some("table")
+    }
+
     /// Format as human-readable hint for AI prompt
     pub fn to_hint_string(&self) -> String {
         let mut parts = vec![format!("{}. {} ", self.order, self.tool)];
@@ -274,7 +403,7 @@ fn format_value(v: &Value) -> String {
 }
 
 // Conversion from old WorkflowStep format
-use super::workflow::{ActionType, WorkflowStep};
+use super::workflow::{ActionType, SelectorStrategy, WorkflowStep};
 
 impl From<&WorkflowStep> for RecordedAction {
     fn from(step: &WorkflowStep) -> Self {
@@ -344,6 +473,43 @@ impl From<&WorkflowStep> for RecordedAction {
                 let key = step.action.value.clone().unwrap_or_default();
                 RecordedAction::send_keys(step.order, &key, timestamp)
             }
+            ActionType::DoubleClick
+            | ActionType::RightClick
+            | ActionType::DragAndDrop
+            | ActionType::ClickAt => RecordedAction::pointer(step, timestamp),
+            ActionType::SavePdf | ActionType::SaveMhtml => RecordedAction::document(step, timestamp),
+            ActionType::Extract if RecordedAction::is_table_extract(step) => RecordedAction::table(step, timestamp),
+            ActionType::ExtractResponse => RecordedAction::response(step, timestamp),
+            ActionType::RunWorkflow => RecordedAction {
+                order: step.order,
+                tool: "run_workflow".to_string(),
+                params: json!(step.action.options),
+                hints: Some(ActionHints {
+                    description: Some(step.name.clone()),
+                    ..Default::default()
+                }),
+                screenshot: None,
+                timestamp,
+            },
+            ActionType::ForEach | ActionType::RepeatUntil => {
+                // Loop bodies are spelled out separately (see describe_loops)
+                let mut params = json!(step.action.options);
+                if let Some(options) = params.as_object_mut() {
+                    options.remove("steps");
+                }
+                RecordedAction {
+                    order: step.order,
+                    tool: if step.action.action_type == ActionType::ForEach { "for_each" } else { "repeat_until" }
+                        .to_string(),
+                    params,
+                    hints: Some(ActionHints {
+                        description: Some(step.name.clone()),
+                        ..Default::default()
+                    }),
+                    screenshot: None,
+                    timestamp,
+                }
+            }
             ActionType::Extract | ActionType::Hover => {
                 // Fallback for unsupported action types
                 RecordedAction {
@@ -361,3 +527,31 @@ impl From<&WorkflowStep> for RecordedAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_response_step_from_taskfile() {
+        // Shape produced by the Taskfile converter
+        let step: WorkflowStep = serde_json::from_value(json!({
+            "id": "prices",
+            "order": 3,
+            "name": "Read prices",
+            "action": {
+                "type": "extract_response",
+                "options": { "url_pattern": "*/api/prices*", "variable": "prices", "method": "GET" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(step.action.action_type, ActionType::ExtractResponse);
+
+        let action = RecordedAction::from(&step);
+        assert_eq!(action.tool, "get_response_body");
+        assert_eq!(action.params, json!({ "url_pattern": "*/api/prices*", "method": "GET" }));
+        let hint = action.to_hint_string();
+        assert!(hint.contains("wait_for_request for '*/api/prices*'"));
+        assert!(hint.contains("key 'prices'"));
+    }
+}
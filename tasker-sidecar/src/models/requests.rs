@@ -1,7 +1,7 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
-use super::workflow::Workflow;
+use super::workflow::{McpServerConfig, ProxySettings, ScreenshotSettings, Workflow};
 
 #[derive(Debug, Deserialize)]
 pub struct StartRecordingRequest {
@@ -16,6 +16,8 @@ pub struct StartRecordingRequest {
     /// Optional client ID for tracking which client started the recording
     /// Used for cleanup when client disconnects
     pub client_id: Option<String>,
+    /// DevTools endpoint of an already running Chrome to record in instead of launching one
+    pub cdp_url: Option<String>,
 }
 
 fn default_viewport_width() -> i32 {
@@ -27,7 +29,7 @@ fn default_viewport_height() -> i32 {
 
 /// Request to start a replay - AI agent is ALWAYS used
 /// Recorded workflow serves as hints/context for the AI, not strict instructions
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct StartReplayRequest {
     pub workflow: Workflow,
     pub llm_provider: Option<String>,
@@ -43,6 +45,81 @@ pub struct StartReplayRequest {
     pub stop_when: Option<String>,
     /// Max steps override (None = use global default)
     pub max_steps: Option<i32>,
+    /// Names of variables whose values are secret (masked in logs, steps and prompts)
+    #[serde(default)]
+    pub secret_variables: Vec<String>,
+    /// Additional secret values to mask, e.g. resolved `${ENV}` secrets
+    #[serde(default)]
+    pub secrets: Vec<String>,
+    /// Black out password inputs in screenshots (None = enabled)
+    pub mask_password_fields: Option<bool>,
+    /// Record network traffic as a HAR file in the run's files
+    #[serde(default)]
+    pub capture_network: bool,
+    /// Switch to tabs opened by the page, e.g. OAuth popups (None = enabled)
+    pub follow_new_tabs: Option<bool>,
+    /// Draw element boxes and index labels on the screenshots sent to the model
+    #[serde(default)]
+    pub annotate_screenshots: bool,
+    /// Proxy settings for this run, overriding the workflow's
+    pub proxy: Option<ProxySettings>,
+    /// DevTools endpoint (`ws://...` or `http://host:9222`) of an already running Chrome
+    /// to drive instead of launching one
+    pub cdp_url: Option<String>,
+    /// Screenshot format/size settings for this run, overriding the workflow's
+    pub screenshots: Option<ScreenshotSettings>,
+    /// How the page is described to the model each step
+    #[serde(default)]
+    pub page_representation: PageRepresentation,
+    /// How long an `ask_human` question waits for an answer (None = 10 minutes)
+    pub input_timeout_secs: Option<u64>,
+    /// What happens when nobody answers in time
+    #[serde(default)]
+    pub on_input_timeout: InputTimeoutPolicy,
+    /// MCP servers for this run, added to the settings' and workflow's (same name replaces)
+    #[serde(default)]
+    pub mcp_servers: Vec<McpServerConfig>,
+    /// Plan the task before the first step and track progress against the plan
+    #[serde(default)]
+    pub planning: bool,
+}
+
+/// Start one run per dataset row. The run settings are shared; each row's values
+/// override `variables`.
+#[derive(Debug, Deserialize)]
+pub struct StartBatchRequest {
+    #[serde(flatten)]
+    pub run: StartReplayRequest,
+    /// CSV with a header row of variable names, JSONL, or a JSON array of objects
+    pub dataset: Option<String>,
+    /// Rows given directly instead of a dataset
+    #[serde(default)]
+    pub rows: Vec<serde_json::Map<String, serde_json::Value>>,
+    /// Runs executing at the same time (None = 2, at most 10)
+    pub concurrency: Option<usize>,
+}
+
+/// How the page is described to the model each step
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PageRepresentation {
+    /// Interactive elements in the viewport
+    #[default]
+    Elements,
+    /// Accessibility outline of the whole page (headings, landmarks, lists, tables, text)
+    /// followed by the interactive elements - gives text-only models the page structure
+    Outline,
+}
+
+/// What an unanswered `ask_human` question does to the run
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InputTimeoutPolicy {
+    /// End the run as failed
+    #[default]
+    Fail,
+    /// Tell the agent nobody answered and let it carry on
+    Continue,
 }
 
 fn default_iterations() -> i32 {
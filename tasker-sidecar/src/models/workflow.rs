@@ -6,6 +6,10 @@ use std::collections::HashMap;
 #[serde(rename_all = "snake_case")]
 pub enum ActionType {
     Click,
+    DoubleClick,
+    RightClick,
+    DragAndDrop,
+    ClickAt,
     Type,
     Navigate,
     Scroll,
@@ -13,8 +17,19 @@ pub enum ActionType {
     Select,
     Wait,
     Screenshot,
+    SavePdf,
+    SaveMhtml,
     Extract,
+    /// Store the body of a network response (`options.url_pattern`, `options.method`)
+    /// as `options.variable`
+    ExtractResponse,
     Custom,
+    /// Run another saved workflow (`options.workflow`) in the same browser
+    RunWorkflow,
+    /// Run nested steps (`options.steps`) once per entry of a list variable
+    ForEach,
+    /// Run nested steps until `options.until` holds
+    RepeatUntil,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -59,6 +74,9 @@ pub struct BrowserAction {
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub coordinates: Option<Coordinates>,
+    /// Drop target for drag_and_drop (falls back to `coordinates` when absent)
+    #[serde(default, deserialize_with = "flexible_selector", skip_serializing_if = "Option::is_none")]
+    pub target: Option<ElementSelector>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub options: HashMap<String, serde_json::Value>,
     // Extra fields from Tauri format
@@ -194,6 +212,288 @@ pub struct WorkflowMetadata {
     pub start_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub llm_provider: Option<String>,
+    /// How native JS dialogs (alert/confirm/prompt) are answered during runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialog_policy: Option<DialogPolicy>,
+    /// What execute_javascript may do: disabled | read_only | full (default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub javascript_policy: Option<JsPolicy>,
+    /// Device, locale, geolocation and network emulation applied to every tab
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emulation: Option<BrowserEmulation>,
+    /// Proxy (or pool of proxies) the run's browser connects through
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+    /// Format, quality, size limits and clip region of run screenshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshots: Option<ScreenshotSettings>,
+    /// External MCP servers whose tools the agent can call during runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServerConfig>,
+}
+
+/// Image format of run screenshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    #[default]
+    #[serde(alias = "jpg")]
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+        }
+    }
+}
+
+/// A rectangle in viewport CSS pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClipRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Screenshot pipeline settings for a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenshotSettings {
+    #[serde(default)]
+    pub format: ImageFormat,
+    /// JPEG/WebP quality 1-100 (default 80)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+    /// Downscale wider screenshots to this width (default 1280, 0 = never)
+    #[serde(default = "default_max_width")]
+    pub max_width: u32,
+    /// Downscale taller screenshots to this height (default: unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u32>,
+    /// Only capture this part of the viewport
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clip: Option<ClipRegion>,
+}
+
+fn default_max_width() -> u32 {
+    1280
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::default(),
+            quality: None,
+            max_width: default_max_width(),
+            max_height: None,
+            clip: None,
+        }
+    }
+}
+
+/// A single HTTP(S) or SOCKS proxy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProxyConfig {
+    /// e.g. "http://proxy.corp:8080" or "socks5://10.0.0.2:1080"
+    pub server: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// Proxy settings: one `server`, or a `pool` rotated across runs
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProxySettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pool: Vec<ProxyConfig>,
+    #[serde(default)]
+    pub rotation: ProxyRotation,
+    /// Hosts that bypass the proxy, e.g. "localhost", "*.internal"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bypass: Vec<String>,
+}
+
+/// How a run picks its proxy from the pool
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyRotation {
+    #[default]
+    RoundRobin,
+    Random,
+}
+
+impl ProxySettings {
+    /// All configured proxies - the single `server` first, then the pool
+    pub fn proxies(&self) -> Vec<ProxyConfig> {
+        let single = self.server.as_ref().map(|server| ProxyConfig {
+            server: server.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+        });
+        single.into_iter().chain(self.pool.iter().cloned()).collect()
+    }
+
+    /// Proxy for a run. `turn` is how many runs have used these settings before.
+    pub fn choose(&self, turn: usize) -> Option<ProxyConfig> {
+        let proxies = self.proxies();
+        if proxies.is_empty() {
+            return None;
+        }
+        let index = match self.rotation {
+            ProxyRotation::RoundRobin => turn % proxies.len(),
+            ProxyRotation::Random => (uuid::Uuid::new_v4().as_u128() % proxies.len() as u128) as usize,
+        };
+        proxies.into_iter().nth(index)
+    }
+}
+
+/// An external MCP server: a `command` started over stdio, or a streamable HTTP `url`
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct McpServerConfig {
+    /// Prefix of the server's tool names, e.g. "crm" gives "crm__lookup_customer"
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Extra environment for the command (the sidecar's own environment is inherited)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Sent with every HTTP request, e.g. an Authorization header
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Only offer these tools to the agent (empty = all of them)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Limit for connecting and for each tool call (None = 60 seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl McpServerConfig {
+    /// Merge server lists - a later server replaces an earlier one with the same name
+    pub fn merge(lists: impl IntoIterator<Item = Vec<McpServerConfig>>) -> Vec<McpServerConfig> {
+        let mut merged: Vec<McpServerConfig> = Vec::new();
+        for server in lists.into_iter().flatten() {
+            match merged.iter_mut().find(|s| s.name == server.name) {
+                Some(existing) => *existing = server,
+                None => merged.push(server),
+            }
+        }
+        merged
+    }
+}
+
+/// Per-workflow browser emulation. Explicit fields override the device preset.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BrowserEmulation {
+    /// Device preset name, e.g. "iphone_15", "pixel_7", "ipad"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<Viewport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_scale_factor: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mobile: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub touch: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geolocation: Option<Geolocation>,
+    /// IANA timezone, e.g. "Europe/Berlin"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// BCP 47 locale, e.g. "de-DE" - also sent as Accept-Language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<ColorScheme>,
+    /// Network throttling preset: "offline", "slow_3g", "fast_3g" or "4g"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Accuracy in meters
+    #[serde(default = "default_geolocation_accuracy")]
+    pub accuracy: f64,
+}
+
+fn default_geolocation_accuracy() -> f64 {
+    10.0
+}
+
+/// Value for the `prefers-color-scheme` media feature
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+/// How native JavaScript dialogs are handled during a run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DialogPolicy {
+    /// Leave the dialog open and let the agent answer it with handle_dialog
+    #[default]
+    Manual,
+    /// Accept every dialog automatically
+    Accept,
+    /// Dismiss every dialog automatically
+    Dismiss,
+}
+
+/// What scripts passed to execute_javascript may do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum JsPolicy {
+    /// The tool is not offered to the model and refuses to run
+    Disabled,
+    /// Scripts run in an isolated world and may only read the DOM: no network, navigation,
+    /// cookies, storage, DOM changes or simulated input
+    ReadOnly,
+    /// Scripts run in the page with full access
+    #[default]
+    Full,
+}
+
+impl JsPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JsPolicy::Disabled => "disabled",
+            JsPolicy::ReadOnly => "read_only",
+            JsPolicy::Full => "full",
+        }
+    }
 }
 
 fn default_recording_source() -> String {
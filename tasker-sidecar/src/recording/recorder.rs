@@ -227,6 +227,11 @@ impl BrowserRecorder {
         }
     }
 
+    /// Record in an already running Chrome instead of launching one (see BrowserManager::set_cdp_url)
+    pub async fn set_cdp_url(&self, url: Option<String>) {
+        self.browser.set_cdp_url(url).await;
+    }
+
     /// Start a new recording session (uses incognito by default for clean sessions)
     pub async fn start(
         &self,
@@ -466,6 +471,12 @@ impl BrowserRecorder {
             tags: vec![],
             start_url: None,
             llm_provider: None,
+            dialog_policy: None,
+            javascript_policy: None,
+            emulation: None,
+            proxy: None,
+            screenshots: None,
+            mcp_servers: Vec::new(),
         };
 
         tracing::info!("Recording stopped, workflow created: {} ({} steps)", workflow.id, step_count);
@@ -624,6 +635,7 @@ async fn create_step_from_event(
                     url: None,
                     coordinates: coords,
                     options,
+                    target: None,
                     clear_first: None,
                 },
                 name,
@@ -660,6 +672,7 @@ async fn create_step_from_event(
                     url: None,
                     coordinates: None,
                     options,
+                    target: None,
                     clear_first: None,
                 },
                 name,
@@ -688,6 +701,7 @@ async fn create_step_from_event(
                     url: None,
                     coordinates: None,
                     options,
+                    target: None,
                     clear_first: None,
                 },
                 name,
@@ -718,6 +732,7 @@ async fn create_step_from_event(
                     url: None,
                     coordinates: None,
                     options,
+                    target: None,
                     clear_first: None,
                 },
                 name,
@@ -847,6 +862,13 @@ fn convert_step_to_recorded_action(step: &WorkflowStep) -> RecordedAction {
                 timestamp,
             }
         }
+        ActionType::DoubleClick
+        | ActionType::RightClick
+        | ActionType::DragAndDrop
+        | ActionType::ClickAt => RecordedAction::pointer(step, timestamp),
+        ActionType::SavePdf | ActionType::SaveMhtml => RecordedAction::document(step, timestamp),
+        ActionType::Extract if RecordedAction::is_table_extract(step) => RecordedAction::table(step, timestamp),
+        ActionType::ExtractResponse => RecordedAction::response(step, timestamp),
         ActionType::Type => RecordedAction {
             order: step.order,
             tool: "input_text".to_string(),
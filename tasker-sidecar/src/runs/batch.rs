@@ -0,0 +1,387 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::redactor::{Redactor, REDACTED};
+
+/// Runs of a batch executing at the same time unless the request says otherwise
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 2;
+
+/// Upper bound on parallel runs - each one drives its own Chrome
+pub const MAX_BATCH_CONCURRENCY: usize = 10;
+
+/// Batch status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    #[default]
+    Running,
+    /// Every row has finished (some rows may have failed)
+    Completed,
+    Cancelled,
+}
+
+impl BatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchStatus::Running => "running",
+            BatchStatus::Completed => "completed",
+            BatchStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for BatchStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(BatchStatus::Running),
+            "completed" => Ok(BatchStatus::Completed),
+            "cancelled" => Ok(BatchStatus::Cancelled),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Status of one row of a batch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchRowStatus {
+    /// Waiting for a free slot
+    #[default]
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl BatchRowStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchRowStatus::Pending => "pending",
+            BatchRowStatus::Running => "running",
+            BatchRowStatus::Completed => "completed",
+            BatchRowStatus::Failed => "failed",
+            BatchRowStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for BatchRowStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(BatchRowStatus::Pending),
+            "running" => Ok(BatchRowStatus::Running),
+            "completed" => Ok(BatchRowStatus::Completed),
+            "failed" => Ok(BatchRowStatus::Failed),
+            "cancelled" => Ok(BatchRowStatus::Cancelled),
+            _ => Err(()),
+        }
+    }
+}
+
+/// One workflow run per dataset row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Batch {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_name: Option<String>,
+    pub status: BatchStatus,
+    pub concurrency: usize,
+    pub total_rows: usize,
+    /// Rows per status, e.g. {"completed": 12, "pending": 88}
+    #[serde(default)]
+    pub counts: HashMap<String, usize>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Rows (populated when fetching batch details)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<BatchRow>,
+}
+
+impl Batch {
+    pub fn new(workflow_id: Option<String>, workflow_name: Option<String>, concurrency: usize, total_rows: usize) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workflow_id,
+            workflow_name,
+            status: BatchStatus::Running,
+            concurrency,
+            total_rows,
+            counts: HashMap::new(),
+            created_at: Utc::now(),
+            completed_at: None,
+            rows: Vec::new(),
+        }
+    }
+}
+
+/// A row of a batch: its variables and the run that processed them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRow {
+    pub batch_id: String,
+    /// Position in the dataset, starting at 0
+    pub row_index: usize,
+    pub variables: Map<String, Value>,
+    pub status: BatchRowStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchRow {
+    pub fn new(batch_id: &str, row_index: usize, variables: Map<String, Value>) -> Self {
+        Self {
+            batch_id: batch_id.to_string(),
+            row_index,
+            variables,
+            status: BatchRowStatus::Pending,
+            run_id: None,
+            result: None,
+            error: None,
+        }
+    }
+}
+
+/// Copy of a row's variables safe to store and export: secret variables are
+/// replaced wholesale, known secret values masked everywhere else
+pub fn mask_variables(
+    variables: &Map<String, Value>,
+    secret_variables: &[String],
+    redactor: &Redactor,
+) -> Map<String, Value> {
+    variables
+        .iter()
+        .map(|(name, value)| {
+            let masked = if secret_variables.contains(name) && !value.is_null() {
+                Value::String(REDACTED.to_string())
+            } else {
+                redactor.redact_value(value)
+            };
+            (name.clone(), masked)
+        })
+        .collect()
+}
+
+/// Variable rows from a dataset file: CSV with a header row of variable names,
+/// JSONL (one object per line) or a JSON array of objects.
+/// Empty CSV cells are left out so the workflow's default applies; blank CSV rows
+/// are rejected so row numbers keep matching the file.
+pub fn parse_dataset(content: &str) -> Result<Vec<Map<String, Value>>> {
+    let trimmed = content.trim_start_matches('\u{feff}').trim();
+    let rows = if trimmed.starts_with('[') {
+        let rows: Vec<Value> = serde_json::from_str(trimmed).map_err(|e| anyhow!("Invalid JSON dataset: {}", e))?;
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| into_object(row, i + 1))
+            .collect::<Result<Vec<_>>>()?
+    } else if trimmed.starts_with('{') {
+        trimmed
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let row = serde_json::from_str(line).map_err(|e| anyhow!("Invalid JSON on line {}: {}", i + 1, e))?;
+                into_object(row, i + 1)
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        parse_csv(trimmed)?
+    };
+
+    if rows.is_empty() {
+        bail!("Dataset has no rows");
+    }
+    Ok(rows)
+}
+
+fn into_object(row: Value, line: usize) -> Result<Map<String, Value>> {
+    match row {
+        Value::Object(map) => Ok(map),
+        _ => bail!("Row {} is not an object", line),
+    }
+}
+
+fn parse_csv(content: &str) -> Result<Vec<Map<String, Value>>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(content.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    if headers.iter().all(|h| h.is_empty()) {
+        bail!("CSV needs a header row of variable names");
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| anyhow!("Invalid CSV: {}", e))?;
+        let (line, byte) = record.position().map(|p| (p.line(), p.byte() as usize)).unwrap_or_default();
+        // The reader silently skips empty lines; a record starting on a line break means it skipped one
+        if content.as_bytes().get(byte).is_some_and(|b| matches!(b, b'\n' | b'\r')) {
+            bail!("Blank line {} in CSV - remove it or fill in the row", line);
+        }
+        let row: Map<String, Value> = headers
+            .iter()
+            .zip(record.iter())
+            .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+            .map(|(name, value)| (name.clone(), Value::String(value.to_string())))
+            .collect();
+        if row.is_empty() {
+            bail!("Row on line {} of the CSV has no values", line);
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Consolidated results: one line per row with its variables, status, run, result and error.
+/// Rows whose result is a JSON object also get a `result.<field>` column per field.
+pub fn results_csv(rows: &[BatchRow]) -> Result<String> {
+    let mut variable_columns: Vec<&str> = Vec::new();
+    let mut result_columns: Vec<String> = Vec::new();
+    let structured: Vec<Option<Map<String, Value>>> = rows
+        .iter()
+        .map(|row| {
+            for name in row.variables.keys() {
+                if !variable_columns.contains(&name.as_str()) {
+                    variable_columns.push(name);
+                }
+            }
+            let fields = row.result.as_deref().and_then(structured_result);
+            for name in fields.iter().flat_map(|f| f.keys()) {
+                if !result_columns.contains(name) {
+                    result_columns.push(name.clone());
+                }
+            }
+            fields
+        })
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut header: Vec<String> = vec!["row".into(), "status".into(), "run_id".into()];
+    header.extend(variable_columns.iter().map(|c| c.to_string()));
+    header.extend(result_columns.iter().map(|c| format!("result.{}", c)));
+    header.extend(["result".to_string(), "error".to_string()]);
+    writer.write_record(&header)?;
+
+    for (row, fields) in rows.iter().zip(&structured) {
+        let mut record: Vec<String> = vec![
+            (row.row_index + 1).to_string(),
+            row.status.as_str().to_string(),
+            row.run_id.clone().unwrap_or_default(),
+        ];
+        record.extend(variable_columns.iter().map(|c| cell(row.variables.get(*c))));
+        record.extend(result_columns.iter().map(|c| cell(fields.as_ref().and_then(|f| f.get(c)))));
+        record.push(row.result.clone().unwrap_or_default());
+        record.push(row.error.clone().unwrap_or_default());
+        writer.write_record(&record)?;
+    }
+
+    String::from_utf8(writer.into_inner()?).map_err(|e| anyhow!("Invalid UTF-8 in results: {}", e))
+}
+
+/// A result that is a JSON object, bare or in a ```json fence
+pub fn structured_result(result: &str) -> Option<Map<String, Value>> {
+    let trimmed = result.trim();
+    let body = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+    match serde_json::from_str(body.trim()) {
+        Ok(Value::Object(map)) => Some(map),
+        _ => None,
+    }
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parses_csv_jsonl_and_json_datasets() {
+        let csv = "\u{feff}supplier,email\nAcme,\"sales@acme.test\"\n\"Smith, Jones\",\n";
+        let rows = parse_dataset(csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["email"], "sales@acme.test");
+        assert_eq!(rows[1]["supplier"], "Smith, Jones");
+        assert!(rows[1].get("email").is_none());
+
+        let jsonl = "{\"supplier\": \"Acme\", \"qty\": 3}\n\n{\"supplier\": \"Globex\"}\n";
+        let rows = parse_dataset(jsonl).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["qty"], 3);
+
+        let rows = parse_dataset(r#"[{"supplier": "Acme"}]"#).unwrap();
+        assert_eq!(rows[0]["supplier"], "Acme");
+
+        assert!(parse_dataset("supplier\n").is_err());
+
+        // Quoted line breaks don't count as blank lines, real blank lines are rejected
+        let rows = parse_dataset("supplier,note\nAcme,\"two\n\nlines\"\nGlobex,\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        let err = parse_dataset("supplier\nAcme\n\nGlobex\n").unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{}", err);
+        let err = parse_dataset("supplier,email\nAcme,a@acme.test\n,\n").unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{}", err);
+        assert!(parse_dataset("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_mask_variables_hides_secret_columns() {
+        let variables = json!({ "email": "ann@acme.test", "password": "hunter2", "note": "token tok-123" });
+        let masked = mask_variables(
+            variables.as_object().unwrap(),
+            &["password".to_string()],
+            &Redactor::new(vec!["tok-123".to_string()]),
+        );
+        assert_eq!(masked["email"], "ann@acme.test");
+        assert_eq!(masked["password"], REDACTED);
+        assert_eq!(masked["note"], format!("token {}", REDACTED));
+    }
+
+    #[test]
+    fn test_results_csv_flattens_structured_results() {
+        let mut done = BatchRow::new("b", 0, json!({ "supplier": "Acme" }).as_object().unwrap().clone());
+        done.status = BatchRowStatus::Completed;
+        done.run_id = Some("run-1".to_string());
+        done.result = Some("```json\n{\"price\": 9.5, \"in_stock\": true}\n```".to_string());
+
+        let mut failed = BatchRow::new("b", 1, json!({ "supplier": "Globex, Inc" }).as_object().unwrap().clone());
+        failed.status = BatchRowStatus::Failed;
+        failed.error = Some("Login required".to_string());
+
+        let csv = results_csv(&[done, failed]).unwrap();
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let header: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+        assert_eq!(
+            header,
+            ["row", "status", "run_id", "supplier", "result.in_stock", "result.price", "result", "error"]
+        );
+        let records: Vec<Vec<String>> = reader
+            .records()
+            .map(|r| r.unwrap().iter().map(String::from).collect())
+            .collect();
+        assert_eq!(records[0][..6], ["1", "completed", "run-1", "Acme", "true", "9.5"]);
+        assert_eq!(records[1], ["2", "failed", "", "Globex, Inc", "", "", "", "Login required"]);
+    }
+}
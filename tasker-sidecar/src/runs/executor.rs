@@ -1,9 +1,11 @@
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
 use genai::chat::{ChatMessage, ChatRequest, ContentPart, Tool, ToolResponse};
 use genai::resolver::{AuthData, AuthResolver};
 use genai::{Client, ModelIden};
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
@@ -12,19 +14,29 @@ use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 
 use crate::agent::UserMessageBuilder;
-use crate::browser::{BrowserManager, SelectorMap};
-use crate::tools::{register_all_tools, ToolContext, ToolRegistry, ToolResult};
+use crate::browser::{BrowserManager, ConsoleEntry, ConsoleLevel, DOMExtractionResult, DialogWatcher, SelectorMap};
+use crate::mcp::{is_mcp_tool, McpClient, McpProxyTool};
+use crate::models::{DialogPolicy, ImageFormat, InputTimeoutPolicy, JsPolicy, McpServerConfig, PageRepresentation};
+use crate::tools::{register_all_tools, Memory, ToolContext, ToolLlm, ToolRegistry, ToolResult};
 
 
+use super::file_models::RunFile;
+use super::human::{HumanChannel, HumanInputs, Unanswered, DEFAULT_INPUT_TIMEOUT_SECS};
 use super::logger::RunLogger;
-use super::models::{Run, RunStatus, RunStep};
+use super::models::{LogLevel, Run, RunStatus, RunStep};
+use super::plan::{parse_plan_steps, PlanTracker, RunPlan, MAX_REPLANS, REPLAN_AFTER_FAILURES};
+use super::sub_workflow::SubWorkflows;
 
-use crate::llm::prompts::SYSTEM_PROMPT;
+use crate::llm::prompts::{PLANNING_PROMPT, SYSTEM_PROMPT};
 
 const MAX_STEPS: usize = 50;
 const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
 
+/// File path of the network capture stored with each run
+const NETWORK_HAR_PATH: &str = "network.har";
+
 /// Configuration for a run execution
+#[derive(Clone)]
 pub struct ExecutorConfig {
     pub model: String,
     pub api_key: Option<String>,
@@ -35,6 +47,31 @@ pub struct ExecutorConfig {
     pub min_llm_delay_ms: u64,
     /// Whether to capture screenshots after each step (disable for faster execution)
     pub capture_screenshots: bool,
+    /// Black out password inputs in screenshots before they are stored or sent to the model
+    pub mask_password_fields: bool,
+    /// Record network traffic and store it as a HAR file with the run
+    pub capture_network: bool,
+    /// Show recent JS errors / failed loads to the model in the page state
+    pub include_page_errors: bool,
+    /// How native JS dialogs are answered (manual = the agent calls handle_dialog)
+    pub dialog_policy: DialogPolicy,
+    /// Switch to tabs the page opens itself (OAuth popups, `target=_blank` links)
+    pub follow_new_tabs: bool,
+    /// Draw element boxes and index labels on the screenshot sent to the model
+    /// (step screenshots in the run history stay clean)
+    pub annotate_screenshots: bool,
+    /// Interactive elements only, or an accessibility outline of the page as well
+    pub page_representation: PageRepresentation,
+    /// What execute_javascript may do (disabled also hides the tool from the model)
+    pub js_policy: JsPolicy,
+    /// How long ask_human waits for a person
+    pub input_timeout_secs: u64,
+    /// What an unanswered ask_human does to the run
+    pub on_input_timeout: InputTimeoutPolicy,
+    /// External MCP servers whose tools are offered to the agent
+    pub mcp_servers: Vec<McpServerConfig>,
+    /// Plan the task before the first step and track progress through update_plan
+    pub planning: bool,
 }
 
 /// Default minimum delay between LLM calls (2 seconds for rate limit safety)
@@ -50,6 +87,18 @@ impl Default for ExecutorConfig {
             provider: None,
             min_llm_delay_ms: DEFAULT_MIN_LLM_DELAY_MS,
             capture_screenshots: true,
+            mask_password_fields: true,
+            capture_network: false,
+            include_page_errors: true,
+            dialog_policy: DialogPolicy::Manual,
+            follow_new_tabs: true,
+            annotate_screenshots: false,
+            page_representation: PageRepresentation::Elements,
+            js_policy: JsPolicy::Full,
+            input_timeout_secs: DEFAULT_INPUT_TIMEOUT_SECS,
+            on_input_timeout: InputTimeoutPolicy::Fail,
+            mcp_servers: Vec::new(),
+            planning: false,
         }
     }
 }
@@ -62,6 +111,14 @@ pub struct RunExecutor {
     browser: Arc<BrowserManager>,
     /// Cancellation token for graceful shutdown
     cancel_token: CancellationToken,
+    /// Sequence number of the first console entry not yet persisted
+    console_cursor: AtomicU64,
+    /// Where ask_human questions wait for answers (None = nobody to ask)
+    human_inputs: Option<Arc<HumanInputs>>,
+    /// Connected MCP servers backing the registry's proxy tools
+    mcp_clients: Vec<Arc<McpClient>>,
+    /// Memories of the calling run, for sub-workflows that share them
+    shared_memories: Option<Arc<RwLock<Vec<Memory>>>>,
 }
 
 impl RunExecutor {
@@ -75,6 +132,67 @@ impl RunExecutor {
             logger,
             browser,
             cancel_token: CancellationToken::new(),
+            console_cursor: AtomicU64::new(0),
+            human_inputs: None,
+            mcp_clients: Vec::new(),
+            shared_memories: None,
+        }
+    }
+
+    /// Executor for a sub-workflow: drives the caller's browser and stops with the caller
+    pub(crate) fn nested(
+        logger: RunLogger,
+        browser: Arc<BrowserManager>,
+        config: ExecutorConfig,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        let mut executor = Self::new(logger, browser, config);
+        executor.cancel_token = cancel_token;
+        // Console output before the call belongs to the caller
+        executor.console_cursor = AtomicU64::new(executor.browser.console().next_seq());
+        executor
+    }
+
+    /// Read and write the caller's memories instead of starting with none
+    pub(crate) fn with_memories(mut self, memories: Arc<RwLock<Vec<Memory>>>) -> Self {
+        self.shared_memories = Some(memories);
+        self
+    }
+
+    /// Let the agent ask a person through the API's shared question registry
+    pub fn with_human_inputs(mut self, inputs: Arc<HumanInputs>) -> Self {
+        self.human_inputs = Some(inputs);
+        self
+    }
+
+    /// Connect to the configured MCP servers and register their tools.
+    /// A server that can't be reached is logged and left out - the run goes on without it.
+    pub async fn connect_mcp_servers(&mut self, run_id: &str) {
+        let connections = futures_util::future::join_all(self.config.mcp_servers.iter().map(|server| async move {
+            let client = Arc::new(McpClient::connect(server).await?);
+            let tools = client.list_tools().await?;
+            anyhow::Ok((client, tools))
+        }))
+        .await;
+
+        for (server, connection) in self.config.mcp_servers.iter().zip(connections) {
+            match connection {
+                Ok((client, tools)) => {
+                    let proxies = McpProxyTool::for_tools(&client, tools);
+                    let names: Vec<String> = proxies.iter().map(|p| p.name().to_string()).collect();
+                    self.logger.info(
+                        run_id,
+                        format!("MCP server '{}' connected: {}", server.name, names.join(", ")),
+                    );
+                    for proxy in proxies {
+                        self.registry.register(Arc::new(proxy));
+                    }
+                    self.mcp_clients.push(client);
+                }
+                Err(e) => self
+                    .logger
+                    .warn(run_id, format!("MCP server '{}' unavailable: {:#}", server.name, e)),
+            }
         }
     }
 
@@ -91,6 +209,47 @@ impl RunExecutor {
     /// Execute a run
     #[instrument(skip(self, run), fields(run_id = %run.id, model = %self.config.model))]
     pub async fn execute(&self, run: &Run) -> Result<()> {
+        // Network events back the network tools; the HAR is only saved when requested
+        if let Err(e) = self.browser.start_network_capture().await {
+            self.logger.warn(&run.id, format!("Network capture unavailable: {}", e));
+        }
+        if self.config.capture_network {
+            self.browser.network().enable_body_capture();
+        }
+        if let Err(e) = self.browser.start_console_capture().await {
+            self.logger.warn(&run.id, format!("Console capture unavailable: {}", e));
+        }
+        if let Err(e) = self.browser.start_dialog_handling(self.config.dialog_policy).await {
+            self.logger.warn(&run.id, format!("Dialog handling unavailable: {}", e));
+        }
+        self.browser.set_follow_new_tabs(self.config.follow_new_tabs);
+
+        let result = self.run_agent_loop(run).await;
+        self.flush_console_logs(&run.id);
+        for client in &self.mcp_clients {
+            client.close().await;
+        }
+
+        // Save traffic even for failed runs - that's when it matters most
+        if self.config.capture_network {
+            self.save_network_har(run);
+        }
+
+        result
+    }
+
+    /// Execute a sub-workflow run in the caller's already prepared browser.
+    /// Boxed because runs can call workflows that call workflows.
+    pub(crate) fn execute_nested<'a>(&'a self, run: &'a Run) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let result = self.run_agent_loop(run).await;
+            self.flush_console_logs(&run.id);
+            result
+        })
+    }
+
+    /// The AI agent loop
+    async fn run_agent_loop(&self, run: &Run) -> Result<()> {
         let run_id = &run.id;
 
         // Update status to running
@@ -105,13 +264,18 @@ impl RunExecutor {
             user_prompt.push_str(&format!("\n\nWorkflow hints (use as guidance):\n{}", hints));
         }
 
+        // Loops whose lists only exist once the run is underway
+        if let Some(loops) = run.metadata.get("loops").and_then(|v| v.as_str()) {
+            user_prompt.push_str(&format!("\n\n<loops>\n{}\n</loops>", loops));
+        }
+
         // Add custom instructions if provided
         if let Some(instructions) = &run.custom_instructions {
             user_prompt.push_str(&format!("\n\nAdditional instructions:\n{}", instructions));
         }
 
         // Extract variables from metadata for use in tool parameter substitution
-        let variables: HashMap<String, String> = run
+        let mut variables: HashMap<String, String> = run
             .metadata
             .get("variables")
             .and_then(|v| v.as_object())
@@ -148,21 +312,14 @@ Keep taking actions until the condition above is clearly met.
             }
         }
 
+        // Recorded hints may contain values typed during recording
+        let user_prompt = self.logger.redactor().redact_str(&user_prompt);
+
         // Create selector map storage (will be updated before each LLM call)
         let selector_map = Arc::new(RwLock::new(SelectorMap::new()));
 
-        // Create in-memory storage for memories/notes
-        let memories = Arc::new(RwLock::new(Vec::new()));
-
-        // Create tool context with file repository access
-        let ctx = ToolContext {
-            run_id: run_id.clone(),
-            workflow_id: run.workflow_id.clone(),
-            browser: Arc::clone(&self.browser),
-            selector_map: Arc::clone(&selector_map),
-            file_repository: Some(Arc::new(self.logger.repository().clone())),
-            memories: Arc::clone(&memories),
-        };
+        // Create in-memory storage for memories/notes (or use the caller's)
+        let memories = self.shared_memories.clone().unwrap_or_default();
 
         // Create genai client
         // Pass API key directly through AuthResolver instead of using environment variables
@@ -178,6 +335,54 @@ Keep taking actions until the condition above is clearly met.
             Client::default()
         };
 
+        let human = self.human_inputs.as_ref().map(|inputs| {
+            let mut channel = HumanChannel::new(
+                Arc::clone(inputs),
+                self.logger.clone(),
+                run_id.clone(),
+                Duration::from_secs(self.config.input_timeout_secs),
+                self.cancel_token.clone(),
+            );
+            channel.headed = !self.config.headless;
+            channel.on_timeout = self.config.on_input_timeout;
+            channel
+        });
+
+        // The plan is created once the client exists, right before the first step
+        let plan = self
+            .config
+            .planning
+            .then(|| PlanTracker::new(self.logger.clone(), run_id.clone()));
+
+        // Create tool context with file repository access
+        let ctx = ToolContext {
+            run_id: run_id.clone(),
+            workflow_id: run.workflow_id.clone(),
+            browser: Arc::clone(&self.browser),
+            selector_map: Arc::clone(&selector_map),
+            file_repository: Some(Arc::new(self.logger.repository().clone())),
+            memories: Arc::clone(&memories),
+            llm: Some(ToolLlm {
+                client: client.clone(),
+                model: self.config.model.clone(),
+                redactor: self.logger.redactor().clone(),
+            }),
+            js_policy: self.config.js_policy,
+            human: human.clone(),
+            sub_workflows: Some(SubWorkflows {
+                logger: self.logger.clone(),
+                browser: Arc::clone(&self.browser),
+                config: Arc::new(self.config.clone()),
+                human_inputs: self.human_inputs.clone(),
+                cancel_token: self.cancel_token.clone(),
+                memories: Arc::clone(&memories),
+                parent_run_id: run_id.clone(),
+                call_stack: workflow_stack(run),
+                depth: run.metadata.get("workflow_depth").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+            }),
+            plan: plan.clone(),
+        };
+
         // Convert our tools to genai tools
         let tools = self.build_genai_tools();
 
@@ -192,6 +397,16 @@ Keep taking actions until the condition above is clearly met.
         let mut first_iteration = true;
         let mut last_llm_call: Option<Instant> = None;
 
+        // Failed tool calls since the last success, and re-plans made so far
+        let mut recent_failures: Vec<String> = Vec::new();
+        let mut replans = 0u32;
+
+        // Planning phase: a numbered plan the agent works through and keeps up to date
+        if let Some(tracker) = &plan {
+            self.create_plan(run_id, &client, &user_prompt, tracker).await;
+            last_llm_call = Some(Instant::now());
+        }
+
         // Get max_steps from run metadata (workflow override) or use config default
         let max_steps = run
             .metadata
@@ -209,6 +424,32 @@ Keep taking actions until the condition above is clearly met.
                 return Ok(());
             }
 
+            // A person asked to take the browser over - wait until they hand it back
+            if let Some(human) = human.as_ref().filter(|h| h.take_takeover()) {
+                if !human.headed {
+                    self.logger.warn(run_id, "Ignoring take-over request: the browser is headless");
+                    continue;
+                }
+                let handed_back = human
+                    .ask("You have control of the browser. Hand it back when you're done.", Vec::new(), true)
+                    .await;
+                match handed_back {
+                    Ok(note) => {
+                        let mut message = "The user took over the browser and has handed it back. The page may have changed - check the current state before continuing.".to_string();
+                        if !note.trim().is_empty() {
+                            message.push_str(&format!("\nTheir note: {}", note.trim()));
+                        }
+                        history.push(ChatMessage::user(message));
+                    }
+                    Err(Unanswered::Cancelled) => continue,
+                    Err(Unanswered::TimedOut) => {
+                        let error = "The browser was not handed back in time".to_string();
+                        self.logger.status(run_id, RunStatus::Failed, Some(error.clone()));
+                        return Err(anyhow!(error));
+                    }
+                }
+            }
+
             if step_number >= max_steps {
                 self.logger.warn(run_id, format!("Reached maximum steps limit ({})", max_steps));
                 self.logger.status(
@@ -221,18 +462,33 @@ Keep taking actions until the condition above is clearly met.
 
             // Build fresh request: history (text-only) + current page state (WITH screenshot)
             // This way only the LATEST screenshot is sent, not all historical ones
-            let (_page_state_text, chat_req) = if first_iteration {
+            // Persist console output since the last turn; errors are shown to the model
+            let console_entries = self.flush_console_logs(run_id);
+
+            // A blocking dialog stalls DOM extraction, so use the regular page state which reports it
+            let dialog_open = self.browser.dialogs().open_dialog().is_some();
+
+            let current_plan = match &plan {
+                Some(tracker) => tracker.snapshot().await,
+                None => None,
+            };
+
+            let (_page_state_text, chat_req) = if first_iteration && !dialog_open {
                 // First iteration: use initial prompt + screenshot
                 // But ALSO populate selector_map for tools to use
-                let dom_result = self.browser.get_indexed_elements().await.unwrap_or_default();
+                let dom_result = self.browser.get_indexed_elements(self.include_outline()).await.unwrap_or_default();
                 *selector_map.write().await = dom_result.selector_map.clone();
 
-                let screenshot = self.browser.screenshot().await.ok();
-                let req = self.build_request_with_screenshot(&history, &user_prompt, screenshot, &tools);
-                (user_prompt.clone(), req)
+                let text = match &current_plan {
+                    Some(current) => format!("{}\n\n{}", user_prompt, current.to_prompt()),
+                    None => user_prompt.clone(),
+                };
+                let screenshot = self.take_llm_screenshot(&dom_result).await.ok();
+                let req = self.build_request_with_screenshot(&history, &text, screenshot, &tools);
+                (text, req)
             } else {
-                // Subsequent iterations: get current page state + screenshot + memories
-                let (text, req) = self.build_current_state_request(&history, &selector_map, &memories, &tools, step_number, max_steps).await;
+                // Subsequent iterations: get current page state + screenshot + memories + plan
+                let (text, req) = self.build_current_state_request(&history, &selector_map, &memories, current_plan.as_ref(), &console_entries, &tools, step_number, max_steps).await;
                 (text, req)
             };
             first_iteration = false;
@@ -331,6 +587,7 @@ Keep taking actions until the condition above is clearly met.
             // Process each tool call
             let mut tool_responses = Vec::new();
             let mut is_done = false;
+            let mut plan_step_failed = false;
 
             for tool_call in &tool_calls {
                 step_number += 1;
@@ -361,14 +618,47 @@ Keep taking actions until the condition above is clearly met.
 
                 let start = std::time::Instant::now();
 
-                // Execute the tool with resolved parameters
-                let result = match self.registry.execute(tool_name, resolved_params, &ctx).await {
-                    Ok(r) => r,
-                    Err(e) => ToolResult::error(format!("Tool execution error: {}", e)),
+                // Execute the tool with resolved parameters. Page interactions stall while a
+                // native dialog is open, so stop waiting as soon as the action opens one.
+                let dialogs = self.browser.dialogs();
+                let execute = async {
+                    match self.registry.execute(tool_name, resolved_params, &ctx).await {
+                        Ok(r) => r,
+                        Err(e) => ToolResult::error(format!("Tool execution error: {}", e)),
+                    }
+                };
+                let result = if let Some(dialog) = dialogs.open_dialog().filter(|_| !usable_with_open_dialog(tool_name)) {
+                    ToolResult::error(format!(
+                        "A {} dialog is open (\"{}\"). Call handle_dialog first.",
+                        dialog.dialog_type, dialog.message
+                    ))
+                } else {
+                    execute_racing_dialogs(tool_name, execute, dialogs).await
                 };
 
                 let duration_ms = start.elapsed().as_millis() as i64;
 
+                // Bookkeeping for re-planning: the agent giving up on a plan step, or tools failing in a row
+                if tool_name == "update_plan" {
+                    plan_step_failed |= result.success && params["status"] == "failed";
+                } else if result.success {
+                    recent_failures.clear();
+                } else {
+                    recent_failures.push(format!("{}: {}", tool_name, result.error.as_deref().unwrap_or("failed")));
+                }
+
+                // A sub-workflow's structured output becomes variables of this run
+                if tool_name == "run_workflow" && result.success {
+                    let output = result.data.as_ref().and_then(|d| d.get("output")).and_then(|o| o.as_object());
+                    for (name, value) in output.into_iter().flatten() {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        variables.insert(name.clone(), value);
+                    }
+                }
+
                 // Log tool result
                 let result_log = format!(
                     "{} -> {} ({}ms)",
@@ -378,6 +668,9 @@ Keep taking actions until the condition above is clearly met.
                 );
                 tracing::info!("{}", result_log);
                 self.logger.info(run_id, result_log);
+                if tool_name == "execute_javascript" {
+                    self.audit_script(run_id, &params, &result, duration_ms).await;
+                }
 
                 // Update step with result
                 step.complete(
@@ -388,8 +681,8 @@ Keep taking actions until the condition above is clearly met.
                 );
 
                 // Take screenshot after action (if browser tool and screenshots enabled)
-                if self.config.capture_screenshots && is_browser_tool(tool_name) {
-                    if let Ok(screenshot) = self.browser.screenshot().await {
+                if self.config.capture_screenshots && is_browser_tool(tool_name) && dialogs.open_dialog().is_none() {
+                    if let Ok(screenshot) = self.take_screenshot().await {
                         step.screenshot = Some(screenshot);
                     }
                 }
@@ -418,9 +711,10 @@ Keep taking actions until the condition above is clearly met.
                     })
                 };
 
+                // Never echo secrets back to the model
                 tool_responses.push(ToolResponse::new(
                     tool_call.call_id.clone(),
-                    response_content.to_string(),
+                    self.logger.redactor().redact_str(&response_content.to_string()),
                 ));
 
                 // Check if done
@@ -466,6 +760,21 @@ Keep taking actions until the condition above is clearly met.
                 history.push(ChatMessage::from(response));
             }
 
+            // Re-plan when the agent keeps failing or marks a plan step failed
+            if let Some(tracker) = &plan {
+                let stuck = plan_step_failed || recent_failures.len() >= REPLAN_AFTER_FAILURES;
+                if stuck && replans < MAX_REPLANS {
+                    replans += 1;
+                    if self.replan(run_id, &client, &user_prompt, tracker, &recent_failures).await {
+                        history.push(ChatMessage::user(
+                            "Your plan was revised because the previous approach failed. Follow the updated <plan> and don't repeat what failed.",
+                        ));
+                    }
+                    recent_failures.clear();
+                    last_llm_call = Some(Instant::now());
+                }
+            }
+
             // Sliding window: keep only system prompt + initial user prompt + last 10 steps worth of messages
             // Each step adds ~2-3 messages (tool calls + responses), so keep last ~30 messages after the first 2
             const MAX_HISTORY_MESSAGES: usize = 32; // 2 initial + 30 for ~10 steps
@@ -480,11 +789,94 @@ Keep taking actions until the condition above is clearly met.
         Ok(())
     }
 
+    /// Ask the model for a numbered plan before the agent starts.
+    /// Without one the run goes on as a plain agent loop.
+    async fn create_plan(&self, run_id: &str, client: &Client, task_prompt: &str, tracker: &PlanTracker) {
+        self.logger.info(run_id, "Planning the task");
+        match self.ask_planner(client, task_prompt.to_string()).await {
+            Ok(steps) => {
+                self.logger.info(run_id, format!("Plan ready: {} steps", steps.len()));
+                tracker.set(RunPlan::new(steps)).await;
+            }
+            Err(e) => self
+                .logger
+                .warn(run_id, format!("Planning failed, continuing without a plan: {}", e)),
+        }
+    }
+
+    /// Plan the unfinished work again after failures. Returns whether the plan was revised.
+    async fn replan(
+        &self,
+        run_id: &str,
+        client: &Client,
+        task_prompt: &str,
+        tracker: &PlanTracker,
+        failures: &[String],
+    ) -> bool {
+        let Some(current) = tracker.snapshot().await else {
+            return false;
+        };
+        self.logger.info(run_id, "Re-planning after failures");
+
+        let mut prompt = format!("{}\n\nThe agent got stuck on this plan:\n{}", task_prompt, current.to_prompt());
+        if !failures.is_empty() {
+            let failures: Vec<String> = failures.iter().map(|f| format!("- {}", f)).collect();
+            prompt.push_str(&format!("\n\nRecent failures:\n{}", failures.join("\n")));
+        }
+        let url = self.browser.current_url().await.unwrap_or_default();
+        prompt.push_str(&format!(
+            "\n\nCurrent page: {}\n\nList only the steps still to do, avoiding the approach that failed.",
+            url
+        ));
+        let prompt = self.logger.redactor().redact_str(&prompt);
+
+        let steps = match self.ask_planner(client, prompt).await {
+            Ok(steps) => steps,
+            Err(e) => {
+                self.logger.warn(run_id, format!("Re-planning failed: {}", e));
+                return false;
+            }
+        };
+        match tracker.revise(steps).await {
+            Ok(plan) => {
+                self.logger.info(
+                    run_id,
+                    format!("Plan revised (revision {}): {} steps", plan.revision, plan.steps.len()),
+                );
+                true
+            }
+            Err(e) => {
+                self.logger.warn(run_id, format!("Re-planning failed: {}", e));
+                false
+            }
+        }
+    }
+
+    /// One planning call; returns the plan's step descriptions
+    async fn ask_planner(&self, client: &Client, prompt: String) -> Result<Vec<String>> {
+        let req = ChatRequest::new(vec![ChatMessage::system(PLANNING_PROMPT), ChatMessage::user(prompt)]);
+        let response = tokio::time::timeout(
+            Duration::from_secs(120),
+            client.exec_chat(&self.config.model, req, None),
+        )
+        .await
+        .map_err(|_| anyhow!("LLM request timeout after 120s"))?
+        .map_err(|e| anyhow!("{}", e))?;
+
+        let steps = parse_plan_steps(response.first_text().unwrap_or_default());
+        if steps.is_empty() {
+            return Err(anyhow!("The model returned no plan steps"));
+        }
+        Ok(steps)
+    }
+
     /// Convert our tool definitions to genai Tool format
     fn build_genai_tools(&self) -> Vec<Tool> {
         self.registry
             .definitions()
             .into_iter()
+            .filter(|def| !(def.name == "execute_javascript" && self.config.js_policy == JsPolicy::Disabled))
+            .filter(|def| def.name != "update_plan" || self.config.planning)
             .map(|def| {
                 Tool::new(&def.name)
                     .with_description(&def.description)
@@ -498,18 +890,18 @@ Keep taking actions until the condition above is clearly met.
         &self,
         history: &[ChatMessage],
         current_text: &str,
-        screenshot: Option<String>,
+        screenshot: Option<(String, ImageFormat)>,
         tools: &[Tool],
     ) -> ChatRequest {
         let mut req = ChatRequest::new(history.to_vec()).with_tools(tools.to_vec());
 
         // Build current message with optional screenshot
         let mut parts = vec![ContentPart::from_text(current_text)];
-        if let Some(screenshot_base64) = screenshot {
+        if let Some((screenshot_base64, format)) = screenshot {
             parts.push(ContentPart::from_binary_base64(
-                "image/jpeg",
+                format.mime_type(),
                 screenshot_base64,
-                Some("screenshot.jpg".to_string()),
+                Some(format!("screenshot.{}", format.extension())),
             ));
         }
         req = req.append_message(ChatMessage::user(parts));
@@ -517,36 +909,64 @@ Keep taking actions until the condition above is clearly met.
     }
 
     /// Build request with current page state (text + screenshot + memories)
+    #[allow(clippy::too_many_arguments)]
     async fn build_current_state_request(
         &self,
         history: &[ChatMessage],
         selector_map: &Arc<RwLock<SelectorMap>>,
         memories: &Arc<RwLock<Vec<crate::tools::Memory>>>,
+        plan: Option<&RunPlan>,
+        console_entries: &[ConsoleEntry],
         tools: &[Tool],
         step_number: usize,
         max_steps: usize,
     ) -> (String, ChatRequest) {
         let url = self.browser.current_url().await.unwrap_or_default();
-        let title = self.browser.get_title().await.unwrap_or_default();
 
-        // Get DOM extraction result from page
-        let dom_result = self.browser.get_indexed_elements().await.unwrap_or_default();
+        // The page can't be inspected while a native dialog blocks it - only report the dialog
+        let open_dialog = self.browser.dialogs().open_dialog();
+        let handled_dialogs = self.browser.dialogs().take_auto_handled();
+        let tab_events = self.browser.take_tab_events();
 
-        // Update the shared selector map for tools
-        *selector_map.write().await = dom_result.selector_map.clone();
+        let (title, dom_result) = if open_dialog.is_none() {
+            let title = self.browser.get_title().await.unwrap_or_default();
+
+            // Get DOM extraction result from page
+            let dom_result = self.browser.get_indexed_elements(self.include_outline()).await.unwrap_or_default();
+
+            // Update the shared selector map for tools
+            *selector_map.write().await = dom_result.selector_map.clone();
+            (title, dom_result)
+        } else {
+            (String::new(), Default::default())
+        };
 
         // Get current memories snapshot
         let memories_snapshot = memories.read().await;
 
-        // Build text content with memories and step info
+        let page_errors: &[ConsoleEntry] = if self.config.include_page_errors {
+            console_entries
+        } else {
+            &[]
+        };
+
+        // Build text content with memories and step info (input values may hold secrets)
         let text = UserMessageBuilder::new()
+            .with_plan(plan)
             .with_memories(&memories_snapshot)
+            .with_page_errors(page_errors)
+            .with_tab_events(&tab_events)
+            .with_dialogs(open_dialog.as_ref(), &handled_dialogs)
             .with_browser_state(&url, &title, &dom_result)
             .with_step_info(step_number, max_steps)
             .build();
+        let text = self.logger.redactor().redact_str(&text);
 
         // Take screenshot
-        let screenshot = self.browser.screenshot().await.ok();
+        let screenshot = match open_dialog {
+            None => self.take_llm_screenshot(&dom_result).await.ok(),
+            Some(_) => None,
+        };
 
         // Build request
         let req = self.build_request_with_screenshot(history, &text, screenshot, tools);
@@ -554,6 +974,123 @@ Keep taking actions until the condition above is clearly met.
         (text, req)
     }
 
+    /// Persist console entries captured since the last flush as run logs and return them
+    fn flush_console_logs(&self, run_id: &str) -> Vec<ConsoleEntry> {
+        let cursor = self.console_cursor.load(Ordering::SeqCst);
+        let (entries, next_seq) = self.browser.console().since(cursor);
+        // A concurrent flush already took these entries (anything newer stays for the next one)
+        if self
+            .console_cursor
+            .compare_exchange(cursor, next_seq, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Vec::new();
+        }
+
+        for entry in &entries {
+            let level = match entry.level {
+                ConsoleLevel::Error => LogLevel::Error,
+                ConsoleLevel::Warning => LogLevel::Warn,
+                ConsoleLevel::Info | ConsoleLevel::Debug => LogLevel::Debug,
+            };
+            self.logger.log_with_metadata(
+                run_id,
+                level,
+                format!("[page {}] {}", entry.source, entry.text),
+                entry.metadata(),
+            );
+        }
+
+        entries
+    }
+
+    /// Record an executed (or refused) script in the run log for auditing
+    async fn audit_script(&self, run_id: &str, params: &Value, result: &ToolResult, duration_ms: i64) {
+        let policy = self.config.js_policy.as_str();
+        let data = result.data.as_ref();
+        self.logger.log_with_metadata(
+            run_id,
+            if result.success { LogLevel::Info } else { LogLevel::Warn },
+            format!(
+                "execute_javascript ({} policy) {}",
+                policy,
+                if result.success { "ran" } else { "failed" }
+            ),
+            json!({
+                "audit": "execute_javascript",
+                "policy": policy,
+                "url": self.browser.current_url().await.ok(),
+                "script": params["script"],
+                "timeout_ms": params.get("timeout_ms"),
+                "success": result.success,
+                "error": result.error,
+                "result_bytes": data.and_then(|d| d.get("result_bytes")),
+                "truncated": data.and_then(|d| d.get("truncated")),
+                "duration_ms": duration_ms,
+            }),
+        );
+    }
+
+    /// Store captured network traffic as a HAR file in the run's files
+    fn save_network_har(&self, run: &Run) {
+        let network = self.browser.network();
+        if network.is_empty() {
+            return;
+        }
+
+        let har = self.logger.redactor().redact_value(&network.to_har());
+        let content = match serde_json::to_vec_pretty(&har) {
+            Ok(c) => c,
+            Err(e) => {
+                self.logger.warn(&run.id, format!("Failed to serialize HAR: {}", e));
+                return;
+            }
+        };
+
+        let mut file = RunFile::new(run.id.clone(), run.workflow_id.clone(), NETWORK_HAR_PATH.to_string(), content);
+        file.mime_type = "application/json".to_string();
+
+        match self.logger.repository().upsert_file(&file) {
+            Ok(()) => self.logger.info(
+                &run.id,
+                format!("Saved network capture ({} requests) to {}", network.len(), NETWORK_HAR_PATH),
+            ),
+            Err(e) => self.logger.warn(&run.id, format!("Failed to save network capture: {}", e)),
+        }
+    }
+
+    fn include_outline(&self) -> bool {
+        self.config.page_representation == PageRepresentation::Outline
+    }
+
+    /// Take the screenshot sent to the model, annotated with element marks if configured
+    async fn take_llm_screenshot(&self, dom_result: &DOMExtractionResult) -> Result<(String, ImageFormat)> {
+        let screenshot = if self.config.annotate_screenshots {
+            self.browser
+                .screenshot_annotated(&dom_result.selector_map.ordered_elements, self.config.mask_password_fields)
+                .await?
+        } else {
+            self.take_screenshot().await?
+        };
+        Ok((screenshot, self.browser.screenshot_format().await))
+    }
+
+    /// Take a screenshot, masking password inputs if configured
+    async fn take_screenshot(&self) -> Result<String> {
+        if self.config.mask_password_fields {
+            self.browser.screenshot_masked().await
+        } else {
+            self.browser.screenshot().await
+        }
+    }
+}
+
+/// Workflow IDs from the top-level run down to this one
+fn workflow_stack(run: &Run) -> Vec<String> {
+    match run.metadata.get("workflow_stack").and_then(|v| v.as_array()) {
+        Some(stack) => stack.iter().filter_map(|id| id.as_str().map(String::from)).collect(),
+        None => run.workflow_id.iter().cloned().collect(),
+    }
 }
 
 /// Check if a tool interacts with the browser (for screenshot capture)
@@ -564,12 +1101,60 @@ fn is_browser_tool(name: &str) -> bool {
             | "go_to_url"
             | "go_back"
             | "click_element"
+            | "double_click"
+            | "right_click"
+            | "drag_and_drop"
+            | "click_at"
             | "input_text"
             | "scroll_down"
             | "scroll_up"
             | "send_keys"
             | "select_dropdown_option"
             | "execute_javascript"
+            | "handle_dialog"
+    )
+}
+
+/// Check if a tool can run while a native dialog blocks the page
+/// Run a tool, stopping early when the action opens a native dialog. Tools that hand the
+/// browser to someone else run to completion: a person may open dialogs themselves, and a
+/// sub-workflow answers the dialogs its own steps open - dropping it would abandon its run.
+async fn execute_racing_dialogs(
+    tool_name: &str,
+    execute: impl std::future::Future<Output = ToolResult>,
+    dialogs: &DialogWatcher,
+) -> ToolResult {
+    if matches!(tool_name, "ask_human" | "run_workflow") {
+        return execute.await;
+    }
+
+    tokio::select! {
+        r = execute => r,
+        dialog = dialogs.wait_opened() => ToolResult::success(format!(
+            "The action opened a {} dialog: \"{}\". Call handle_dialog to answer it.",
+            dialog.dialog_type, dialog.message
+        )),
+    }
+}
+
+fn usable_with_open_dialog(name: &str) -> bool {
+    // External tools never touch the page
+    is_mcp_tool(name) || matches!(
+        name,
+        "handle_dialog"
+            | "ask_human"
+            | "update_plan"
+            | "done"
+            | "wait"
+            | "wait_for_request"
+            | "get_response_body"
+            | "list_files"
+            | "read_file"
+            | "write_file"
+            | "replace_in_file"
+            | "save_memory"
+            | "recall_memories"
+            | "delete_memory"
     )
 }
 
@@ -616,3 +1201,54 @@ fn resolve_variables_recursive(value: &Value, variables: &HashMap<String, String
         _ => value.clone(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Headless browser with manual dialog handling (None = Chrome isn't installed)
+    async fn browser_with_dialogs() -> Option<BrowserManager> {
+        let browser = BrowserManager::new();
+        if let Err(e) = browser.launch("about:blank", true, None).await {
+            eprintln!("Skipping: Chrome is not available ({})", e);
+            return None;
+        }
+        browser.start_dialog_handling(DialogPolicy::Manual).await.unwrap();
+        Some(browser)
+    }
+
+    /// Stands in for a sub-workflow whose step opens an alert() and answers it
+    async fn callee_with_alert(browser: &BrowserManager) -> ToolResult {
+        let dialogs = browser.dialogs();
+        let (opened, answered) = tokio::join!(browser.evaluate("alert('from the callee')"), async {
+            dialogs.wait_opened().await;
+            dialogs.handle(true, None).await
+        });
+        opened.unwrap();
+        answered.unwrap();
+        ToolResult::success("Sub-workflow completed")
+    }
+
+    #[tokio::test]
+    async fn test_sub_workflow_dialogs_do_not_interrupt_the_call() {
+        let Some(browser) = browser_with_dialogs().await else {
+            return;
+        };
+
+        let result = execute_racing_dialogs("run_workflow", callee_with_alert(&browser), browser.dialogs()).await;
+        assert_eq!(result.content.as_deref(), Some("Sub-workflow completed"));
+        assert!(browser.dialogs().open_dialog().is_none());
+
+        // Any other action stops waiting as soon as the dialog opens
+        let click = async {
+            browser.evaluate("alert('from the page')").await.ok();
+            ToolResult::success("Clicked")
+        };
+        let result = execute_racing_dialogs("click", click, browser.dialogs()).await;
+        let content = result.content.unwrap_or_default();
+        assert!(content.contains("opened a alert dialog"), "{}", content);
+        browser.dialogs().handle(true, None).await.unwrap();
+
+        browser.close().await.unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, DashSet};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use crate::models::InputTimeoutPolicy;
+
+use super::logger::RunLogger;
+use super::models::RunStatus;
+
+/// How long a run waits for a person by default
+pub const DEFAULT_INPUT_TIMEOUT_SECS: u64 = 600;
+
+/// A question waiting for a person (from `ask_human` or a take-over)
+#[derive(Debug, Clone, Serialize)]
+pub struct HumanQuestion {
+    pub question: String,
+    /// Suggested answers - free text is accepted as well
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    /// The person works in the browser window and hands it back instead of typing an answer
+    pub take_over: bool,
+    pub asked_at: DateTime<Utc>,
+    pub timeout_secs: u64,
+}
+
+/// Why a question went unanswered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unanswered {
+    TimedOut,
+    Cancelled,
+}
+
+struct Pending {
+    question: HumanQuestion,
+    answer: oneshot::Sender<String>,
+}
+
+/// Open questions and take-over requests by run ID, shared by executors and the API
+#[derive(Default)]
+pub struct HumanInputs {
+    pending: DashMap<String, Pending>,
+    /// Runs a person asked to take over at the agent's next step
+    takeovers: DashSet<String>,
+}
+
+impl HumanInputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a question for the run and wait for its answer. The wait also ends when
+    /// `cancel` fires - a stopped parent run cancels its sub-workflows through their tokens.
+    pub async fn wait(
+        &self,
+        run_id: &str,
+        question: HumanQuestion,
+        timeout: Duration,
+        cancel: &CancellationToken,
+    ) -> Result<String, Unanswered> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.insert(run_id.to_string(), Pending { question, answer: tx });
+
+        let result = tokio::select! {
+            answer = rx => match answer {
+                Ok(answer) => Ok(answer),
+                // Sender dropped: the run was stopped while waiting
+                Err(_) => Err(Unanswered::Cancelled),
+            },
+            _ = tokio::time::sleep(timeout) => Err(Unanswered::TimedOut),
+            _ = cancel.cancelled() => Err(Unanswered::Cancelled),
+        };
+        self.pending.remove(run_id);
+        result
+    }
+
+    /// The run's open question, if it is waiting for one
+    pub fn question(&self, run_id: &str) -> Option<HumanQuestion> {
+        self.pending.get(run_id).map(|p| p.question.clone())
+    }
+
+    /// Deliver an answer. Returns false if the run isn't waiting for one.
+    pub fn answer(&self, run_id: &str, answer: String) -> bool {
+        match self.pending.remove(run_id) {
+            Some((_, pending)) => pending.answer.send(answer).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Ask the run to hand its browser to a person before the agent's next step
+    pub fn request_takeover(&self, run_id: &str) {
+        self.takeovers.insert(run_id.to_string());
+    }
+
+    /// Whether a take-over was requested for the run (clears the request)
+    pub fn take_takeover(&self, run_id: &str) -> bool {
+        self.takeovers.remove(run_id).is_some()
+    }
+
+    /// Drop the run's open question and take-over request, ending any wait
+    pub fn cancel(&self, run_id: &str) {
+        self.pending.remove(run_id);
+        self.takeovers.remove(run_id);
+    }
+}
+
+/// One run's line to a person: waits through [`HumanInputs`] and reports the wait as run status
+#[derive(Clone)]
+pub struct HumanChannel {
+    inputs: Arc<HumanInputs>,
+    logger: RunLogger,
+    run_id: String,
+    timeout: Duration,
+    /// The run's cancel token - stopping the run ends the wait
+    cancel_token: CancellationToken,
+    /// Take-over needs a browser window the person can see
+    pub headed: bool,
+    pub on_timeout: InputTimeoutPolicy,
+}
+
+impl HumanChannel {
+    pub fn new(
+        inputs: Arc<HumanInputs>,
+        logger: RunLogger,
+        run_id: String,
+        timeout: Duration,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            inputs,
+            logger,
+            run_id,
+            timeout,
+            cancel_token,
+            headed: false,
+            on_timeout: InputTimeoutPolicy::default(),
+        }
+    }
+
+    /// How long a question stays open
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Pause the run until a person answers (or hands the browser back)
+    pub async fn ask(&self, question: &str, options: Vec<String>, take_over: bool) -> Result<String, Unanswered> {
+        let question = HumanQuestion {
+            question: question.to_string(),
+            options,
+            take_over,
+            asked_at: Utc::now(),
+            timeout_secs: self.timeout.as_secs(),
+        };
+
+        self.logger.info(&self.run_id, format!("Waiting for input: {}", question.question));
+        self.logger.status(&self.run_id, RunStatus::WaitingForInput, None);
+        self.logger.input(&self.run_id, Some(question.clone()));
+
+        let answer = self
+            .inputs
+            .wait(&self.run_id, question, self.timeout, &self.cancel_token)
+            .await;
+
+        self.logger.input(&self.run_id, None);
+        match answer {
+            Ok(_) => self.logger.info(&self.run_id, "Input received"),
+            Err(Unanswered::TimedOut) => self.logger.warn(
+                &self.run_id,
+                format!("No input within {}s", self.timeout.as_secs()),
+            ),
+            // The cancelled status is set by the agent loop
+            Err(Unanswered::Cancelled) => return answer,
+        }
+        self.logger.status(&self.run_id, RunStatus::Running, None);
+        answer
+    }
+
+    /// Whether a person asked to take over the browser (clears the request)
+    pub fn take_takeover(&self) -> bool {
+        self.inputs.take_takeover(&self.run_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(text: &str) -> HumanQuestion {
+        HumanQuestion {
+            question: text.to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            take_over: false,
+            asked_at: Utc::now(),
+            timeout_secs: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_answer_resolves_pending_question() {
+        let inputs = Arc::new(HumanInputs::new());
+        assert!(!inputs.answer("run-1", "too early".to_string()));
+
+        let waiter = {
+            let inputs = Arc::clone(&inputs);
+            tokio::spawn(async move { inputs.wait("run-1", question("Continue?"), Duration::from_secs(5), &CancellationToken::new()).await })
+        };
+        while inputs.question("run-1").is_none() {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(inputs.question("run-1").unwrap().question, "Continue?");
+        assert!(inputs.answer("run-1", "Yes".to_string()));
+        assert_eq!(waiter.await.unwrap(), Ok("Yes".to_string()));
+        assert!(inputs.question("run-1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_ends_on_timeout_and_cancel() {
+        let inputs = Arc::new(HumanInputs::new());
+        let token = CancellationToken::new();
+        let result = inputs.wait("run-1", question("Anyone?"), Duration::from_millis(10), &token).await;
+        assert_eq!(result, Err(Unanswered::TimedOut));
+        assert!(inputs.question("run-1").is_none());
+
+        let waiter = {
+            let inputs = Arc::clone(&inputs);
+            let token = token.clone();
+            tokio::spawn(async move { inputs.wait("run-1", question("Still there?"), Duration::from_secs(5), &token).await })
+        };
+        while inputs.question("run-1").is_none() {
+            tokio::task::yield_now().await;
+        }
+        inputs.cancel("run-1");
+        assert_eq!(waiter.await.unwrap(), Err(Unanswered::Cancelled));
+
+        // Stopping a parent run cancels a sub-workflow's token without touching its question
+        let parent = CancellationToken::new();
+        let waiter = {
+            let inputs = Arc::clone(&inputs);
+            let child = parent.child_token();
+            tokio::spawn(async move { inputs.wait("child-1", question("Which one?"), Duration::from_secs(5), &child).await })
+        };
+        while inputs.question("child-1").is_none() {
+            tokio::task::yield_now().await;
+        }
+        parent.cancel();
+        assert_eq!(waiter.await.unwrap(), Err(Unanswered::Cancelled));
+        assert!(inputs.question("child-1").is_none());
+    }
+
+    #[test]
+    fn test_takeover_request_is_consumed_once() {
+        let inputs = HumanInputs::new();
+        assert!(!inputs.take_takeover("run-1"));
+        inputs.request_takeover("run-1");
+        assert!(inputs.take_takeover("run-1"));
+        assert!(!inputs.take_takeover("run-1"));
+    }
+}
@@ -1,6 +1,9 @@
 use tokio::sync::broadcast;
 
+use super::human::HumanQuestion;
 use super::models::{LogLevel, RunLog, RunStep, RunStatus};
+use super::plan::RunPlan;
+use super::redactor::Redactor;
 use super::repository::RunRepository;
 
 /// WebSocket events for run updates
@@ -22,12 +25,24 @@ pub enum RunEvent {
         status: RunStatus,
         error: Option<String>,
     },
+    /// The run asked a person something (None = the question was answered or dropped)
+    Input {
+        run_id: String,
+        question: Option<HumanQuestion>,
+    },
+    /// The run's plan was created, updated or revised
+    Plan {
+        run_id: String,
+        plan: RunPlan,
+    },
 }
 
 /// Run logger for structured logging with persistence and broadcast
 pub struct RunLogger {
     repository: RunRepository,
     broadcast: broadcast::Sender<RunEvent>,
+    /// Masks secret values before anything is persisted or broadcast
+    redactor: Redactor,
 }
 
 impl RunLogger {
@@ -37,9 +52,21 @@ impl RunLogger {
         Self {
             repository,
             broadcast: tx,
+            redactor: Redactor::default(),
         }
     }
 
+    /// Mask the given secrets in every log, step, status and result
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Get the redactor used for this run
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
     /// Subscribe to run events
     pub fn subscribe(&self) -> broadcast::Receiver<RunEvent> {
         self.broadcast.subscribe()
@@ -84,9 +111,9 @@ impl RunLogger {
         message: String,
         metadata: Option<serde_json::Value>,
     ) {
-        let log = RunLog::new(run_id.to_string(), level, message);
+        let log = RunLog::new(run_id.to_string(), level, self.redactor.redact_str(&message));
         let log = if let Some(meta) = metadata {
-            log.with_metadata(meta)
+            log.with_metadata(self.redactor.redact_value(&meta))
         } else {
             log
         };
@@ -105,28 +132,34 @@ impl RunLogger {
 
     /// Create a step in DB (no broadcast - use update_step after completion)
     pub fn step(&self, step: &RunStep) {
+        let step = self.redact_step(step);
+
         // Persist to database only - no broadcast until step completes
-        if let Err(e) = self.repository.create_step(step) {
+        if let Err(e) = self.repository.create_step(&step) {
             tracing::error!("Failed to persist step: {}", e);
         }
     }
 
     /// Update a step after execution and broadcast to clients
     pub fn update_step(&self, step: &RunStep) {
+        let step = self.redact_step(step);
+
         // Update in database
-        if let Err(e) = self.repository.update_step(step) {
+        if let Err(e) = self.repository.update_step(&step) {
             tracing::error!("Failed to update step: {}", e);
         }
 
         // Broadcast completed step to WebSocket clients
         let _ = self.broadcast.send(RunEvent::Step {
             run_id: step.run_id.clone(),
-            step: step.clone(),
+            step,
         });
     }
 
     /// Update run status
     pub fn status(&self, run_id: &str, status: RunStatus, error: Option<String>) {
+        let error = self.redactor.redact_opt(error.as_deref());
+
         // Update in database
         if let Err(e) = self.repository.update_run_status(run_id, status, error.as_deref()) {
             tracing::error!("Failed to update run status: {}", e);
@@ -140,6 +173,36 @@ impl RunLogger {
         });
     }
 
+    /// Broadcast an open question (or that it closed) to clients
+    pub fn input(&self, run_id: &str, question: Option<HumanQuestion>) {
+        let question = question.map(|mut q| {
+            q.question = self.redactor.redact_str(&q.question);
+            q
+        });
+        let _ = self.broadcast.send(RunEvent::Input {
+            run_id: run_id.to_string(),
+            question,
+        });
+    }
+
+    /// Store the run's plan and broadcast it to clients
+    pub fn plan(&self, run_id: &str, plan: &RunPlan) {
+        let mut plan = plan.clone();
+        for step in &mut plan.steps {
+            step.description = self.redactor.redact_str(&step.description);
+            step.note = self.redactor.redact_opt(step.note.as_deref());
+        }
+
+        if let Err(e) = self.repository.update_run_plan(run_id, &plan) {
+            tracing::error!("Failed to update run plan: {}", e);
+        }
+
+        let _ = self.broadcast.send(RunEvent::Plan {
+            run_id: run_id.to_string(),
+            plan,
+        });
+    }
+
     /// Get the repository (for direct access if needed)
     pub fn repository(&self) -> &RunRepository {
         &self.repository
@@ -147,11 +210,23 @@ impl RunLogger {
 
     /// Update run result (final response from agent)
     pub fn result(&self, run_id: &str, result: &str) {
-        if let Err(e) = self.repository.update_run_result(run_id, result) {
+        let result = self.redactor.redact_str(result);
+        if let Err(e) = self.repository.update_run_result(run_id, &result) {
             tracing::error!("Failed to update run result: {}", e);
         }
     }
 
+    /// Copy of a step with secrets masked in params, result and error
+    fn redact_step(&self, step: &RunStep) -> RunStep {
+        let mut step = step.clone();
+        if self.redactor.is_empty() {
+            return step;
+        }
+        step.params = self.redactor.redact_value(&step.params);
+        step.result = step.result.as_ref().map(|r| self.redactor.redact_value(r));
+        step.error = self.redactor.redact_opt(step.error.as_deref());
+        step
+    }
 }
 
 impl Clone for RunLogger {
@@ -159,6 +234,7 @@ impl Clone for RunLogger {
         Self {
             repository: self.repository.clone(),
             broadcast: self.broadcast.clone(),
+            redactor: self.redactor.clone(),
         }
     }
 }
@@ -1,11 +1,21 @@
+pub mod batch;
 pub mod executor;
 pub mod file_models;
+pub mod human;
 pub mod logger;
 pub mod models;
+pub mod plan;
+pub mod redactor;
 pub mod repository;
+pub mod sub_workflow;
 
+pub use batch::{Batch, BatchRow, BatchRowStatus, BatchStatus};
 pub use executor::{ExecutorConfig, RunExecutor};
 pub use file_models::*;
+pub use human::{HumanChannel, HumanInputs, HumanQuestion, Unanswered};
 pub use logger::{RunEvent, RunLogger};
 pub use models::*;
+pub use plan::{PlanStep, PlanStepStatus, PlanTracker, RunPlan};
+pub use redactor::Redactor;
 pub use repository::RunRepository;
+pub use sub_workflow::{SubWorkflowOutcome, SubWorkflows, MAX_WORKFLOW_DEPTH};
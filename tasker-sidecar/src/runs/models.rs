@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::str::FromStr;
 
+use super::plan::RunPlan;
+
 /// Run status enum
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -10,6 +12,8 @@ pub enum RunStatus {
     #[default]
     Pending,
     Running,
+    /// Paused until a person answers an `ask_human` question or hands the browser back
+    WaitingForInput,
     Completed,
     Failed,
     Cancelled,
@@ -20,11 +24,17 @@ impl RunStatus {
         match self {
             RunStatus::Pending => "pending",
             RunStatus::Running => "running",
+            RunStatus::WaitingForInput => "waiting_for_input",
             RunStatus::Completed => "completed",
             RunStatus::Failed => "failed",
             RunStatus::Cancelled => "cancelled",
         }
     }
+
+    /// Completed, failed or cancelled
+    pub fn is_finished(&self) -> bool {
+        matches!(self, RunStatus::Completed | RunStatus::Failed | RunStatus::Cancelled)
+    }
 }
 
 impl FromStr for RunStatus {
@@ -34,6 +44,7 @@ impl FromStr for RunStatus {
         match s {
             "pending" => Ok(RunStatus::Pending),
             "running" => Ok(RunStatus::Running),
+            "waiting_for_input" => Ok(RunStatus::WaitingForInput),
             "completed" => Ok(RunStatus::Completed),
             "failed" => Ok(RunStatus::Failed),
             "cancelled" => Ok(RunStatus::Cancelled),
@@ -101,6 +112,9 @@ pub struct Run {
     pub result: Option<String>,
     #[serde(default)]
     pub metadata: Value,
+    /// Numbered plan the agent works through (runs started with planning)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<RunPlan>,
     /// Steps executed in this run (populated when fetching run details)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub steps: Vec<RunStep>,
@@ -129,6 +143,7 @@ impl Run {
             error: None,
             result: None,
             metadata: Value::Object(serde_json::Map::new()),
+            plan: None,
             steps: Vec::new(),
             logs: Vec::new(),
         }
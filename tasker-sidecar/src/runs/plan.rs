@@ -0,0 +1,302 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::logger::RunLogger;
+
+/// Steps kept from a planner answer
+pub const MAX_PLAN_STEPS: usize = 15;
+
+/// Failed tool calls in a row before the run is re-planned
+pub const REPLAN_AFTER_FAILURES: usize = 3;
+
+/// Re-plans allowed per run
+pub const MAX_REPLANS: u32 = 3;
+
+/// Progress of one plan step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Done,
+    Failed,
+    Skipped,
+}
+
+impl PlanStepStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanStepStatus::Pending => "pending",
+            PlanStepStatus::InProgress => "in_progress",
+            PlanStepStatus::Done => "done",
+            PlanStepStatus::Failed => "failed",
+            PlanStepStatus::Skipped => "skipped",
+        }
+    }
+
+    /// Done or skipped - nothing left to do for the step
+    pub fn is_settled(&self) -> bool {
+        matches!(self, PlanStepStatus::Done | PlanStepStatus::Skipped)
+    }
+
+    fn marker(&self) -> &'static str {
+        match self {
+            PlanStepStatus::Pending => "[ ]",
+            PlanStepStatus::InProgress => "[>]",
+            PlanStepStatus::Done => "[x]",
+            PlanStepStatus::Failed => "[!]",
+            PlanStepStatus::Skipped => "[-]",
+        }
+    }
+}
+
+impl FromStr for PlanStepStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(PlanStepStatus::Pending),
+            "in_progress" => Ok(PlanStepStatus::InProgress),
+            "done" => Ok(PlanStepStatus::Done),
+            "failed" => Ok(PlanStepStatus::Failed),
+            "skipped" => Ok(PlanStepStatus::Skipped),
+            _ => Err(()),
+        }
+    }
+}
+
+/// One numbered step of a run's plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// 1-based position in the plan
+    pub number: usize,
+    pub description: String,
+    pub status: PlanStepStatus,
+    /// The agent's note from its last update (what was found, why it failed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Numbered plan the agent works through, stored with the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunPlan {
+    pub steps: Vec<PlanStep>,
+    /// 0 for the first plan, +1 for each re-plan
+    pub revision: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RunPlan {
+    pub fn new(descriptions: Vec<String>) -> Self {
+        let steps = descriptions
+            .into_iter()
+            .enumerate()
+            .map(|(i, description)| PlanStep {
+                number: i + 1,
+                description,
+                status: PlanStepStatus::Pending,
+                note: None,
+            })
+            .collect();
+        Self {
+            steps,
+            revision: 0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Set a step's status (and note). Starting a step leaves the others alone -
+    /// the agent may work on several at once.
+    pub fn update(&mut self, number: usize, status: PlanStepStatus, note: Option<String>) -> Result<&PlanStep> {
+        let total = self.steps.len();
+        let step = self
+            .steps
+            .iter_mut()
+            .find(|s| s.number == number)
+            .ok_or_else(|| anyhow!("The plan has no step {} (steps 1-{})", number, total))?;
+        step.status = status;
+        if note.is_some() {
+            step.note = note;
+        }
+        self.updated_at = Utc::now();
+        Ok(step)
+    }
+
+    /// Replace the unfinished steps with new ones. Done and skipped steps are kept
+    /// in front, so progress made before the re-plan stays visible.
+    pub fn revise(&mut self, remaining: Vec<String>) {
+        let kept: Vec<PlanStep> = self.steps.iter().filter(|s| s.status.is_settled()).cloned().collect();
+        let mut revised = RunPlan::new(remaining);
+        let mut steps = kept;
+        steps.append(&mut revised.steps);
+        for (i, step) in steps.iter_mut().enumerate() {
+            step.number = i + 1;
+        }
+        self.steps = steps;
+        self.revision += 1;
+        self.updated_at = Utc::now();
+    }
+
+    /// Steps done or skipped
+    pub fn completed(&self) -> usize {
+        self.steps.iter().filter(|s| s.status.is_settled()).count()
+    }
+
+    /// The step being worked on, or the first one still to do
+    pub fn current(&self) -> Option<&PlanStep> {
+        self.steps
+            .iter()
+            .find(|s| s.status == PlanStepStatus::InProgress)
+            .or_else(|| self.steps.iter().find(|s| !s.status.is_settled()))
+    }
+
+    /// Plan progress for the agent's turn message
+    pub fn to_prompt(&self) -> String {
+        let mut lines = vec![
+            "<plan>".to_string(),
+            format!("Plan progress: {}/{} steps done", self.completed(), self.steps.len()),
+        ];
+        for step in &self.steps {
+            let mut line = format!("{} {}. {}", step.status.marker(), step.number, step.description);
+            if let Some(note) = &step.note {
+                line.push_str(&format!(" ({})", note));
+            }
+            lines.push(line);
+        }
+        match self.current() {
+            Some(step) => lines.push(format!(
+                "Current step: {}. Mark steps with update_plan as you start and finish them.",
+                step.number
+            )),
+            None => lines.push("Every step is done - verify the result and call done.".to_string()),
+        }
+        lines.push("</plan>".to_string());
+        lines.join("\n")
+    }
+}
+
+/// Step descriptions from a planner answer: its numbered or bulleted lines
+pub fn parse_plan_steps(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let rest = match line.strip_prefix(['-', '*', '•']) {
+                Some(rest) => rest,
+                None => {
+                    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                    if digits == 0 {
+                        return None;
+                    }
+                    line[digits..].strip_prefix(['.', ')', ':'])?
+                }
+            };
+            let description = rest.trim().trim_matches('*').trim();
+            (!description.is_empty()).then(|| description.to_string())
+        })
+        .take(MAX_PLAN_STEPS)
+        .collect()
+}
+
+/// A run's plan, shared by the executor and the update_plan tool.
+/// Every change is stored with the run and broadcast to clients.
+#[derive(Clone)]
+pub struct PlanTracker {
+    plan: Arc<RwLock<Option<RunPlan>>>,
+    logger: RunLogger,
+    run_id: String,
+}
+
+impl PlanTracker {
+    pub fn new(logger: RunLogger, run_id: String) -> Self {
+        Self {
+            plan: Arc::new(RwLock::new(None)),
+            logger,
+            run_id,
+        }
+    }
+
+    /// Copy of the current plan (None until planning succeeded)
+    pub async fn snapshot(&self) -> Option<RunPlan> {
+        self.plan.read().await.clone()
+    }
+
+    /// Store a new plan
+    pub async fn set(&self, plan: RunPlan) {
+        self.logger.plan(&self.run_id, &plan);
+        *self.plan.write().await = Some(plan);
+    }
+
+    /// Update one step's status; returns the plan after the change
+    pub async fn update_step(&self, number: usize, status: PlanStepStatus, note: Option<String>) -> Result<RunPlan> {
+        let mut guard = self.plan.write().await;
+        let plan = guard.as_mut().ok_or_else(|| anyhow!("This run has no plan"))?;
+        plan.update(number, status, note)?;
+        self.logger.plan(&self.run_id, plan);
+        Ok(plan.clone())
+    }
+
+    /// Replace the unfinished steps after a re-plan
+    pub async fn revise(&self, remaining: Vec<String>) -> Result<RunPlan> {
+        let mut guard = self.plan.write().await;
+        let plan = guard.as_mut().ok_or_else(|| anyhow!("This run has no plan"))?;
+        plan.revise(remaining);
+        self.logger.plan(&self.run_id, plan);
+        Ok(plan.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_numbered_and_bulleted_plans() {
+        let text = "Here is the plan:\n1. Open the supplier portal\n2) Log in with {{email}}\n\n- **Search for the order**\n3: Download the invoice\nThat's it.";
+        assert_eq!(
+            parse_plan_steps(text),
+            vec![
+                "Open the supplier portal",
+                "Log in with {{email}}",
+                "Search for the order",
+                "Download the invoice"
+            ]
+        );
+        assert!(parse_plan_steps("2024 was a good year").is_empty());
+
+        let long: String = (1..=30).map(|n| format!("{}. step\n", n)).collect();
+        assert_eq!(parse_plan_steps(&long).len(), MAX_PLAN_STEPS);
+    }
+
+    #[test]
+    fn test_tracks_progress_and_keeps_finished_steps_on_revise() {
+        let mut plan = RunPlan::new(vec!["Open portal".into(), "Log in".into(), "Export report".into()]);
+        plan.update(1, PlanStepStatus::Done, None).unwrap();
+        plan.update(2, PlanStepStatus::Failed, Some("login button missing".into())).unwrap();
+        assert!(plan.update(7, PlanStepStatus::Done, None).is_err());
+        assert_eq!(plan.current().map(|s| s.number), Some(2));
+
+        let prompt = plan.to_prompt();
+        assert!(prompt.contains("Plan progress: 1/3 steps done"));
+        assert!(prompt.contains("[x] 1. Open portal"));
+        assert!(prompt.contains("[!] 2. Log in (login button missing)"));
+        assert!(prompt.contains("Current step: 2."));
+
+        plan.revise(vec!["Log in through SSO".into(), "Export report".into()]);
+        assert_eq!(plan.revision, 1);
+        let steps: Vec<(usize, &str, PlanStepStatus)> =
+            plan.steps.iter().map(|s| (s.number, s.description.as_str(), s.status)).collect();
+        assert_eq!(
+            steps,
+            vec![
+                (1, "Open portal", PlanStepStatus::Done),
+                (2, "Log in through SSO", PlanStepStatus::Pending),
+                (3, "Export report", PlanStepStatus::Pending),
+            ]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use serde_json::Value;
+
+/// Placeholder written in place of a secret value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Values shorter than this are ignored - masking "a" or "1" would mangle every log line
+const MIN_SECRET_LEN: usize = 3;
+
+/// Masks every known secret value for a run (sensitive variables, resolved `${ENV}` secrets)
+/// before it is persisted, broadcast to clients or sent back to the model.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Secret values, longest first so overlapping secrets are fully masked
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    /// Create a redactor for the given secret values
+    pub fn new(secrets: impl IntoIterator<Item = String>) -> Self {
+        let mut secrets: Vec<String> = secrets
+            .into_iter()
+            .filter(|s| s.chars().count() >= MIN_SECRET_LEN)
+            .collect();
+        // Ties are broken alphabetically so duplicates end up adjacent and dedup drops them all
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    /// A redactor that also masks the given values
+    pub fn extended(&self, secrets: impl IntoIterator<Item = String>) -> Self {
+        Self::new(self.secrets.iter().cloned().chain(secrets))
+    }
+
+    /// Whether there is nothing to redact
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    /// Mask all secret values in a string
+    pub fn redact_str(&self, input: &str) -> String {
+        let mut result = input.to_string();
+        for secret in &self.secrets {
+            if result.contains(secret.as_str()) {
+                result = result.replace(secret.as_str(), REDACTED);
+            }
+        }
+        result
+    }
+
+    /// Mask all secret values in an optional string
+    pub fn redact_opt(&self, input: Option<&str>) -> Option<String> {
+        input.map(|s| self.redact_str(s))
+    }
+
+    /// Recursively mask all secret values in a JSON value (keys and string leaves)
+    pub fn redact_value(&self, value: &Value) -> Value {
+        if self.is_empty() {
+            return value.clone();
+        }
+
+        match value {
+            Value::String(s) => Value::String(self.redact_str(s)),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (self.redact_str(k), self.redact_value(v)))
+                    .collect(),
+            ),
+            Value::Array(arr) => Value::Array(arr.iter().map(|v| self.redact_value(v)).collect()),
+            _ => value.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_str() {
+        let redactor = Redactor::new(vec!["hunter2".to_string()]);
+        assert_eq!(
+            redactor.redact_str("Typed 'hunter2' into element 4"),
+            "Typed '[REDACTED]' into element 4"
+        );
+        assert_eq!(redactor.redact_str("nothing to see"), "nothing to see");
+    }
+
+    #[test]
+    fn test_short_values_ignored() {
+        let redactor = Redactor::new(vec!["ab".to_string(), String::new()]);
+        assert!(redactor.is_empty());
+        assert_eq!(redactor.redact_str("abc"), "abc");
+    }
+
+    #[test]
+    fn test_overlapping_secrets_longest_first() {
+        let redactor = Redactor::new(vec!["pass".to_string(), "password123".to_string()]);
+        assert_eq!(redactor.redact_str("password123"), "[REDACTED]");
+    }
+
+    #[test]
+    fn test_duplicate_secrets_removed() {
+        let redactor = Redactor::new(["abcd", "wxyz", "abcd", "efgh", "wxyz"].map(String::from));
+        assert_eq!(redactor.secrets, vec!["abcd", "efgh", "wxyz"]);
+        assert_eq!(redactor.extended(vec!["efgh".to_string()]).secrets, redactor.secrets);
+    }
+
+    #[test]
+    fn test_redact_value_nested() {
+        let redactor = Redactor::new(vec!["s3cr3t".to_string()]);
+        let value = json!({
+            "text": "login with s3cr3t",
+            "list": ["s3cr3t", 42, true],
+            "nested": { "s3cr3t": null }
+        });
+        assert_eq!(
+            redactor.redact_value(&value),
+            json!({
+                "text": "login with [REDACTED]",
+                "list": ["[REDACTED]", 42, true],
+                "nested": { "[REDACTED]": null }
+            })
+        );
+    }
+}
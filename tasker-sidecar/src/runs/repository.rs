@@ -3,8 +3,10 @@ use rusqlite::{params, Connection, OptionalExtension};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use super::batch::{Batch, BatchRow, BatchRowStatus, BatchStatus};
 use super::file_models::{RunFile, RunFileMetadata, MAX_FILE_SIZE};
 use super::models::{Run, RunListQuery, RunLog, RunStatus, RunStep};
+use super::plan::RunPlan;
 
 /// Database path for runs (uses same Tauri data directory)
 fn get_db_path() -> Result<PathBuf> {
@@ -47,6 +49,17 @@ impl RunRepository {
         Ok(repo)
     }
 
+    /// Repository backed by an in-memory database
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Result<Self> {
+        let repo = Self {
+            conn: Arc::new(Mutex::new(Connection::open_in_memory()?)),
+        };
+        repo.init_schema()?;
+        repo.run_migrations()?;
+        Ok(repo)
+    }
+
     /// Run any necessary migrations
     fn run_migrations(&self) -> Result<()> {
         let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
@@ -61,6 +74,14 @@ impl RunRepository {
             conn.execute("ALTER TABLE runs ADD COLUMN result TEXT", [])?;
         }
 
+        let has_plan_column: bool = conn
+            .prepare("SELECT plan FROM runs LIMIT 0")
+            .is_ok();
+
+        if !has_plan_column {
+            conn.execute("ALTER TABLE runs ADD COLUMN plan TEXT", [])?;
+        }
+
         Ok(())
     }
 
@@ -82,7 +103,8 @@ impl RunRepository {
                 completed_at TEXT,
                 error TEXT,
                 result TEXT,
-                metadata TEXT DEFAULT '{}'
+                metadata TEXT DEFAULT '{}',
+                plan TEXT
             );
 
             CREATE INDEX IF NOT EXISTS idx_runs_workflow_id ON runs(workflow_id);
@@ -140,6 +162,33 @@ impl RunRepository {
             CREATE INDEX IF NOT EXISTS idx_run_files_run_id ON run_files(run_id);
             CREATE INDEX IF NOT EXISTS idx_run_files_workflow_id ON run_files(workflow_id);
             CREATE UNIQUE INDEX IF NOT EXISTS idx_run_files_path ON run_files(run_id, file_path);
+
+            -- Batches table (one run per dataset row)
+            CREATE TABLE IF NOT EXISTS batches (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT,
+                workflow_name TEXT,
+                status TEXT NOT NULL DEFAULT 'running',
+                concurrency INTEGER NOT NULL,
+                total_rows INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                completed_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_batches_created_at ON batches(created_at DESC);
+
+            -- Batch rows table
+            CREATE TABLE IF NOT EXISTS batch_rows (
+                batch_id TEXT NOT NULL,
+                row_index INTEGER NOT NULL,
+                variables TEXT NOT NULL DEFAULT '{}',
+                status TEXT NOT NULL DEFAULT 'pending',
+                run_id TEXT,
+                result TEXT,
+                error TEXT,
+                PRIMARY KEY (batch_id, row_index),
+                FOREIGN KEY (batch_id) REFERENCES batches(id) ON DELETE CASCADE
+            );
             "#,
         )?;
 
@@ -181,7 +230,7 @@ impl RunRepository {
         let mut stmt = conn.prepare(
             r#"
             SELECT id, workflow_id, workflow_name, status, task_description,
-                   custom_instructions, started_at, completed_at, error, result, metadata
+                   custom_instructions, started_at, completed_at, error, result, metadata, plan
             FROM runs WHERE id = ?1
             "#,
         )?;
@@ -263,7 +312,7 @@ impl RunRepository {
         let select_sql = format!(
             r#"
             SELECT id, workflow_id, workflow_name, status, task_description,
-                   custom_instructions, started_at, completed_at, error, result, metadata
+                   custom_instructions, started_at, completed_at, error, result, metadata, plan
             FROM runs {}
             ORDER BY started_at {}
             LIMIT ?{} OFFSET ?{}
@@ -502,6 +551,10 @@ impl RunRepository {
                 let json_str: String = row.get(10)?;
                 serde_json::from_str(&json_str).unwrap_or_default()
             },
+            plan: {
+                let json_str: Option<String> = row.get(11)?;
+                json_str.and_then(|s| serde_json::from_str(&s).ok())
+            },
             steps: Vec::new(),
             logs: Vec::new(),
         })
@@ -519,6 +572,18 @@ impl RunRepository {
         Ok(())
     }
 
+    /// Store a run's plan
+    pub fn update_run_plan(&self, id: &str, plan: &RunPlan) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+
+        conn.execute(
+            "UPDATE runs SET plan = ?1 WHERE id = ?2",
+            params![serde_json::to_string(plan)?, id],
+        )?;
+
+        Ok(())
+    }
+
     // ==================== File Operations ====================
 
     /// Create or update a file (upsert by run_id + file_path)
@@ -702,6 +767,217 @@ impl RunRepository {
         Ok(deleted as i64)
     }
 
+    // ==================== Batch Operations ====================
+
+    /// Create a batch and its pending rows
+    pub fn create_batch(&self, batch: &Batch, rows: &[BatchRow]) -> Result<()> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            r#"
+            INSERT INTO batches (id, workflow_id, workflow_name, status, concurrency, total_rows, created_at, completed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            params![
+                batch.id,
+                batch.workflow_id,
+                batch.workflow_name,
+                batch.status.as_str(),
+                batch.concurrency as i64,
+                batch.total_rows as i64,
+                batch.created_at.to_rfc3339(),
+                batch.completed_at.map(|dt| dt.to_rfc3339()),
+            ],
+        )?;
+        for row in rows {
+            tx.execute(
+                r#"
+                INSERT INTO batch_rows (batch_id, row_index, variables, status, run_id, result, error)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                params![
+                    row.batch_id,
+                    row.row_index as i64,
+                    serde_json::to_string(&row.variables)?,
+                    row.status.as_str(),
+                    row.run_id,
+                    row.result,
+                    row.error,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Update a batch's status
+    pub fn update_batch_status(&self, id: &str, status: BatchStatus) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+
+        let completed_at = (status != BatchStatus::Running).then(|| chrono::Utc::now().to_rfc3339());
+        conn.execute(
+            "UPDATE batches SET status = ?1, completed_at = COALESCE(?2, completed_at) WHERE id = ?3",
+            params![status.as_str(), completed_at, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Save a row's status, run and outcome
+    pub fn update_batch_row(&self, row: &BatchRow) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+
+        conn.execute(
+            r#"
+            UPDATE batch_rows SET status = ?1, run_id = ?2, result = ?3, error = ?4
+            WHERE batch_id = ?5 AND row_index = ?6
+            "#,
+            params![
+                row.status.as_str(),
+                row.run_id,
+                row.result,
+                row.error,
+                row.batch_id,
+                row.row_index as i64,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Mark every row still waiting for a slot as cancelled
+    pub fn cancel_pending_batch_rows(&self, batch_id: &str) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+
+        conn.execute(
+            "UPDATE batch_rows SET status = 'cancelled' WHERE batch_id = ?1 AND status = 'pending'",
+            params![batch_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get a batch by ID with its row counts (and rows when `with_rows` is set)
+    pub fn get_batch(&self, id: &str, with_rows: bool) -> Result<Option<Batch>> {
+        let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+
+        let batch = conn
+            .query_row(
+                r#"
+                SELECT id, workflow_id, workflow_name, status, concurrency, total_rows, created_at, completed_at
+                FROM batches WHERE id = ?1
+                "#,
+                params![id],
+                |row| Ok(self.row_to_batch(row)),
+            )
+            .optional()?;
+
+        match batch {
+            Some(Ok(mut batch)) => {
+                batch.counts = self.batch_counts_internal(&conn, &batch.id)?;
+                if with_rows {
+                    batch.rows = self.get_batch_rows_internal(&conn, &batch.id)?;
+                }
+                Ok(Some(batch))
+            }
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    /// List batches, newest first
+    pub fn list_batches(&self, limit: i64, offset: i64) -> Result<Vec<Batch>> {
+        let conn = self.conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, workflow_id, workflow_name, status, concurrency, total_rows, created_at, completed_at
+            FROM batches
+            ORDER BY created_at DESC
+            LIMIT ?1 OFFSET ?2
+            "#,
+        )?;
+
+        let mut batches: Vec<Batch> = stmt
+            .query_map(params![limit, offset], |row| Ok(self.row_to_batch(row)))?
+            .filter_map(|r| r.ok())
+            .filter_map(|r| r.ok())
+            .collect();
+        drop(stmt);
+
+        for batch in &mut batches {
+            batch.counts = self.batch_counts_internal(&conn, &batch.id)?;
+        }
+        Ok(batches)
+    }
+
+    /// Rows per status
+    fn batch_counts_internal(&self, conn: &Connection, batch_id: &str) -> Result<std::collections::HashMap<String, usize>> {
+        let mut stmt = conn.prepare("SELECT status, COUNT(*) FROM batch_rows WHERE batch_id = ?1 GROUP BY status")?;
+        let counts = stmt
+            .query_map(params![batch_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(counts)
+    }
+
+    /// Internal helper to get a batch's rows in dataset order
+    fn get_batch_rows_internal(&self, conn: &Connection, batch_id: &str) -> Result<Vec<BatchRow>> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT batch_id, row_index, variables, status, run_id, result, error
+            FROM batch_rows WHERE batch_id = ?1
+            ORDER BY row_index ASC
+            "#,
+        )?;
+
+        let rows = stmt
+            .query_map(params![batch_id], |row| {
+                Ok(BatchRow {
+                    batch_id: row.get(0)?,
+                    row_index: row.get::<_, i64>(1)? as usize,
+                    variables: {
+                        let json_str: String = row.get(2)?;
+                        serde_json::from_str(&json_str).unwrap_or_default()
+                    },
+                    status: {
+                        let status_str: String = row.get(3)?;
+                        status_str.parse().unwrap_or(BatchRowStatus::Pending)
+                    },
+                    run_id: row.get(4)?,
+                    result: row.get(5)?,
+                    error: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(rows)
+    }
+
+    /// Helper to convert a row to a Batch
+    fn row_to_batch(&self, row: &rusqlite::Row) -> Result<Batch> {
+        Ok(Batch {
+            id: row.get(0)?,
+            workflow_id: row.get(1)?,
+            workflow_name: row.get(2)?,
+            status: {
+                let status_str: String = row.get(3)?;
+                status_str.parse().unwrap_or_default()
+            },
+            concurrency: row.get::<_, i64>(4)? as usize,
+            total_rows: row.get::<_, i64>(5)? as usize,
+            counts: Default::default(),
+            created_at: parse_datetime(row.get::<_, String>(6)?),
+            completed_at: row.get::<_, Option<String>>(7)?.map(parse_datetime),
+            rows: Vec::new(),
+        })
+    }
+
     /// Helper to convert a row to a RunFile
     fn row_to_file(&self, row: &rusqlite::Row) -> Result<RunFile> {
         Ok(RunFile {
@@ -0,0 +1,312 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::browser::BrowserManager;
+use crate::mcp::workflows::{find_saved_workflow, SavedVariable};
+use crate::models::{describe_loops, expand_loops};
+use crate::tools::Memory;
+
+use super::batch::structured_result;
+use super::executor::{ExecutorConfig, RunExecutor};
+use super::human::HumanInputs;
+use super::logger::RunLogger;
+use super::models::{Run, RunStatus};
+
+/// Nested run_workflow calls allowed below the top-level run
+pub const MAX_WORKFLOW_DEPTH: usize = 3;
+
+/// Tells the callee it is one step of a bigger task
+const SUB_WORKFLOW_INSTRUCTIONS: &str = "You are running as one step of another workflow, in the same browser. \
+The page may already be where you need it (e.g. logged in) - check before navigating. \
+When the workflow produces values the caller needs, end with done and put them in the text as a JSON object.";
+
+/// What a sub-workflow run ended with
+#[derive(Debug, Clone)]
+pub struct SubWorkflowOutcome {
+    pub run_id: String,
+    pub workflow_name: String,
+    pub status: RunStatus,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// Fields of a JSON-object result, handed back to the caller's variables
+    pub output: Map<String, Value>,
+}
+
+/// Runs other saved workflows from inside a run, in the caller's browser.
+/// Each call becomes a child run with its own steps and logs.
+#[derive(Clone)]
+pub struct SubWorkflows {
+    pub(crate) logger: RunLogger,
+    pub(crate) browser: Arc<BrowserManager>,
+    pub(crate) config: Arc<ExecutorConfig>,
+    pub(crate) human_inputs: Option<Arc<HumanInputs>>,
+    /// The caller's token - cancelling the caller stops its sub-workflows too
+    pub(crate) cancel_token: CancellationToken,
+    /// The caller's memories, shared when a call asks for it
+    pub(crate) memories: Arc<RwLock<Vec<Memory>>>,
+    pub(crate) parent_run_id: String,
+    /// Workflow IDs from the top-level run down to the caller
+    pub(crate) call_stack: Vec<String>,
+    /// How many sub-workflow levels the caller is below the top-level run
+    pub(crate) depth: usize,
+}
+
+impl SubWorkflows {
+    /// Run a saved workflow (by ID or name) with the given variables and wait for it
+    pub async fn run(&self, workflow: &str, variables: Map<String, Value>, share_memories: bool) -> Result<SubWorkflowOutcome> {
+        let saved = find_saved_workflow(workflow)?;
+        let secret_variables = saved.secret_variables();
+        let callee = saved.workflow;
+        check_call(&self.call_stack, self.depth, &callee.id, &callee.name)?;
+
+        let values = call_variables(&saved.variables, variables);
+        // The callee's sensitive values are masked in its run and everything it logs
+        let logger = self
+            .logger
+            .clone()
+            .with_redactor(self.logger.redactor().extended(secret_values(&values, &secret_variables)));
+
+        let mut call_stack = self.call_stack.clone();
+        call_stack.push(callee.id.clone());
+
+        let steps = expand_loops(&callee.steps, &values);
+        let mut run = Run::new(
+            Some(callee.id.clone()),
+            Some(callee.name.clone()),
+            callee.task_description.clone().or_else(|| Some(format!("Complete the workflow '{}'", callee.name))),
+            Some(SUB_WORKFLOW_INSTRUCTIONS.to_string()),
+        );
+        run.metadata = json!({
+            "hints": steps,
+            "loops": describe_loops(&steps),
+            "variables": values,
+            "stop_when": callee.stop_when,
+            "max_steps": callee.max_steps,
+            "parent_run_id": self.parent_run_id,
+            "workflow_stack": call_stack,
+            "workflow_depth": self.depth + 1,
+            "secret_variables": secret_variables,
+        });
+
+        let mut persisted = run.clone();
+        persisted.metadata = logger.redactor().redact_value(&run.metadata);
+        let repo = logger.repository();
+        repo.create_run(&persisted)?;
+        self.logger.info(
+            &self.parent_run_id,
+            format!("Running sub-workflow '{}' as run {}", callee.name, run.id),
+        );
+
+        let mut executor = RunExecutor::nested(
+            logger.clone(),
+            Arc::clone(&self.browser),
+            (*self.config).clone(),
+            self.cancel_token.child_token(),
+        );
+        if let Some(inputs) = &self.human_inputs {
+            executor = executor.with_human_inputs(Arc::clone(inputs));
+        }
+        if share_memories {
+            executor = executor.with_memories(Arc::clone(&self.memories));
+        }
+        let guard = ChildRunGuard { logger: logger.clone(), run_id: run.id.clone(), finished: false };
+        let error = executor.execute_nested(&run).await.err().map(|e| {
+            tracing::warn!("Sub-workflow run {} failed: {}", run.id, e);
+            e.to_string()
+        });
+        guard.finish(RunStatus::Failed, error.unwrap_or_else(|| "Sub-workflow stopped without a result".to_string()));
+
+        let finished = repo
+            .get_run(&run.id)?
+            .ok_or_else(|| anyhow!("Sub-workflow run {} not found", run.id))?;
+        Ok(SubWorkflowOutcome {
+            run_id: run.id,
+            workflow_name: callee.name,
+            status: finished.status,
+            output: output_variables(finished.result.as_deref()),
+            result: finished.result,
+            error: finished.error,
+        })
+    }
+}
+
+/// Gives a child run a final status if its executor stops without one: the call returned
+/// early, or its future was dropped because the calling run stopped mid-step
+struct ChildRunGuard {
+    logger: RunLogger,
+    run_id: String,
+    /// Set once finish() ran, so dropping the guard changes nothing
+    finished: bool,
+}
+
+impl ChildRunGuard {
+    /// The executor returned - mark the run with `status` if it is still unfinished
+    fn finish(mut self, status: RunStatus, error: String) {
+        self.close(status, error);
+        self.finished = true;
+    }
+
+    fn close(&self, status: RunStatus, error: String) {
+        let unfinished = match self.logger.repository().get_run(&self.run_id) {
+            Ok(Some(run)) => !run.status.is_finished(),
+            _ => false,
+        };
+        if unfinished {
+            self.logger.status(&self.run_id, status, Some(error));
+        }
+    }
+}
+
+impl Drop for ChildRunGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.close(RunStatus::Cancelled, "The calling run stopped before the sub-workflow finished".to_string());
+    }
+}
+
+/// Refuse calls back into a workflow already on the call stack, and calls past MAX_WORKFLOW_DEPTH
+fn check_call(call_stack: &[String], depth: usize, workflow_id: &str, workflow_name: &str) -> Result<()> {
+    if call_stack.iter().any(|id| id == workflow_id) {
+        bail!("Workflow '{}' is already running further up this call chain - workflows can't call themselves", workflow_name);
+    }
+    if depth >= MAX_WORKFLOW_DEPTH {
+        bail!("Sub-workflows can only nest {} levels deep", MAX_WORKFLOW_DEPTH);
+    }
+    Ok(())
+}
+
+/// The callee's variables: its defaults, overridden by the caller's values
+fn call_variables(callee_variables: &[SavedVariable], variables: Map<String, Value>) -> HashMap<String, Value> {
+    let mut values: HashMap<String, Value> = callee_variables
+        .iter()
+        .filter_map(|v| v.default_value.clone().map(|value| (v.name.clone(), value)))
+        .collect();
+    values.extend(variables);
+    values
+}
+
+/// Values of the sensitive variables, as the redactor masks them
+fn secret_values(values: &HashMap<String, Value>, secret_variables: &[String]) -> Vec<String> {
+    secret_variables
+        .iter()
+        .filter_map(|name| match values.get(name)? {
+            Value::String(value) => Some(value.clone()),
+            Value::Null => None,
+            value => Some(value.to_string()),
+        })
+        .collect()
+}
+
+/// Fields of a JSON-object result, handed back to the caller's variables
+fn output_variables(result: Option<&str>) -> Map<String, Value> {
+    result.and_then(structured_result).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runs::RunRepository;
+
+    fn variable(name: &str, default: Option<Value>, sensitive: bool) -> SavedVariable {
+        SavedVariable {
+            name: name.to_string(),
+            var_type: "string".to_string(),
+            default_value: default,
+            sensitive,
+        }
+    }
+
+    #[test]
+    fn test_check_call_rejects_recursion_and_deep_nesting() {
+        let stack = vec!["wf-main".to_string(), "wf-login".to_string()];
+        let err = check_call(&stack, 1, "wf-login", "Login").unwrap_err();
+        assert!(err.to_string().contains("can't call themselves"), "{}", err);
+        assert!(check_call(&stack, 1, "wf-export", "Export").is_ok());
+
+        assert!(check_call(&[], MAX_WORKFLOW_DEPTH - 1, "wf-export", "Export").is_ok());
+        let err = check_call(&[], MAX_WORKFLOW_DEPTH, "wf-export", "Export").unwrap_err();
+        assert!(err.to_string().contains("3 levels"), "{}", err);
+    }
+
+    #[test]
+    fn test_caller_values_override_defaults() {
+        let callee = vec![
+            variable("account", Some(json!("default-co")), false),
+            variable("region", Some(json!("eu")), false),
+            variable("password", None, true),
+        ];
+        let caller = json!({ "account": "acme", "password": "hunter22" }).as_object().unwrap().clone();
+
+        let values = call_variables(&callee, caller);
+        assert_eq!(values["account"], "acme");
+        assert_eq!(values["region"], "eu");
+        assert_eq!(values["password"], "hunter22");
+
+        let secrets = secret_values(&values, &["password".to_string(), "missing".to_string()]);
+        assert_eq!(secrets, ["hunter22"]);
+    }
+
+    #[test]
+    fn test_output_variables_from_structured_result() {
+        let output = output_variables(Some("```json\n{\"invoice\": \"INV-7\", \"total\": 12.5}\n```"));
+        assert_eq!(output["invoice"], "INV-7");
+        assert_eq!(output["total"], 12.5);
+
+        assert!(output_variables(Some("Logged in as Acme")).is_empty());
+        assert!(output_variables(Some("[1, 2]")).is_empty());
+        assert!(output_variables(None).is_empty());
+    }
+
+    fn child_run(logger: &RunLogger, status: RunStatus) -> String {
+        let run = Run::new(None, Some("Login".to_string()), None, None);
+        logger.repository().create_run(&run).unwrap();
+        logger.status(&run.id, status, None);
+        run.id
+    }
+
+    fn status(logger: &RunLogger, run_id: &str) -> RunStatus {
+        logger.repository().get_run(run_id).unwrap().unwrap().status
+    }
+
+    #[tokio::test]
+    async fn test_dropped_call_cancels_child_run() {
+        let logger = RunLogger::new(RunRepository::in_memory().unwrap());
+        let run_id = child_run(&logger, RunStatus::Running);
+
+        // The caller's step is dropped while the sub-workflow is still running
+        let call = {
+            let guard = ChildRunGuard { logger: logger.clone(), run_id: run_id.clone(), finished: false };
+            async move {
+                let _guard = guard;
+                std::future::pending::<()>().await
+            }
+        };
+        let stopped = tokio::time::timeout(std::time::Duration::from_millis(10), call).await;
+        assert!(stopped.is_err());
+
+        let run = logger.repository().get_run(&run_id).unwrap().unwrap();
+        assert_eq!(run.status, RunStatus::Cancelled);
+        assert!(run.error.unwrap().contains("calling run stopped"));
+    }
+
+    #[test]
+    fn test_finished_call_keeps_final_status() {
+        let logger = RunLogger::new(RunRepository::in_memory().unwrap());
+
+        let completed = child_run(&logger, RunStatus::Completed);
+        ChildRunGuard { logger: logger.clone(), run_id: completed.clone(), finished: false }
+            .finish(RunStatus::Failed, "no result".to_string());
+        assert_eq!(status(&logger, &completed), RunStatus::Completed);
+
+        let stuck = child_run(&logger, RunStatus::WaitingForInput);
+        ChildRunGuard { logger: logger.clone(), run_id: stuck.clone(), finished: false }
+            .finish(RunStatus::Failed, "no result".to_string());
+        assert_eq!(status(&logger, &stuck), RunStatus::Failed);
+    }
+}
@@ -3,14 +3,16 @@ use async_trait::async_trait;
 use serde_json::{json, Value};
 
 use super::registry::{Tool, ToolContext, ToolDefinition, ToolResult};
-use crate::runs::RunFile;
+use crate::browser::{keyboard, pointer, BackendNodeId, DragTarget, PdfOptions};
+use crate::models::{InputTimeoutPolicy, JsPolicy};
+use crate::runs::{RunFile, Unanswered};
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
 /// Parse an integer parameter robustly - handles both integer and string values
-fn parse_int_param(params: &Value, name: &str) -> Option<i32> {
+pub(super) fn parse_int_param(params: &Value, name: &str) -> Option<i32> {
     // Try as integer first
     if let Some(i) = params[name].as_i64() {
         return Some(i as i32);
@@ -38,6 +40,55 @@ fn parse_int_param(params: &Value, name: &str) -> Option<i32> {
     None
 }
 
+/// Virtual path for a saved page: the requested one (with the extension added if missing),
+/// or a timestamped one under /output
+fn document_path(params: &Value, extension: &str) -> String {
+    match params["file_path"].as_str().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) if path.to_ascii_lowercase().ends_with(&format!(".{}", extension)) => path.to_string(),
+        Some(path) => format!("{}.{}", path, extension),
+        None => format!(
+            "/output/page-{}.{}",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S%3f"),
+            extension
+        ),
+    }
+}
+
+/// Store a saved page in the run's files
+pub(super) fn store_document(ctx: &ToolContext, path: String, content: Vec<u8>, mime_type: &str, kind: &str) -> ToolResult {
+    let repo = match &ctx.file_repository {
+        Some(r) => r,
+        None => return ToolResult::error("File storage not available"),
+    };
+
+    let mut file = RunFile::new(ctx.run_id.clone(), ctx.workflow_id.clone(), path.clone(), content);
+    file.mime_type = mime_type.to_string();
+    if !file.is_size_valid() {
+        return ToolResult::error(format!("{} is too large to store ({} bytes)", kind, file.file_size));
+    }
+
+    match repo.upsert_file(&file) {
+        Ok(()) => ToolResult::success_with_data(
+            format!("Saved {} to '{}' ({} bytes)", kind, path, file.file_size),
+            json!({ "file_path": path, "file_size": file.file_size }),
+        ),
+        Err(e) => ToolResult::error(format!("Failed to save {} to '{}': {}", kind, path, e)),
+    }
+}
+
+/// Look up an element's backend_node_id, or the error result to return for a bad index
+pub(super) async fn lookup_backend_id(ctx: &ToolContext, index: i32) -> std::result::Result<BackendNodeId, ToolResult> {
+    let selector_map = ctx.selector_map.read().await;
+    selector_map.get_backend_id(index).ok_or_else(|| {
+        let msg = if selector_map.is_empty() {
+            format!("Element index {} not found. No interactive elements on page.", index)
+        } else {
+            format!("Element index {} not found. Valid indices: 1-{}", index, selector_map.len())
+        };
+        ToolResult::error(msg)
+    })
+}
+
 // ============================================================================
 // Navigation Tools
 // ============================================================================
@@ -195,6 +246,10 @@ impl Tool for ClickTool {
                     "index": {
                         "type": "integer",
                         "description": "The 1-based index of the element to click (e.g., [1], [2], [3])"
+                    },
+                    "modifiers": {
+                        "type": "string",
+                        "description": "Optional modifier keys to hold while clicking, joined with '+': 'Control', 'Shift', 'Alt', 'Meta' (e.g. 'Control' to open a link in a new tab, 'Shift' to extend a selection)"
                     }
                 },
                 "required": ["index"]
@@ -225,7 +280,14 @@ impl Tool for ClickTool {
         };
         drop(selector_map); // Release read lock before async operations
 
-        match ctx.browser.click_by_backend_id(backend_id).await {
+        let modifiers_name = params["modifiers"].as_str().unwrap_or_default();
+        let modifiers = match keyboard::parse_modifiers(modifiers_name) {
+            Ok(m) => m,
+            Err(e) => return Ok(ToolResult::error(format!("{}. Use Control, Shift, Alt or Meta.", e))),
+        };
+
+        match ctx.browser.click_by_backend_id_with_modifiers(backend_id, modifiers).await {
+            Ok(()) if modifiers != 0 => Ok(ToolResult::success(format!("{}+Clicked element [{}]", modifiers_name, index))),
             Ok(()) => Ok(ToolResult::success(format!("Clicked element [{}]", index))),
             Err(e) => Ok(ToolResult::error(format!("Failed to click element [{}]: {}", index, e)))
         }
@@ -284,6 +346,205 @@ impl Tool for HoverTool {
     }
 }
 
+/// Double-click an element (open files, edit cells, select words)
+pub struct DoubleClickTool;
+
+#[async_trait]
+impl Tool for DoubleClickTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "double_click".to_string(),
+            description: "Double-click an element, e.g. to open an item, edit a table cell or select a word".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "index": {
+                        "type": "integer",
+                        "description": "The 1-based index of the element to double-click"
+                    }
+                },
+                "required": ["index"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let index = match parse_int_param(&params, "index") {
+            Some(i) => i,
+            None => return Ok(ToolResult::error(
+                "Missing 'index' parameter. Use the element number from the list, e.g. index: 5"
+            )),
+        };
+        let backend_id = match lookup_backend_id(ctx, index).await {
+            Ok(id) => id,
+            Err(result) => return Ok(result),
+        };
+
+        match ctx.browser.double_click_by_backend_id(backend_id).await {
+            Ok(()) => Ok(ToolResult::success(format!("Double-clicked element [{}]", index))),
+            Err(e) => Ok(ToolResult::error(format!("Failed to double-click element [{}]: {}", index, e)))
+        }
+    }
+}
+
+/// Right-click an element to open its context menu
+pub struct RightClickTool;
+
+#[async_trait]
+impl Tool for RightClickTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "right_click".to_string(),
+            description: "Right-click an element to open its context menu".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "index": {
+                        "type": "integer",
+                        "description": "The 1-based index of the element to right-click"
+                    }
+                },
+                "required": ["index"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let index = match parse_int_param(&params, "index") {
+            Some(i) => i,
+            None => return Ok(ToolResult::error(
+                "Missing 'index' parameter. Use the element number from the list, e.g. index: 5"
+            )),
+        };
+        let backend_id = match lookup_backend_id(ctx, index).await {
+            Ok(id) => id,
+            Err(result) => return Ok(result),
+        };
+
+        match ctx.browser.right_click_by_backend_id(backend_id).await {
+            Ok(()) => Ok(ToolResult::success(format!("Right-clicked element [{}]. Check the page for a context menu.", index))),
+            Err(e) => Ok(ToolResult::error(format!("Failed to right-click element [{}]: {}", index, e)))
+        }
+    }
+}
+
+/// Drag an element onto another element or a point
+pub struct DragAndDropTool;
+
+#[async_trait]
+impl Tool for DragAndDropTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "drag_and_drop".to_string(),
+            description: "Drag an element and drop it onto another element (to_index) or a viewport point (to_x, to_y). Works for kanban cards, sortable lists, sliders and file drop zones".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from_index": {
+                        "type": "integer",
+                        "description": "The 1-based index of the element to drag"
+                    },
+                    "to_index": {
+                        "type": "integer",
+                        "description": "The 1-based index of the element to drop onto"
+                    },
+                    "to_x": {
+                        "type": "number",
+                        "description": "Drop point X in CSS pixels from the viewport's left edge (instead of to_index)"
+                    },
+                    "to_y": {
+                        "type": "number",
+                        "description": "Drop point Y in CSS pixels from the viewport's top edge (instead of to_index)"
+                    }
+                },
+                "required": ["from_index"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let from_index = match parse_int_param(&params, "from_index") {
+            Some(i) => i,
+            None => return Ok(ToolResult::error(
+                "Missing 'from_index' parameter. Use the number of the element to drag, e.g. from_index: 5"
+            )),
+        };
+        let from = match lookup_backend_id(ctx, from_index).await {
+            Ok(id) => id,
+            Err(result) => return Ok(result),
+        };
+
+        let (target, target_desc) = match (parse_int_param(&params, "to_index"), params["to_x"].as_f64(), params["to_y"].as_f64()) {
+            (Some(to_index), _, _) => match lookup_backend_id(ctx, to_index).await {
+                Ok(id) => (DragTarget::Element(id), format!("element [{}]", to_index)),
+                Err(result) => return Ok(result),
+            },
+            (None, Some(x), Some(y)) => (DragTarget::Point { x, y }, format!("({}, {})", x, y)),
+            _ => return Ok(ToolResult::error(
+                "Provide either 'to_index' (element to drop onto) or both 'to_x' and 'to_y'"
+            )),
+        };
+
+        match ctx.browser.drag_and_drop(from, target).await {
+            Ok(()) => Ok(ToolResult::success(format!("Dragged element [{}] to {}", from_index, target_desc))),
+            Err(e) => Ok(ToolResult::error(format!("Failed to drag element [{}]: {}", from_index, e)))
+        }
+    }
+}
+
+/// Click at page coordinates (canvas, maps, charts)
+pub struct ClickAtTool;
+
+#[async_trait]
+impl Tool for ClickAtTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "click_at".to_string(),
+            description: "Click at a point in the viewport. Use only for canvas apps, maps, charts and other graphics that have no indexed element".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "x": {
+                        "type": "number",
+                        "description": "X in CSS pixels from the viewport's left edge (matches screenshot pixels at the default viewport)"
+                    },
+                    "y": {
+                        "type": "number",
+                        "description": "Y in CSS pixels from the viewport's top edge"
+                    },
+                    "button": {
+                        "type": "string",
+                        "enum": ["left", "right", "middle"],
+                        "description": "Mouse button (default: left)"
+                    },
+                    "click_count": {
+                        "type": "integer",
+                        "description": "2 for a double-click (default: 1)"
+                    }
+                },
+                "required": ["x", "y"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let (x, y) = match (params["x"].as_f64(), params["y"].as_f64()) {
+            (Some(x), Some(y)) if x >= 0.0 && y >= 0.0 => (x, y),
+            _ => return Ok(ToolResult::error("Missing or negative 'x'/'y' parameters")),
+        };
+        let button = match pointer::parse_button(params["button"].as_str().unwrap_or_default()) {
+            Ok(b) => b,
+            Err(e) => return Ok(ToolResult::error(format!("{}. Use left, right or middle.", e))),
+        };
+        let click_count = parse_int_param(&params, "click_count").unwrap_or(1).clamp(1, 3) as i64;
+
+        match ctx.browser.click_at(x, y, button, click_count).await {
+            Ok(()) => Ok(ToolResult::success(format!("Clicked at ({}, {})", x, y))),
+            Err(e) => Ok(ToolResult::error(format!("Failed to click at ({}, {}): {}", x, y, e)))
+        }
+    }
+}
+
 /// Input text into a field
 pub struct InputTextTool;
 
@@ -503,13 +764,13 @@ impl Tool for SendKeysTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "send_keys".to_string(),
-            description: "Send keyboard keys like Enter, Tab, Escape, arrow keys, etc.".to_string(),
+            description: "Send keyboard keys or shortcuts to the focused element, e.g. Enter, Tab, Escape, arrow keys, Control+A, Shift+Tab, Meta+Enter. Use input_text to type text.".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "keys": {
                         "type": "string",
-                        "description": "Key to send: 'Enter', 'Tab', 'Escape', 'Backspace', 'Delete', 'ArrowUp', 'ArrowDown', 'ArrowLeft', 'ArrowRight', 'Space', 'Home', 'End', 'PageUp', 'PageDown'"
+                        "description": "A key or chord, or several separated by spaces (pressed in order). Keys: 'Enter', 'Tab', 'Escape', 'Backspace', 'Delete', 'ArrowUp', 'ArrowDown', 'ArrowLeft', 'ArrowRight', 'Space', 'Home', 'End', 'PageUp', 'PageDown', 'F1'-'F12', or a single character; any other word (e.g. unicode like 'Grüße') is inserted as text. Chords join modifiers (Control, Shift, Alt, Meta) with '+', e.g. 'Control+A', 'Shift+Tab', 'Control+Shift+K'. Example sequence: 'Control+A Backspace'"
                     }
                 },
                 "required": ["keys"]
@@ -524,7 +785,7 @@ impl Tool for SendKeysTool {
 
         // Use CDP-based key press for reliable keyboard input
         match ctx.browser.press_key(keys).await {
-            Ok(()) => Ok(ToolResult::success(format!("Pressed key: {}", keys))),
+            Ok(()) => Ok(ToolResult::success(format!("Pressed keys: {}", keys))),
             Err(e) => Ok(ToolResult::error(format!("Failed to press key '{}': {}", keys, e)))
         }
     }
@@ -534,67 +795,100 @@ impl Tool for SendKeysTool {
 // Extraction Tools
 // ============================================================================
 
-/// Extract content from page
-pub struct ExtractTool;
+/// Take a screenshot
+pub struct ScreenshotTool;
 
 #[async_trait]
-impl Tool for ExtractTool {
+impl Tool for ScreenshotTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
-            name: "extract_content".to_string(),
-            description: "Extract and return specific content from the page based on a goal".to_string(),
+            name: "screenshot".to_string(),
+            description: "Take a screenshot of the current page state, or of a single element".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "goal": {
-                        "type": "string",
-                        "description": "What information to extract (e.g., 'product prices', 'article text', 'all links')"
+                    "full_page": {
+                        "type": "boolean",
+                        "description": "If true, captures the entire scrollable page. If false (default), captures only the visible viewport.",
+                        "default": false
+                    },
+                    "index": {
+                        "type": "integer",
+                        "description": "Element index to capture on its own (cropped to the element, scrolled into view first)"
                     }
                 },
-                "required": ["goal"]
+                "required": []
             }),
         }
     }
 
     async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
-        let goal = params["goal"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing 'goal' parameter"))?;
-
-        // Get page content for extraction
-        let html = ctx.browser.get_dom().await?;
+        let full_page = params["full_page"].as_bool().unwrap_or(false);
+        let element = params.get("index").filter(|v| !v.is_null()).and(parse_int_param(&params, "index"));
 
-        // For now, return the page content - the LLM can parse it
-        // In production, this could use more sophisticated extraction
-        let content_preview = if html.len() > 5000 {
-            format!("{}...\n[Content truncated. Total {} chars]", &html[..5000], html.len())
-        } else {
-            html
+        let (screenshot_base64, msg) = match element {
+            Some(index) => {
+                let backend_id = match lookup_backend_id(ctx, index).await {
+                    Ok(id) => id,
+                    Err(result) => return Ok(result),
+                };
+                match ctx.browser.screenshot_element(backend_id).await {
+                    Ok(screenshot) => (screenshot, format!("Screenshot of element [{}] captured successfully", index)),
+                    Err(e) => return Ok(ToolResult::error(format!("Failed to screenshot element [{}]: {}", index, e))),
+                }
+            }
+            None => {
+                let msg = if full_page {
+                    "Full page screenshot captured successfully"
+                } else {
+                    "Viewport screenshot captured successfully"
+                };
+                (ctx.browser.screenshot_with_options(full_page).await?, msg.to_string())
+            }
         };
 
         Ok(ToolResult::success_with_data(
-            format!("Extracted content for goal: '{}'. Review the data field for details.", goal),
-            json!({ "goal": goal, "content": content_preview })
+            msg,
+            json!({
+                "screenshot": screenshot_base64,
+                "mime_type": ctx.browser.screenshot_format().await.mime_type()
+            })
         ))
     }
 }
 
-/// Take a screenshot
-pub struct ScreenshotTool;
+/// Save the page as a PDF in the run's files
+pub struct SavePdfTool;
 
 #[async_trait]
-impl Tool for ScreenshotTool {
+impl Tool for SavePdfTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
-            name: "screenshot".to_string(),
-            description: "Take a screenshot of the current page state".to_string(),
+            name: "save_pdf".to_string(),
+            description: "Print the current page to a PDF and store it in the run's files, e.g. as evidence of a submitted form".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "full_page": {
+                    "file_path": {
+                        "type": "string",
+                        "description": "Virtual path to save to (e.g., '/evidence/confirmation.pdf'). Defaults to a timestamped file in /output"
+                    },
+                    "paper": {
+                        "type": "string",
+                        "enum": ["letter", "legal", "tabloid", "a3", "a4", "a5"],
+                        "description": "Paper size (default letter)"
+                    },
+                    "landscape": {
                         "type": "boolean",
-                        "description": "If true, captures the entire scrollable page. If false (default), captures only the visible viewport.",
-                        "default": false
+                        "description": "Landscape orientation (default false)"
+                    },
+                    "print_background": {
+                        "type": "boolean",
+                        "description": "Include background colors and images (default true)"
+                    },
+                    "page_ranges": {
+                        "type": "string",
+                        "description": "Pages to include, e.g. '1-3, 5' (default all)"
                     }
                 },
                 "required": []
@@ -603,19 +897,50 @@ impl Tool for ScreenshotTool {
     }
 
     async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
-        let full_page = params["full_page"].as_bool().unwrap_or(false);
-        let screenshot_base64 = ctx.browser.screenshot_with_options(full_page).await?;
+        let options = PdfOptions {
+            paper: params["paper"].as_str().map(String::from),
+            landscape: params["landscape"].as_bool().unwrap_or(false),
+            print_background: params["print_background"].as_bool().unwrap_or(true),
+            page_ranges: params["page_ranges"].as_str().map(String::from),
+        };
+        let pdf = match ctx.browser.print_pdf(&options).await {
+            Ok(pdf) => pdf,
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
+        };
 
-        let msg = if full_page {
-            "Full page screenshot captured successfully"
-        } else {
-            "Viewport screenshot captured successfully"
+        Ok(store_document(ctx, document_path(&params, "pdf"), pdf, "application/pdf", "PDF"))
+    }
+}
+
+/// Save the page as an MHTML archive in the run's files
+pub struct SaveMhtmlTool;
+
+#[async_trait]
+impl Tool for SaveMhtmlTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "save_mhtml".to_string(),
+            description: "Save the current page with its images and styles as a single MHTML archive in the run's files".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "file_path": {
+                        "type": "string",
+                        "description": "Virtual path to save to (e.g., '/evidence/receipt.mhtml'). Defaults to a timestamped file in /output"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let mhtml = match ctx.browser.capture_mhtml().await {
+            Ok(mhtml) => mhtml,
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
         };
 
-        Ok(ToolResult::success_with_data(
-            msg.to_string(),
-            json!({ "screenshot": screenshot_base64 })
-        ))
+        Ok(store_document(ctx, document_path(&params, "mhtml"), mhtml, "multipart/related", "MHTML archive"))
     }
 }
 
@@ -1053,11 +1378,68 @@ impl Tool for ListTabsTool {
     }
 }
 
+// ============================================================================
+// Dialog Tool
+// ============================================================================
+
+/// Accept or dismiss a native alert/confirm/prompt dialog
+pub struct HandleDialogTool;
+
+#[async_trait]
+impl Tool for HandleDialogTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "handle_dialog".to_string(),
+            description: "Accept or dismiss the open JavaScript dialog (alert, confirm, prompt, or leave-page warning). The page is frozen until the dialog is answered.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "accept": {
+                        "type": "boolean",
+                        "description": "true to click OK/Leave, false to click Cancel"
+                    },
+                    "prompt_text": {
+                        "type": "string",
+                        "description": "Text to enter before accepting a prompt() dialog"
+                    }
+                },
+                "required": ["accept"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let accept = match &params["accept"] {
+            Value::Bool(b) => *b,
+            Value::String(s) => s.eq_ignore_ascii_case("true"),
+            _ => return Ok(ToolResult::error("Missing 'accept' parameter (true or false)")),
+        };
+        let prompt_text = params["prompt_text"].as_str().map(|s| s.to_string());
+
+        match ctx.browser.dialogs().handle(accept, prompt_text).await {
+            Ok(dialog) => Ok(ToolResult::success(format!(
+                "{} {} dialog: \"{}\"",
+                if accept { "Accepted" } else { "Dismissed" },
+                dialog.dialog_type,
+                dialog.message
+            ))),
+            Err(e) => Ok(ToolResult::error(format!("Failed to handle dialog: {}", e))),
+        }
+    }
+}
+
 // ============================================================================
 // JavaScript Tool
 // ============================================================================
 
-/// Execute custom JavaScript
+/// Default and maximum time a script may run, in milliseconds
+const SCRIPT_TIMEOUT_MS: i32 = 5_000;
+const MAX_SCRIPT_TIMEOUT_MS: i32 = 30_000;
+
+/// Longest script result (as JSON) returned to the model
+const MAX_SCRIPT_RESULT_BYTES: usize = 16_000;
+
+/// Execute custom JavaScript, as far as the workflow's JavaScript policy allows
 pub struct EvaluateJsTool;
 
 #[async_trait]
@@ -1065,13 +1447,17 @@ impl Tool for EvaluateJsTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "execute_javascript".to_string(),
-            description: "Execute custom JavaScript code in the browser context".to_string(),
+            description: "Execute custom JavaScript in the page and return its value. An expression or function literal returns its value; statements need `return`. Some workflows only allow read-only scripts (no DOM changes, clicks, navigation, network, cookies or storage).".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "script": {
                         "type": "string",
                         "description": "The JavaScript code to execute"
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "Time limit in milliseconds (default 5000, max 30000)"
                     }
                 },
                 "required": ["script"]
@@ -1083,17 +1469,62 @@ impl Tool for EvaluateJsTool {
         let script = params["script"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing 'script' parameter"))?;
+        let read_only = match ctx.js_policy {
+            JsPolicy::Disabled => {
+                return Ok(ToolResult::error("JavaScript execution is disabled for this workflow"));
+            }
+            JsPolicy::ReadOnly => true,
+            JsPolicy::Full => false,
+        };
+        let timeout_ms = parse_int_param(&params, "timeout_ms")
+            .unwrap_or(SCRIPT_TIMEOUT_MS)
+            .clamp(1, MAX_SCRIPT_TIMEOUT_MS);
+
+        let result = match ctx
+            .browser
+            .run_script(script, read_only, std::time::Duration::from_millis(timeout_ms as u64))
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => return Ok(ToolResult::error(format!("JavaScript error: {}", e))),
+        };
 
-        match ctx.browser.evaluate(script).await {
-            Ok(result) => Ok(ToolResult::success_with_data(
-                "JavaScript executed successfully".to_string(),
-                json!({ "result": result })
-            )),
-            Err(e) => Ok(ToolResult::error(format!("JavaScript error: {}", e)))
-        }
+        let (result, truncated, result_bytes) = cap_script_result(result);
+
+        Ok(ToolResult::success_with_data(
+            if truncated {
+                format!(
+                    "JavaScript executed successfully (result truncated to {} of {} bytes - return less data)",
+                    MAX_SCRIPT_RESULT_BYTES, result_bytes
+                )
+            } else {
+                "JavaScript executed successfully".to_string()
+            },
+            json!({
+                "result": result,
+                "truncated": truncated,
+                "result_bytes": result_bytes,
+                "policy": ctx.js_policy.as_str(),
+            }),
+        ))
     }
 }
 
+/// Result to return to the model: values over MAX_SCRIPT_RESULT_BYTES of JSON become
+/// their JSON text cut at that size. Also returns whether it was cut and the full size.
+fn cap_script_result(result: Value) -> (Value, bool, usize) {
+    let text = result.to_string();
+    let result_bytes = text.len();
+    if result_bytes <= MAX_SCRIPT_RESULT_BYTES {
+        return (result, false, result_bytes);
+    }
+    let mut cut = MAX_SCRIPT_RESULT_BYTES;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    (json!(text[..cut]), true, result_bytes)
+}
+
 // ============================================================================
 // File Tools (Database-backed storage for run files)
 // ============================================================================
@@ -1374,6 +1805,100 @@ impl Tool for ReplaceInFileTool {
     }
 }
 
+// ============================================================================
+// Human Input Tool
+// ============================================================================
+
+/// Pause the run and ask the person running it (CAPTCHAs, MFA codes, ambiguous choices)
+pub struct AskHumanTool;
+
+#[async_trait]
+impl Tool for AskHumanTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "ask_human".to_string(),
+            description: "Ask the person running this task and wait for the answer. Use for CAPTCHAs, MFA/verification codes, or choices you cannot make from the task. With take_over=true the person completes the step in the browser window themselves and hands it back.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "question": {
+                        "type": "string",
+                        "description": "What you need, e.g. 'Enter the 6-digit code sent to your phone'"
+                    },
+                    "options": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Suggested answers to choose from (optional)"
+                    },
+                    "take_over": {
+                        "type": "boolean",
+                        "description": "Let the person use the browser (e.g. to solve a CAPTCHA) instead of typing an answer",
+                        "default": false
+                    }
+                },
+                "required": ["question"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let question = match params["question"].as_str().map(str::trim) {
+            Some(q) if !q.is_empty() => q,
+            _ => return Ok(ToolResult::error("Missing 'question' parameter")),
+        };
+        let options: Vec<String> = params["options"]
+            .as_array()
+            .map(|items| items.iter().filter_map(|o| o.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let take_over = params["take_over"].as_bool().unwrap_or(false);
+
+        let Some(human) = &ctx.human else {
+            return Ok(ToolResult::error("Nobody is available to answer in this run - continue on your own"));
+        };
+        if take_over && !human.headed {
+            return Ok(ToolResult::error(
+                "The browser is headless, so nobody can take it over - ask for an answer instead",
+            ));
+        }
+
+        match human.ask(question, options, take_over).await {
+            Ok(answer) if take_over => {
+                let note = if answer.trim().is_empty() {
+                    String::new()
+                } else {
+                    format!(" Their note: {}", answer.trim())
+                };
+                Ok(ToolResult::success(format!(
+                    "The person handed the browser back. Check the current page state before continuing.{}",
+                    note
+                )))
+            }
+            Ok(answer) => Ok(ToolResult::success_with_data(
+                format!("The person answered: {}", answer),
+                json!({ "answer": answer }),
+            )),
+            Err(Unanswered::Cancelled) => Ok(ToolResult::error("The run was stopped while waiting for an answer")),
+            Err(Unanswered::TimedOut) => {
+                let message = format!(
+                    "Nobody answered \"{}\" within {}s",
+                    question,
+                    human.timeout().as_secs()
+                );
+                match human.on_timeout {
+                    InputTimeoutPolicy::Fail => Ok(ToolResult {
+                        is_done: true,
+                        ..ToolResult::error(message)
+                    }),
+                    InputTimeoutPolicy::Continue => Ok(ToolResult::error(format!(
+                        "{} - continue without it or finish with done(success=false)",
+                        message
+                    ))),
+                }
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Completion Tool
 // ============================================================================
@@ -1433,6 +1958,10 @@ pub fn register_all_tools(registry: &mut ToolRegistry) {
     // Interaction
     registry.register(Arc::new(ClickTool));
     registry.register(Arc::new(HoverTool));
+    registry.register(Arc::new(DoubleClickTool));
+    registry.register(Arc::new(RightClickTool));
+    registry.register(Arc::new(DragAndDropTool));
+    registry.register(Arc::new(ClickAtTool));
     registry.register(Arc::new(InputTextTool));
     registry.register(Arc::new(ClearInputTool));
     registry.register(Arc::new(ScrollTool));
@@ -1440,8 +1969,12 @@ pub fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(SendKeysTool));
 
     // Extraction
+    use super::extract_tools::{ExtractTableTool, ExtractTool};
     registry.register(Arc::new(ExtractTool));
+    registry.register(Arc::new(ExtractTableTool));
     registry.register(Arc::new(ScreenshotTool));
+    registry.register(Arc::new(SavePdfTool));
+    registry.register(Arc::new(SaveMhtmlTool));
     registry.register(Arc::new(FindTextTool));
 
     // Forms
@@ -1455,9 +1988,17 @@ pub fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(CloseTabTool));
     registry.register(Arc::new(ListTabsTool));
 
+    // Dialogs
+    registry.register(Arc::new(HandleDialogTool));
+
     // JavaScript
     registry.register(Arc::new(EvaluateJsTool));
 
+    // Network
+    use super::network_tools::{GetResponseBodyTool, WaitForRequestTool};
+    registry.register(Arc::new(WaitForRequestTool));
+    registry.register(Arc::new(GetResponseBodyTool));
+
     // Files
     registry.register(Arc::new(ListFilesTool));
     registry.register(Arc::new(ReadFileTool));
@@ -1470,6 +2011,37 @@ pub fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(RecallMemoriesTool));
     registry.register(Arc::new(DeleteMemoryTool));
 
+    // Human input
+    registry.register(Arc::new(AskHumanTool));
+
+    // Sub-workflows
+    use super::workflow_tools::RunWorkflowTool;
+    registry.register(Arc::new(RunWorkflowTool));
+
+    // Plan tracking
+    use super::plan_tools::UpdatePlanTool;
+    registry.register(Arc::new(UpdatePlanTool));
+
     // Completion
     registry.register(Arc::new(DoneTool));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_script_result() {
+        let small = json!({ "title": "Inbox", "unread": 3 });
+        assert_eq!(cap_script_result(small.clone()), (small, false, 28));
+
+        // Cut on a character boundary, never inside a multi-byte character
+        let big = json!("é".repeat(MAX_SCRIPT_RESULT_BYTES));
+        let (capped, truncated, bytes) = cap_script_result(big);
+        assert!(truncated);
+        assert_eq!(bytes, MAX_SCRIPT_RESULT_BYTES * 2 + 2);
+        let text = capped.as_str().unwrap();
+        assert!(text.len() <= MAX_SCRIPT_RESULT_BYTES && text.len() > MAX_SCRIPT_RESULT_BYTES - 2);
+        assert!(text.starts_with("\"é"));
+    }
+}
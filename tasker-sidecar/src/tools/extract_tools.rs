@@ -0,0 +1,358 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+use super::browser_tools::{lookup_backend_id, parse_int_param, store_document};
+use super::registry::{Tool, ToolContext, ToolDefinition, ToolResult};
+use crate::browser::markdown::chunk_markdown;
+use crate::browser::table::{ExtractedTable, TableTarget};
+use crate::llm::prompts::EXTRACTION_PROMPT;
+
+/// Characters of markdown returned to the model per page
+const CHUNK_CHARS: usize = 12_000;
+
+/// Characters of markdown sent to the structured extraction pass per page
+const LLM_CHUNK_CHARS: usize = 60_000;
+
+/// Most pages extract_table will follow "next" links through
+const MAX_TABLE_PAGES: i32 = 20;
+
+/// How long to wait for the next page's rows after clicking "next"
+const NEXT_PAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Rows included in the tool result as a preview
+const PREVIEW_ROWS: usize = 5;
+
+// ============================================================================
+// Extract Content Tool
+// ============================================================================
+
+pub struct ExtractTool;
+
+#[async_trait]
+impl Tool for ExtractTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "extract_content".to_string(),
+            description: "Read the page (or one element) as clean markdown with links preserved, split into pages for long content. With a JSON schema, returns structured data for the goal instead.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "goal": {
+                        "type": "string",
+                        "description": "What information to extract (e.g., 'product prices', 'article text', 'all links')"
+                    },
+                    "index": {
+                        "type": "integer",
+                        "description": "Only extract the content of this element (e.g. a results list or article)"
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Part of long content to return, starting at 1 (default 1)"
+                    },
+                    "schema": {
+                        "type": "object",
+                        "description": "JSON schema of the data to return. When set, the content is read by a model and only matching JSON is returned."
+                    },
+                    "include_boilerplate": {
+                        "type": "boolean",
+                        "description": "Keep navigation, headers, footers, sidebars and banners (default false)"
+                    }
+                },
+                "required": ["goal"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let goal = params["goal"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Missing 'goal' parameter"))?;
+        let page = parse_int_param(&params, "page").unwrap_or(1).max(1) as usize;
+        let schema = params.get("schema").filter(|s| !s.is_null());
+
+        let root = match params.get("index").filter(|v| !v.is_null()).and(parse_int_param(&params, "index")) {
+            Some(index) => match lookup_backend_id(ctx, index).await {
+                Ok(id) => Some(id),
+                Err(result) => return Ok(result),
+            },
+            None => None,
+        };
+        // A chosen element is taken as-is
+        let drop_boilerplate = root.is_none() && !params["include_boilerplate"].as_bool().unwrap_or(false);
+
+        let markdown = ctx.browser.page_markdown(root, drop_boilerplate).await?;
+        if markdown.is_empty() {
+            return Ok(ToolResult::error(
+                "No readable text found. The page may still be loading, or its content is drawn in a canvas or iframe.",
+            ));
+        }
+        let url = ctx.browser.current_url().await.unwrap_or_default();
+
+        let chunks = chunk_markdown(&markdown, if schema.is_some() { LLM_CHUNK_CHARS } else { CHUNK_CHARS });
+        let total_pages = chunks.len();
+        let Some(chunk) = chunks.get(page - 1) else {
+            return Ok(ToolResult::error(format!(
+                "Page {} does not exist - the content has {} page(s)",
+                page, total_pages
+            )));
+        };
+        let next_hint = if page < total_pages {
+            format!(" Call extract_content with page: {} to continue.", page + 1)
+        } else {
+            String::new()
+        };
+
+        let Some(schema) = schema else {
+            return Ok(ToolResult::success_with_data(
+                format!(
+                    "Content for '{}' as markdown (page {}/{}, {} chars).{}",
+                    goal,
+                    page,
+                    total_pages,
+                    chunk.chars().count(),
+                    next_hint
+                ),
+                json!({
+                    "goal": goal,
+                    "url": url,
+                    "page": page,
+                    "total_pages": total_pages,
+                    "content": chunk,
+                }),
+            ));
+        };
+
+        let Some(llm) = &ctx.llm else {
+            return Ok(ToolResult::error("Structured extraction needs a model, and none is available in this run"));
+        };
+        let schema_text = match schema {
+            Value::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        let system = EXTRACTION_PROMPT.replace("{schema}", &schema_text);
+        let user = format!("Goal: {}\nPage: {}\n\n<content>\n{}\n</content>", goal, url, chunk);
+
+        let reply = llm.complete(&system, &user).await?;
+        match parse_json_reply(&reply) {
+            Ok(data) => Ok(ToolResult::success_with_data(
+                format!("Extracted '{}' (page {}/{}).{}", goal, page, total_pages, next_hint),
+                json!({
+                    "goal": goal,
+                    "url": url,
+                    "page": page,
+                    "total_pages": total_pages,
+                    "data": data,
+                }),
+            )),
+            Err(e) => Ok(ToolResult::error(format!("Extraction did not return valid JSON: {}", e))),
+        }
+    }
+}
+
+// ============================================================================
+// Extract Table Tool
+// ============================================================================
+
+pub struct ExtractTableTool;
+
+#[async_trait]
+impl Tool for ExtractTableTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "extract_table".to_string(),
+            description: "Extract a table, data grid or repeated list of cards as rows and save it to the run's files as CSV and/or JSON. Can follow 'next page' links and merge the rows.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "index": {
+                        "type": "integer",
+                        "description": "Element index of the table or list (default: the largest one on the page)"
+                    },
+                    "css": {
+                        "type": "string",
+                        "description": "CSS selector of the table or list, instead of an index"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["csv", "json", "both"],
+                        "description": "Output format (default csv)"
+                    },
+                    "file_path": {
+                        "type": "string",
+                        "description": "Where to save the rows (default /output/table-<timestamp>)"
+                    },
+                    "max_pages": {
+                        "type": "integer",
+                        "description": "Follow 'next page' links and merge up to this many pages (default 1, max 20)"
+                    },
+                    "next_css": {
+                        "type": "string",
+                        "description": "CSS selector of the 'next page' control, when it isn't found automatically"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let format = params["format"].as_str().unwrap_or("csv").to_ascii_lowercase();
+        if !matches!(format.as_str(), "csv" | "json" | "both") {
+            return Ok(ToolResult::error(format!("Unknown format '{}' - use csv, json or both", format)));
+        }
+        let max_pages = parse_int_param(&params, "max_pages").unwrap_or(1).clamp(1, MAX_TABLE_PAGES);
+        let next_css = params["next_css"].as_str().map(str::trim).filter(|s| !s.is_empty());
+        let css = params["css"].as_str().map(str::trim).filter(|s| !s.is_empty());
+
+        let target = match (params.get("index").filter(|v| !v.is_null()).and(parse_int_param(&params, "index")), css) {
+            (Some(index), _) => match lookup_backend_id(ctx, index).await {
+                Ok(id) => TableTarget::Element(id),
+                Err(result) => return Ok(result),
+            },
+            (None, Some(css)) => TableTarget::Css(css.to_string()),
+            (None, None) => TableTarget::Auto,
+        };
+
+        let Some(mut table) = ctx.browser.extract_table(&target).await? else {
+            return Ok(ToolResult::error(
+                "No table, grid or repeated list found. Pass the index or css of the element that holds the rows.",
+            ));
+        };
+        if table.rows.is_empty() {
+            return Ok(ToolResult::error("The table has no rows yet - the page may still be loading"));
+        }
+
+        // Later pages are read from the same container, found again by its selector
+        let follow = table.selector.clone().map(TableTarget::Css).unwrap_or(target);
+        let mut pages = 1;
+        let mut last_rows = table.rows.clone();
+        while pages < max_pages {
+            if !ctx.browser.click_next_page(next_css).await? {
+                break;
+            }
+            let Some(next) = wait_for_new_rows(ctx, &follow, &last_rows).await else {
+                break;
+            };
+            last_rows = next.rows.clone();
+            table.append(next);
+            pages += 1;
+        }
+
+        let base = table_path(&params);
+        let mut files = Vec::new();
+        if format != "json" {
+            let result = store_document(ctx, format!("{}.csv", base), table.to_csv().into_bytes(), "text/csv", "table");
+            if !result.success {
+                return Ok(result);
+            }
+            files.push(format!("{}.csv", base));
+        }
+        if format != "csv" {
+            let content = serde_json::to_vec_pretty(&table.to_json())?;
+            let result = store_document(ctx, format!("{}.json", base), content, "application/json", "table");
+            if !result.success {
+                return Ok(result);
+            }
+            files.push(format!("{}.json", base));
+        }
+
+        let columns = table.columns();
+        let preview: Vec<Value> = table.to_json().as_array().into_iter().flatten().take(PREVIEW_ROWS).cloned().collect();
+        Ok(ToolResult::success_with_data(
+            format!(
+                "Extracted {} rows x {} columns from a {} ({} page{}) to {}",
+                table.rows.len(),
+                columns.len(),
+                table.kind,
+                pages,
+                if pages == 1 { "" } else { "s" },
+                files.join(" and ")
+            ),
+            json!({
+                "kind": table.kind,
+                "columns": columns,
+                "row_count": table.rows.len(),
+                "pages": pages,
+                "files": files,
+                "selector": table.selector,
+                "preview": preview,
+            }),
+        ))
+    }
+}
+
+/// Poll until the table shows rows different from the previous page's
+async fn wait_for_new_rows(ctx: &ToolContext, target: &TableTarget, previous: &[Vec<String>]) -> Option<ExtractedTable> {
+    let deadline = Instant::now() + NEXT_PAGE_TIMEOUT;
+    while Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        // Errors are expected while the next page navigates
+        if let Ok(Some(table)) = ctx.browser.extract_table(target).await {
+            if !table.rows.is_empty() && table.rows != previous {
+                return Some(table);
+            }
+        }
+    }
+    None
+}
+
+/// Output path without extension
+fn table_path(params: &Value) -> String {
+    match params["file_path"].as_str().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => {
+            let lower = path.to_ascii_lowercase();
+            if lower.ends_with(".csv") || lower.ends_with(".json") {
+                path[..path.rfind('.').unwrap_or(path.len())].to_string()
+            } else {
+                path.to_string()
+            }
+        }
+        None => format!("/output/table-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S%3f")),
+    }
+}
+
+/// Parse a model reply as JSON, tolerating code fences and text around the value
+fn parse_json_reply(reply: &str) -> Result<Value> {
+    let text = reply.trim();
+    let text = text
+        .strip_prefix("```json")
+        .or_else(|| text.strip_prefix("```"))
+        .and_then(|t| t.strip_suffix("```"))
+        .unwrap_or(text)
+        .trim();
+
+    if let Ok(value) = serde_json::from_str(text) {
+        return Ok(value);
+    }
+
+    let start = text.find(['{', '[']).ok_or_else(|| anyhow!("no JSON value in reply"))?;
+    let end = text.rfind(['}', ']']).filter(|&end| end > start).ok_or_else(|| anyhow!("no JSON value in reply"))?;
+    serde_json::from_str(&text[start..=end]).map_err(|e| anyhow!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_reply() {
+        assert_eq!(parse_json_reply(r#"{"price": "$10"}"#).unwrap(), json!({ "price": "$10" }));
+        assert_eq!(
+            parse_json_reply("```json\n[{\"name\": \"Pro\"}]\n```").unwrap(),
+            json!([{ "name": "Pro" }])
+        );
+        assert_eq!(
+            parse_json_reply("Here is the data: {\"count\": 3}. Let me know!").unwrap(),
+            json!({ "count": 3 })
+        );
+        assert!(parse_json_reply("No prices on this page.").is_err());
+    }
+
+    #[test]
+    fn test_table_path() {
+        assert_eq!(table_path(&json!({ "file_path": "/output/prices.csv" })), "/output/prices");
+        assert_eq!(table_path(&json!({ "file_path": "/output/prices" })), "/output/prices");
+        assert!(table_path(&json!({})).starts_with("/output/table-"));
+    }
+}
@@ -107,7 +107,7 @@ impl Tool for RecallMemoriesTool {
 
         // If specific key requested, get that one memory
         if let Some(key) = params["key"].as_str() {
-            return match memories.iter().find(|m| m.key.as_ref().map(|k| k.as_str()) == Some(key)) {
+            return match memories.iter().find(|m| m.key.as_deref() == Some(key)) {
                 Some(memory) => Ok(ToolResult::success_with_data(
                     format!("Found memory [{}]: {}", key, truncate(&memory.content, 200)),
                     json!({
@@ -128,7 +128,7 @@ impl Tool for RecallMemoriesTool {
             .iter()
             .filter(|m| {
                 if let Some(cat) = category_filter {
-                    m.category.as_ref().map(|c| c.as_str()) == Some(cat)
+                    m.category.as_deref() == Some(cat)
                 } else {
                     true
                 }
@@ -190,7 +190,7 @@ impl Tool for DeleteMemoryTool {
         let mut memories = ctx.memories.write().await;
         let initial_len = memories.len();
 
-        memories.retain(|m| m.key.as_ref().map(|k| k.as_str()) != Some(key));
+        memories.retain(|m| m.key.as_deref() != Some(key));
 
         if memories.len() < initial_len {
             Ok(ToolResult::success(format!("Deleted memory [{}]", key)))
@@ -1,9 +1,16 @@
 // Tools module - tool registry and implementations for AI agent automation
 
 pub mod browser_tools;
+pub mod extract_tools;
 pub mod memory_tools;
+pub mod network_tools;
+pub mod plan_tools;
 pub mod registry;
+pub mod workflow_tools;
 
 pub use browser_tools::register_all_tools;
 pub use memory_tools::{DeleteMemoryTool, RecallMemoriesTool, SaveMemoryTool};
+pub use network_tools::{GetResponseBodyTool, WaitForRequestTool};
+pub use plan_tools::UpdatePlanTool;
 pub use registry::*;
+pub use workflow_tools::RunWorkflowTool;
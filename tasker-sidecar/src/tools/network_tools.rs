@@ -0,0 +1,217 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::registry::{Tool, ToolContext, ToolDefinition, ToolResult};
+use crate::browser::NetworkEntry;
+
+/// Default / maximum time to wait for a matching request
+const DEFAULT_WAIT_MS: u64 = 10_000;
+const MAX_WAIT_MS: u64 = 60_000;
+
+/// Default number of body characters returned to the model
+const DEFAULT_MAX_CHARS: usize = 20_000;
+
+// ============================================================================
+// Wait For Request Tool
+// ============================================================================
+
+pub struct WaitForRequestTool;
+
+#[async_trait]
+impl Tool for WaitForRequestTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "wait_for_request".to_string(),
+            description: "Wait until a network request (XHR/fetch/document) whose URL matches the pattern has completed. Returns its URL, method and status. Use after an action that triggers an API call, then read the data with get_response_body.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url_pattern": {
+                        "type": "string",
+                        "description": "Substring of the request URL, or a pattern with * wildcards (e.g. '/api/search', '*/graphql*')"
+                    },
+                    "method": {
+                        "type": "string",
+                        "description": "Optional HTTP method filter (e.g. 'GET', 'POST')"
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "Maximum time to wait in milliseconds (default 10000, max 60000)"
+                    },
+                    "new_only": {
+                        "type": "boolean",
+                        "description": "Ignore requests that already completed before this call (default false)"
+                    }
+                },
+                "required": ["url_pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let url_pattern = params["url_pattern"]
+            .as_str()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url_pattern' parameter"))?;
+        let method = params["method"].as_str();
+        let timeout_ms = params["timeout_ms"]
+            .as_u64()
+            .unwrap_or(DEFAULT_WAIT_MS)
+            .min(MAX_WAIT_MS);
+        let new_only = params["new_only"].as_bool().unwrap_or(false);
+
+        let network = ctx.browser.network();
+        // The agent is about to read an API response - keep bodies from now on
+        network.enable_body_capture();
+        let min_seq = if new_only { network.next_seq() } else { 0 };
+
+        match network
+            .wait_for(url_pattern, method, min_seq, Duration::from_millis(timeout_ms))
+            .await
+        {
+            Some(entry) => Ok(ToolResult::success_with_data(
+                format!(
+                    "Request completed: {} {} -> {}",
+                    entry.method,
+                    entry.url,
+                    status_label(&entry)
+                ),
+                entry_summary(&entry),
+            )),
+            None => Ok(ToolResult::error(format!(
+                "No request matching '{}' completed within {}ms",
+                url_pattern, timeout_ms
+            ))),
+        }
+    }
+}
+
+// ============================================================================
+// Get Response Body Tool
+// ============================================================================
+
+pub struct GetResponseBodyTool;
+
+#[async_trait]
+impl Tool for GetResponseBodyTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "get_response_body".to_string(),
+            description: "Get the response body of the most recent completed network request whose URL matches the pattern. JSON responses are returned as-is - prefer this over scraping the page when the site loads its data from an API.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url_pattern": {
+                        "type": "string",
+                        "description": "Substring of the request URL, or a pattern with * wildcards"
+                    },
+                    "method": {
+                        "type": "string",
+                        "description": "Optional HTTP method filter (e.g. 'GET', 'POST')"
+                    },
+                    "max_chars": {
+                        "type": "integer",
+                        "description": "Maximum characters of the body to return (default 20000)"
+                    }
+                },
+                "required": ["url_pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let url_pattern = params["url_pattern"]
+            .as_str()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url_pattern' parameter"))?;
+        let method = params["method"].as_str();
+        let max_chars = params["max_chars"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_CHARS);
+
+        ctx.browser.network().enable_body_capture();
+        let entry = match ctx.browser.network().find_latest(url_pattern, method, 0) {
+            Some(entry) => entry,
+            None => {
+                return Ok(ToolResult::error(format!(
+                    "No completed request matching '{}'. Use wait_for_request first if the page is still loading.",
+                    url_pattern
+                )))
+            }
+        };
+
+        let body = match entry.body_text() {
+            Some(body) => body,
+            None => {
+                return Ok(ToolResult::error(format!(
+                    "Response body for {} is not available (binary, too large, dropped to save memory, finished before the first wait_for_request, or request failed: {})",
+                    entry.url,
+                    status_label(&entry)
+                )))
+            }
+        };
+
+        let total_chars = body.chars().count();
+        let truncated = total_chars > max_chars;
+        let text: String = if truncated {
+            body.chars().take(max_chars).collect()
+        } else {
+            body.clone()
+        };
+
+        let mut data = entry_summary(&entry);
+        data["truncated"] = json!(truncated);
+        data["total_chars"] = json!(total_chars);
+        // Parsed JSON is handy for deterministic extraction, but only when complete
+        if !truncated && entry.mime_type.contains("json") {
+            if let Ok(parsed) = serde_json::from_str::<Value>(&body) {
+                data["json"] = parsed;
+            }
+        }
+
+        let suffix = if truncated {
+            format!("\n... truncated ({} of {} chars shown)", max_chars, total_chars)
+        } else {
+            String::new()
+        };
+
+        Ok(ToolResult::success_with_data(
+            format!(
+                "Response from {} {} ({}, {}):\n{}{}",
+                entry.method,
+                entry.url,
+                status_label(&entry),
+                entry.mime_type,
+                text,
+                suffix
+            ),
+            data,
+        ))
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn status_label(entry: &NetworkEntry) -> String {
+    match (&entry.error, entry.status) {
+        (Some(error), _) => format!("failed: {}", error),
+        (None, Some(status)) => format!("{} {}", status, entry.status_text).trim().to_string(),
+        (None, None) => "no response".to_string(),
+    }
+}
+
+fn entry_summary(entry: &NetworkEntry) -> Value {
+    json!({
+        "url": entry.url,
+        "method": entry.method,
+        "status": entry.status,
+        "mime_type": entry.mime_type,
+        "resource_type": entry.resource_type,
+        "error": entry.error,
+    })
+}
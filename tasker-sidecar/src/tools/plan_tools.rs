@@ -0,0 +1,86 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::registry::{Tool, ToolContext, ToolDefinition, ToolResult};
+use crate::runs::PlanStepStatus;
+
+// ============================================================================
+// Update Plan Tool
+// ============================================================================
+
+/// Mark progress on the run's numbered plan
+pub struct UpdatePlanTool;
+
+#[async_trait]
+impl Tool for UpdatePlanTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "update_plan".to_string(),
+            description: "Update the status of a step of your plan. Mark a step in_progress when you start it and done when it is finished; mark it failed when it can't be done this way (the run may then be re-planned) or skipped when it turned out to be unnecessary.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "step": {
+                        "type": "integer",
+                        "description": "Number of the plan step"
+                    },
+                    "status": {
+                        "type": "string",
+                        "enum": ["in_progress", "done", "failed", "skipped"],
+                        "description": "New status of the step"
+                    },
+                    "note": {
+                        "type": "string",
+                        "description": "Optional short note, e.g. what was found or why it failed"
+                    }
+                },
+                "required": ["step", "status"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let Some(number) = params["step"].as_u64() else {
+            return Ok(ToolResult::error("Missing 'step' parameter"));
+        };
+        let Some(status) = params["status"].as_str().and_then(|s| s.parse::<PlanStepStatus>().ok()) else {
+            return Ok(ToolResult::error(
+                "'status' must be one of in_progress, done, failed, skipped",
+            ));
+        };
+        let note = params["note"]
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
+        let Some(tracker) = &ctx.plan else {
+            return Ok(ToolResult::error("This run has no plan"));
+        };
+
+        let plan = match tracker.update_step(number as usize, status, note).await {
+            Ok(plan) => plan,
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
+        };
+
+        let mut content = format!(
+            "Step {} marked {}. Plan progress: {}/{} steps done.",
+            number,
+            status.as_str(),
+            plan.completed(),
+            plan.steps.len()
+        );
+        match plan.current() {
+            Some(next) if next.number as u64 != number => {
+                content.push_str(&format!(" Next: {}. {}", next.number, next.description));
+            }
+            Some(_) => {}
+            None => content.push_str(" Every step is done."),
+        }
+        Ok(ToolResult::success_with_data(
+            content,
+            serde_json::to_value(&plan).unwrap_or_default(),
+        ))
+    }
+}
@@ -1,13 +1,19 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use genai::chat::{ChatMessage, ChatRequest};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 use crate::browser::{BrowserManager, SelectorMap};
-use crate::runs::RunRepository;
+use crate::models::JsPolicy;
+use crate::runs::{HumanChannel, PlanTracker, Redactor, RunRepository, SubWorkflows};
+
+/// Time limit for an LLM call made by a tool
+const TOOL_LLM_TIMEOUT: Duration = Duration::from_secs(120);
 
 /// A memory/note stored during a run
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,6 +113,43 @@ pub struct ToolContext {
     pub file_repository: Option<Arc<RunRepository>>,
     /// In-memory storage for notes/memories during this run
     pub memories: Arc<RwLock<Vec<Memory>>>,
+    /// The run's model, for tools that make their own LLM call
+    pub llm: Option<ToolLlm>,
+    /// What execute_javascript may do in this run
+    pub js_policy: JsPolicy,
+    /// Reaches the person running the workflow (None = nobody to ask, e.g. API-only runs)
+    pub human: Option<HumanChannel>,
+    /// Runs other saved workflows in this browser (None outside the agent loop)
+    pub sub_workflows: Option<SubWorkflows>,
+    /// The run's plan (None when the run was started without planning)
+    pub plan: Option<PlanTracker>,
+}
+
+/// The run's model client, shared with tools (e.g. structured extraction)
+#[derive(Clone)]
+pub struct ToolLlm {
+    pub client: genai::Client,
+    pub model: String,
+    /// Page content sent to the model is masked like the agent's own prompts
+    pub redactor: Redactor,
+}
+
+impl ToolLlm {
+    /// Single-turn completion, returns the reply text
+    pub async fn complete(&self, system: &str, user: &str) -> Result<String> {
+        let request = ChatRequest::new(vec![
+            ChatMessage::system(system),
+            ChatMessage::user(self.redactor.redact_str(user)),
+        ]);
+        let response = tokio::time::timeout(TOOL_LLM_TIMEOUT, self.client.exec_chat(&self.model, request, None))
+            .await
+            .map_err(|_| anyhow!("LLM request timeout after {}s", TOOL_LLM_TIMEOUT.as_secs()))?
+            .map_err(|e| anyhow!("LLM request failed: {}", e))?;
+        response
+            .first_text()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("No text in LLM response"))
+    }
 }
 
 /// Trait for implementing tools
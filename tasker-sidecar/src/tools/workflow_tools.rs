@@ -0,0 +1,137 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::registry::{Tool, ToolContext, ToolDefinition, ToolResult};
+use crate::runs::{RunStatus, SubWorkflowOutcome};
+
+// ============================================================================
+// Run Workflow Tool
+// ============================================================================
+
+/// Run another saved workflow as a step of this one, in the same browser
+pub struct RunWorkflowTool;
+
+#[async_trait]
+impl Tool for RunWorkflowTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "run_workflow".to_string(),
+            description: "Run another saved workflow (e.g. 'Login to portal') in this browser and wait for it to finish. Fields of a JSON object it returns become variables of this run, usable as {{name}}.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "workflow": {
+                        "type": "string",
+                        "description": "Name or ID of the saved workflow"
+                    },
+                    "variables": {
+                        "type": "object",
+                        "description": "Values for the workflow's variables, e.g. {\"account\": \"acme\"}"
+                    },
+                    "share_memories": {
+                        "type": "boolean",
+                        "description": "Let the workflow read and add to this run's memories",
+                        "default": false
+                    }
+                },
+                "required": ["workflow"]
+            }),
+        }
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let workflow = match params["workflow"].as_str().map(str::trim) {
+            Some(w) if !w.is_empty() => w,
+            _ => return Ok(ToolResult::error("Missing 'workflow' parameter")),
+        };
+        let variables = params["variables"].as_object().cloned().unwrap_or_default();
+        let share_memories = params["share_memories"].as_bool().unwrap_or(false);
+
+        let Some(sub_workflows) = &ctx.sub_workflows else {
+            return Ok(ToolResult::error("Workflows can't be run from here"));
+        };
+
+        match sub_workflows.run(workflow, variables, share_memories).await {
+            Ok(outcome) => Ok(outcome_result(outcome)),
+            Err(e) => Ok(ToolResult::error(e.to_string())),
+        }
+    }
+}
+
+/// Tool result for a finished sub-workflow: its result and the variables it handed back
+fn outcome_result(outcome: SubWorkflowOutcome) -> ToolResult {
+    let data = json!({
+        "run_id": outcome.run_id,
+        "status": outcome.status.as_str(),
+        "result": outcome.result,
+        "output": outcome.output,
+    });
+    match outcome.status {
+        RunStatus::Completed => {
+            let mut content = format!(
+                "Workflow '{}' completed: {}",
+                outcome.workflow_name,
+                outcome.result.as_deref().unwrap_or("(no result)")
+            );
+            if !outcome.output.is_empty() {
+                let names: Vec<String> = outcome.output.keys().map(|k| format!("{{{{{}}}}}", k)).collect();
+                content.push_str(&format!("\nNow available as variables: {}", names.join(", ")));
+            }
+            ToolResult::success_with_data(content, data)
+        }
+        status => ToolResult {
+            success: false,
+            content: None,
+            error: Some(format!(
+                "Workflow '{}' {}: {}",
+                outcome.workflow_name,
+                status.as_str(),
+                outcome.error.as_deref().unwrap_or("no details")
+            )),
+            data: Some(data),
+            is_done: false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(status: RunStatus, result: Option<&str>, output: Value) -> SubWorkflowOutcome {
+        SubWorkflowOutcome {
+            run_id: "run-2".to_string(),
+            workflow_name: "Login to portal".to_string(),
+            status,
+            result: result.map(String::from),
+            error: (status != RunStatus::Completed).then(|| "Login button not found".to_string()),
+            output: output.as_object().cloned().unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn test_completed_outcome_lists_output_variables() {
+        let result = outcome_result(outcome(
+            RunStatus::Completed,
+            Some("{\"account_id\": \"A-17\"}"),
+            json!({ "account_id": "A-17" }),
+        ));
+        assert!(result.success);
+        let content = result.content.unwrap();
+        assert!(content.starts_with("Workflow 'Login to portal' completed"));
+        assert!(content.ends_with("Now available as variables: {{account_id}}"), "{}", content);
+        assert_eq!(result.data.unwrap()["output"]["account_id"], "A-17");
+    }
+
+    #[test]
+    fn test_failed_outcome_is_an_error() {
+        let result = outcome_result(outcome(RunStatus::Failed, None, Value::Null));
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Workflow 'Login to portal' failed: Login button not found")
+        );
+        assert_eq!(result.data.unwrap()["status"], "failed");
+    }
+}
@@ -0,0 +1,64 @@
+//! Integration tests for driving an already running Chrome over its DevTools endpoint.
+//!
+//! These tests start their own headless Chrome to stand in for the user's browser,
+//! connect a BrowserManager to it and check what close() leaves behind. They are
+//! skipped when Chrome is not installed.
+//!
+//! Run with: cargo test --test connect_integration -- --test-threads=1
+
+use chromiumoxide::{Browser, BrowserConfig};
+use futures_util::StreamExt;
+
+use tasker_sidecar::browser::BrowserManager;
+
+/// Start a headless Chrome the test doesn't hand to the manager (None = Chrome missing)
+async fn users_chrome() -> Option<Browser> {
+    let config = BrowserConfig::builder().build().ok()?;
+    let (browser, mut handler) = match Browser::launch(config).await {
+        Ok(launched) => launched,
+        Err(e) => {
+            eprintln!("Skipping: Chrome is not available ({})", e);
+            return None;
+        }
+    };
+    tokio::spawn(async move { while handler.next().await.is_some() {} });
+    Some(browser)
+}
+
+/// Ids of the open tabs, sorted
+async fn tab_ids(browser: &mut Browser) -> Vec<String> {
+    let mut ids: Vec<String> = browser
+        .fetch_targets()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|t| t.r#type == "page")
+        .map(|t| t.target_id.inner().clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_close_leaves_connected_chrome_running() {
+    let Some(mut chrome) = users_chrome().await else {
+        return;
+    };
+    let users_tabs = tab_ids(&mut chrome).await;
+
+    let browser = BrowserManager::new();
+    browser.set_cdp_url(Some(chrome.websocket_address().clone())).await;
+    browser.launch("about:blank", true, None).await.unwrap();
+    browser.new_tab("about:blank").await.unwrap();
+    assert!(!browser.owns_browser());
+    assert_eq!(tab_ids(&mut chrome).await.len(), users_tabs.len() + 2);
+
+    browser.close().await.unwrap();
+
+    // Only the tabs this session opened are gone - Chrome and the user's tabs are still there
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert_eq!(tab_ids(&mut chrome).await, users_tabs);
+    assert!(chrome.version().await.is_ok());
+
+    chrome.close().await.unwrap();
+}
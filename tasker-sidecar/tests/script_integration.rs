@@ -0,0 +1,167 @@
+//! Integration tests for execute_javascript's script runner.
+//!
+//! These tests launch a real headless Chrome and check the read-only guard
+//! (DOM writes, network and navigation are refused), timeouts and full mode.
+//!
+//! Run with: cargo test --test script_integration -- --test-threads=1
+
+use std::time::Duration;
+
+use tasker_sidecar::browser::BrowserManager;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Get file:// URL for the test page
+fn test_page_url() -> String {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    format!("file://{}/tests/fixtures/test_page.html", manifest_dir)
+}
+
+async fn launch() -> BrowserManager {
+    let browser = BrowserManager::new();
+    browser.launch(&test_page_url(), true, None).await.unwrap();
+    browser
+}
+
+/// Run a read-only script and return its error message
+async fn read_only_error(browser: &BrowserManager, script: &str) -> String {
+    match browser.run_script(script, true, TIMEOUT).await {
+        Ok(value) => panic!("Script should have been refused, returned {}", value),
+        Err(e) => e.to_string(),
+    }
+}
+
+// ============================================================================
+// Read-only guard
+// ============================================================================
+
+#[tokio::test]
+async fn test_read_only_reads_the_dom() {
+    let browser = launch().await;
+
+    let title = browser.run_script("document.title", true, TIMEOUT).await.unwrap();
+    assert_eq!(title, "Tasker Recording Test");
+
+    let text = browser
+        .run_script("return document.querySelector('#btn-simple').textContent;", true, TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(text, "Simple Button");
+
+    // The guarded world is reused: globals a script defines are still there next time
+    browser.run_script("window.found = 3", true, TIMEOUT).await.unwrap();
+    assert_eq!(browser.run_script("found", true, TIMEOUT).await.unwrap(), 3);
+
+    browser.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_blocks_dom_writes() {
+    let browser = launch().await;
+
+    for script in [
+        "document.body.appendChild(document.createElement('div'))",
+        "document.querySelector('#btn-simple').textContent = 'changed'",
+        "document.querySelector('#btn-simple').click()",
+        "document.title = 'changed'",
+    ] {
+        let error = read_only_error(&browser, script).await;
+        assert!(error.contains("read-only"), "{}: {}", script, error);
+    }
+
+    // Nothing reached the page
+    let text = browser
+        .run_script("document.querySelector('#btn-simple').textContent", false, TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(text, "Simple Button");
+    assert_eq!(browser.run_script("document.title", false, TIMEOUT).await.unwrap(), "Tasker Recording Test");
+
+    browser.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_blocks_network_and_storage() {
+    let browser = launch().await;
+
+    for script in [
+        "fetch('https://example.com')",
+        "new XMLHttpRequest()",
+        "navigator.sendBeacon('https://example.com', 'x')",
+        "document.cookie",
+        "localStorage.getItem('x')",
+        "import('https://example.com/x.js')",
+    ] {
+        let error = read_only_error(&browser, script).await;
+        assert!(error.contains("not allowed"), "{}: {}", script, error);
+    }
+
+    browser.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_blocks_navigation() {
+    let browser = launch().await;
+    let url = test_page_url();
+
+    // Refused by the guard's location stand-in
+    let error = read_only_error(&browser, "location.href = 'https://example.com'").await;
+    assert!(error.contains("Navigation"), "{}", error);
+
+    // The Navigation API reaches the real frame - the request is blocked over CDP instead
+    let target = format!("{}?moved", url);
+    let error = read_only_error(&browser, &format!("navigation.navigate('{}')", target)).await;
+    assert!(error.contains("not allowed"), "{}", error);
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(browser.current_url().await.unwrap(), url);
+
+    // Later scripts still run normally
+    assert_eq!(browser.run_script("1 + 1", true, TIMEOUT).await.unwrap(), 2);
+
+    browser.close().await.unwrap();
+}
+
+// ============================================================================
+// Timeouts and full mode
+// ============================================================================
+
+#[tokio::test]
+async fn test_script_timeout() {
+    let browser = launch().await;
+    let short = Duration::from_millis(300);
+
+    for read_only in [false, true] {
+        assert!(browser.run_script("while (true) {}", read_only, short).await.is_err());
+        let error = browser
+            .run_script("new Promise(() => {})", read_only, short)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("timed out"), "{}", error);
+    }
+
+    // The page is still usable afterwards
+    assert_eq!(browser.run_script("1 + 1", false, TIMEOUT).await.unwrap(), 2);
+
+    browser.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_full_mode_calls_functions_once() {
+    let browser = launch().await;
+
+    let value = browser
+        .run_script("window.calls = (window.calls || 0) + 1; () => window.calls", false, TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(value, 1);
+
+    let value = browser
+        .run_script("async () => document.title", false, TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(value, "Tasker Recording Test");
+
+    browser.close().await.unwrap();
+}